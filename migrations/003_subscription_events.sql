@@ -0,0 +1,43 @@
+ALTER TABLE subscriptions ADD COLUMN updated_at TIMESTAMP;
+
+CREATE TABLE IF NOT EXISTS subscription_events (
+    subscription_id TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    tenant_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    occurred_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (subscription_id, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_events_tenant_id ON subscription_events(tenant_id, occurred_at);
+
+CREATE TRIGGER IF NOT EXISTS subscription_events_no_update
+BEFORE UPDATE ON subscription_events
+BEGIN
+    SELECT RAISE(ABORT, 'subscription_events is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS subscription_events_no_delete
+BEFORE DELETE ON subscription_events
+BEGIN
+    SELECT RAISE(ABORT, 'subscription_events is append-only');
+END;
+
+CREATE TABLE IF NOT EXISTS subscription_snapshots (
+    subscription_id TEXT PRIMARY KEY,
+    version INTEGER NOT NULL,
+    state TEXT NOT NULL,
+    taken_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS subscription_projection (
+    id TEXT PRIMARY KEY,
+    tenant_id TEXT NOT NULL,
+    plan_id TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_projection_tenant_id ON subscription_projection(tenant_id);
@@ -0,0 +1,31 @@
+-- SQLite cannot alter a CHECK constraint, so the table is rebuilt with the
+-- RBAC role names. Existing keys keep their access: `tenant` keys become
+-- `tenant_admin` and `admin` keys become `platform_admin`.
+CREATE TABLE api_keys_rbac (
+    id TEXT PRIMARY KEY,
+    key_hash TEXT NOT NULL UNIQUE,
+    tenant_id TEXT,
+    role TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP,
+    CHECK (
+        (role IN ('tenant_admin', 'tenant_viewer') AND tenant_id IS NOT NULL)
+        OR (role IN ('billing_ops', 'platform_admin') AND tenant_id IS NULL)
+    )
+);
+
+INSERT INTO api_keys_rbac (id, key_hash, tenant_id, role, created_at, revoked_at)
+SELECT
+    id,
+    key_hash,
+    tenant_id,
+    CASE role WHEN 'tenant' THEN 'tenant_admin' ELSE 'platform_admin' END,
+    created_at,
+    revoked_at
+FROM api_keys;
+
+DROP TABLE api_keys;
+
+ALTER TABLE api_keys_rbac RENAME TO api_keys;
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_tenant_id ON api_keys(tenant_id);
@@ -0,0 +1,6 @@
+-- In-progress keys whose lease has passed can be claimed again, so a request
+-- that never finished does not block retries until the key expires.
+ALTER TABLE idempotency_keys ADD COLUMN locked_until TIMESTAMP;
+
+-- JSON array of [name, value] pairs, replayed with the stored body.
+ALTER TABLE idempotency_keys ADD COLUMN response_headers TEXT;
@@ -0,0 +1,11 @@
+-- Business events waiting to be copied to the chained log file. A row is
+-- written in the transaction that makes the change it describes, and deleted
+-- once the relay has appended it. AUTOINCREMENT keeps ids from being reused
+-- after a delete, since the log records the last id it copied.
+CREATE TABLE IF NOT EXISTS business_event_outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    request_id TEXT,
+    recorded_at TIMESTAMP NOT NULL
+);
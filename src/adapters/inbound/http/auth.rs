@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::domain::AuthenticationError;
+use crate::ports::{ApiKeyRepository, TokenVerifier};
+use crate::services::AuthService;
+
+use super::errors::ApiError;
+use super::trace_context;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Clone)]
+pub struct AuthState<K, T>
+where
+    K: ApiKeyRepository,
+    T: TokenVerifier,
+{
+    pub auth_service: Arc<AuthService<K, T>>,
+}
+
+impl<K, T> AuthState<K, T>
+where
+    K: ApiKeyRepository,
+    T: TokenVerifier,
+{
+    pub fn new(auth_service: AuthService<K, T>) -> Self {
+        Self {
+            auth_service: Arc::new(auth_service),
+        }
+    }
+}
+
+enum Credentials {
+    Bearer(String),
+    ApiKey(String),
+}
+
+/// `Authorization: Bearer` takes precedence over `X-Api-Key` when both are sent.
+fn credentials(request: &Request) -> Option<Credentials> {
+    let headers = request.headers();
+
+    if let Some(authorization) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        let (scheme, token) = authorization.split_once(' ')?;
+        return scheme
+            .eq_ignore_ascii_case("bearer")
+            .then(|| Credentials::Bearer(token.trim().to_string()));
+    }
+
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|key| Credentials::ApiKey(key.to_string()))
+}
+
+/// Authenticates a bearer token or the `X-Api-Key` header and stores the resulting `Principal`
+/// in the request extensions for the `Principal` and `RequestContext`
+/// extractors. Requests without valid credentials never reach a handler.
+pub async fn authenticate<K, T>(
+    State(state): State<AuthState<K, T>>,
+    mut request: Request,
+    next: Next,
+) -> Response
+where
+    K: ApiKeyRepository + 'static,
+    T: TokenVerifier + 'static,
+{
+    let result = match credentials(&request) {
+        Some(Credentials::Bearer(token)) => {
+            state.auth_service.authenticate_bearer_token(&token).await
+        }
+        Some(Credentials::ApiKey(key)) => state.auth_service.authenticate_api_key(&key).await,
+        None => Err(AuthenticationError::MissingCredentials),
+    };
+
+    match result {
+        Ok(principal) => {
+            trace_context::attach_tenant(request.headers(), principal.tenant_id.as_ref());
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
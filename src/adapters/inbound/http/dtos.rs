@@ -1,23 +1,114 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::domain::{CreateSubscriptionRequest, PlanId, Subscription, TenantId};
+use crate::domain::{
+    ApiKey, AuditEntry, ChangeSubscriptionPlanRequest, CreateSubscriptionRequest,
+    IssueApiKeyRequest, LogFilterStatus, PlanId, Role, Subscription, SubscriptionId, TenantId,
+    ValidationError, WebhookDelivery, WebhookDeliveryAttempt, WebhookEndpoint,
+};
 
-#[derive(Debug, Deserialize)]
+use crate::ports::ProbeStatus;
+use crate::services::ReadinessReport;
+
+use super::errors::ApiError;
+use super::problem::FieldError;
+
+/// Collects field errors while a request is converted into its domain form,
+/// so the caller sees every invalid field in one response.
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn check<T>(&mut self, field: &str, result: Result<T, ValidationError>) -> Option<T> {
+        result
+            .map_err(|e| self.0.push(FieldError::new(field, e.to_string())))
+            .ok()
+    }
+
+    pub fn push(&mut self, field: &str, detail: impl Into<String>) {
+        self.0.push(FieldError::new(field, detail));
+    }
+}
+
+impl From<FieldErrors> for ApiError {
+    fn from(errors: FieldErrors) -> Self {
+        ApiError::validation(errors.0)
+    }
+}
+
+/// Validates a single value, typically a path parameter.
+pub fn parse_field<T>(field: &str, result: Result<T, ValidationError>) -> Result<T, FieldErrors> {
+    let mut errors = FieldErrors::default();
+    errors.check(field, result).ok_or(errors)
+}
+
+// String fields default to empty so a missing field is reported alongside the
+// others instead of failing deserialization on the first one.
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateSubscriptionHttpBody {
+    #[serde(default)]
+    #[schema(example = "tenant_with_payment")]
     pub tenant_id: String,
+    #[serde(default)]
+    #[schema(example = "pro")]
     pub plan_id: String,
 }
 
-impl From<CreateSubscriptionHttpBody> for CreateSubscriptionRequest {
-    fn from(body: CreateSubscriptionHttpBody) -> Self {
-        Self {
-            tenant_id: TenantId::new(body.tenant_id),
-            plan_id: PlanId::new(body.plan_id),
+impl TryFrom<CreateSubscriptionHttpBody> for CreateSubscriptionRequest {
+    type Error = FieldErrors;
+
+    fn try_from(body: CreateSubscriptionHttpBody) -> Result<Self, Self::Error> {
+        let mut errors = FieldErrors::default();
+        let tenant_id = errors.check("tenant_id", TenantId::parse(body.tenant_id));
+        let plan_id = errors.check("plan_id", PlanId::parse(body.plan_id));
+
+        match (tenant_id, plan_id) {
+            (Some(tenant_id), Some(plan_id)) => Ok(Self { tenant_id, plan_id }),
+            _ => Err(errors),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangeSubscriptionPlanHttpBody {
+    #[serde(default)]
+    #[schema(example = "enterprise")]
+    pub plan_id: String,
+}
+
+impl TryFrom<(String, ChangeSubscriptionPlanHttpBody)> for ChangeSubscriptionPlanRequest {
+    type Error = FieldErrors;
+
+    fn try_from(
+        (subscription_id, body): (String, ChangeSubscriptionPlanHttpBody),
+    ) -> Result<Self, Self::Error> {
+        let mut errors = FieldErrors::default();
+        let subscription_id =
+            errors.check("subscription_id", SubscriptionId::parse(subscription_id));
+        let plan_id = errors.check("plan_id", PlanId::parse(body.plan_id));
+
+        match (subscription_id, plan_id) {
+            (Some(subscription_id), Some(plan_id)) => Ok(Self {
+                subscription_id,
+                plan_id,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListSubscriptionsQuery {
+    /// Point in time to list subscriptions at (event-sourced store only).
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SubscriptionResponse {
     pub id: String,
     pub tenant_id: String,
@@ -35,3 +126,303 @@ impl From<Subscription> for SubscriptionResponse {
         }
     }
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditEntryResponse {
+    pub id: String,
+    pub actor: String,
+    pub action: String,
+    pub before: Option<SubscriptionResponse>,
+    pub after: Option<SubscriptionResponse>,
+    pub request_id: Option<String>,
+    pub occurred_at: String,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(e: AuditEntry) -> Self {
+        Self {
+            id: e.id,
+            actor: e.actor,
+            action: e.action.as_str().to_string(),
+            before: e.before.map(Into::into),
+            after: e.after.map(Into::into),
+            request_id: e.request_id,
+            occurred_at: e.occurred_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterWebhookHttpBody {
+    #[serde(default)]
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookEndpointResponse {
+    pub id: String,
+    pub tenant_id: String,
+    pub url: String,
+    pub secret: String,
+    pub created_at: String,
+}
+
+impl From<WebhookEndpoint> for WebhookEndpointResponse {
+    fn from(e: WebhookEndpoint) -> Self {
+        Self {
+            id: e.id.as_ref().to_string(),
+            tenant_id: e.tenant_id.as_ref().to_string(),
+            url: e.url,
+            secret: e.secret,
+            created_at: e.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveryAttemptResponse {
+    pub attempt: u32,
+    pub response_code: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub attempted_at: String,
+}
+
+impl From<WebhookDeliveryAttempt> for WebhookDeliveryAttemptResponse {
+    fn from(a: WebhookDeliveryAttempt) -> Self {
+        Self {
+            attempt: a.attempt,
+            response_code: a.response_code,
+            error: a.error,
+            duration_ms: a.duration_ms,
+            attempted_at: a.attempted_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    pub endpoint_id: String,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: u32,
+    pub last_response_code: Option<u16>,
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub log: Vec<WebhookDeliveryAttemptResponse>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(d: WebhookDelivery) -> Self {
+        Self {
+            id: d.id.as_ref().to_string(),
+            endpoint_id: d.endpoint_id.as_ref().to_string(),
+            event_type: d.event_type,
+            status: d.status.as_str().to_string(),
+            attempts: d.attempts,
+            last_response_code: d.last_response_code,
+            next_attempt_at: d.next_attempt_at.map(|t| t.to_rfc3339()),
+            created_at: d.created_at.to_rfc3339(),
+            log: Vec::new(),
+        }
+    }
+}
+
+impl From<(WebhookDelivery, Vec<WebhookDeliveryAttempt>)> for WebhookDeliveryResponse {
+    fn from((delivery, attempts): (WebhookDelivery, Vec<WebhookDeliveryAttempt>)) -> Self {
+        Self {
+            log: attempts.into_iter().map(Into::into).collect(),
+            ..Self::from(delivery)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IssueApiKeyHttpBody {
+    /// Required for tenant roles, absent for staff roles.
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    #[schema(example = "tenant_admin")]
+    pub role: String,
+}
+
+impl TryFrom<IssueApiKeyHttpBody> for IssueApiKeyRequest {
+    type Error = FieldErrors;
+
+    fn try_from(body: IssueApiKeyHttpBody) -> Result<Self, Self::Error> {
+        let mut errors = FieldErrors::default();
+        let tenant_id = match body.tenant_id {
+            Some(tenant_id) => errors
+                .check("tenant_id", TenantId::parse(tenant_id))
+                .map(Some),
+            None => Some(None),
+        };
+        let role = Role::parse(&body.role);
+        if role.is_none() {
+            errors.push(
+                "role",
+                "must be one of tenant_admin, tenant_viewer, billing_ops, platform_admin",
+            );
+        }
+
+        match (tenant_id, role) {
+            (Some(tenant_id), Some(role)) => Ok(Self { tenant_id, role }),
+            _ => Err(errors),
+        }
+    }
+}
+
+/// The only response that ever contains the plaintext key.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssuedApiKeyResponse {
+    pub id: String,
+    pub key: String,
+    pub tenant_id: Option<String>,
+    #[schema(value_type = String, example = "tenant_admin")]
+    pub role: Role,
+    pub created_at: String,
+}
+
+impl From<(ApiKey, String)> for IssuedApiKeyResponse {
+    fn from((api_key, key): (ApiKey, String)) -> Self {
+        Self {
+            id: api_key.id,
+            key,
+            tenant_id: api_key.tenant_id.map(|t| t.as_ref().to_string()),
+            role: api_key.role,
+            created_at: api_key.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetLogFilterHttpBody {
+    /// `RUST_LOG` syntax.
+    #[serde(default)]
+    #[schema(example = "hexagonal_rust=debug,tower_http=info")]
+    pub directives: String,
+    /// Go back to the configured filter after this many seconds (at most a
+    /// day). Required when the directives enable a more verbose level than
+    /// the configured filter.
+    pub revert_after_secs: Option<u64>,
+}
+
+/// Longest temporary change. Verbose filters must revert (see
+/// `LogFilterHandle::set`), so they are never left on for longer.
+const MAX_LOG_FILTER_REVERT_SECS: u64 = 86_400;
+
+impl SetLogFilterHttpBody {
+    /// Checks the body and returns the revert delay, if any. The directives
+    /// themselves are parsed when they are installed.
+    pub fn validate(&self) -> Result<Option<Duration>, FieldErrors> {
+        let mut errors = FieldErrors::default();
+        if self.directives.trim().is_empty() {
+            errors.push("directives", "must not be empty");
+        }
+        if matches!(self.revert_after_secs, Some(secs) if secs == 0 || secs > MAX_LOG_FILTER_REVERT_SECS)
+        {
+            errors.push(
+                "revert_after_secs",
+                format!("must be between 1 and {}", MAX_LOG_FILTER_REVERT_SECS),
+            );
+        }
+
+        if errors.0.is_empty() {
+            Ok(self.revert_after_secs.map(Duration::from_secs))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LogFilterResponse {
+    pub directives: String,
+    /// The configured `RUST_LOG`, restored on revert.
+    pub default_directives: String,
+    /// When a temporary filter reverts, if one is set.
+    pub revert_at: Option<String>,
+}
+
+impl From<LogFilterStatus> for LogFilterResponse {
+    fn from(status: LogFilterStatus) -> Self {
+        Self {
+            directives: status.directives,
+            default_directives: status.default_directives,
+            revert_at: status.revert_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthCheckResponse {
+    #[schema(example = "up")]
+    pub status: String,
+    pub critical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(flatten)]
+    pub details: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    #[schema(example = "ready")]
+    pub status: String,
+    pub checks: BTreeMap<String, HealthCheckResponse>,
+}
+
+impl From<ReadinessReport> for ReadinessResponse {
+    fn from(report: ReadinessReport) -> Self {
+        Self {
+            status: if report.draining {
+                "draining"
+            } else if report.ready {
+                "ready"
+            } else {
+                "not_ready"
+            }
+            .to_string(),
+            checks: report
+                .checks
+                .into_iter()
+                .map(|(name, check)| {
+                    let status = match check.report.status {
+                        ProbeStatus::Up => "up",
+                        ProbeStatus::Down => "down",
+                        ProbeStatus::Disabled => "disabled",
+                    };
+                    (
+                        name.to_string(),
+                        HealthCheckResponse {
+                            status: status.to_string(),
+                            critical: check.critical,
+                            latency_ms: check.report.latency_ms,
+                            details: check.report.details,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_subscription_body_reports_every_invalid_field() {
+        let body: CreateSubscriptionHttpBody =
+            serde_json::from_str(r#"{"tenant_id": "Acme Corp"}"#).unwrap();
+
+        let errors = CreateSubscriptionRequest::try_from(body).unwrap_err();
+        let problem = ApiError::from(errors).problem();
+
+        assert_eq!(problem.status, 422);
+        let fields: Vec<&str> = problem.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["tenant_id", "plan_id"]);
+    }
+}
@@ -1,24 +1,243 @@
-use axum::{http::StatusCode, response::IntoResponse, Json};
-use opentelemetry::trace::Status;
-use serde::Serialize;
-use std::collections::HashMap;
+use axum::{
+    extract::rejection::{JsonRejection, QueryRejection},
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+};
+use opentelemetry::KeyValue;
+use std::collections::{BTreeMap, HashMap};
 use tracing::{error, warn, Span};
 
-use crate::domain::CreateSubscriptionError;
+use crate::domain::{
+    AccessDenied, AuthenticationError, ChangeSubscriptionPlanError, CreateSubscriptionError,
+    IssueApiKeyError, ListSubscriptionsError, LogFilterError, RegisterWebhookError,
+    SubscriptionHistoryError, WebhookDeliveryError,
+};
+use crate::metrics::instruments;
 
-#[derive(Debug, Serialize)]
+use super::problem::{
+    type_slug, type_title, FieldError, ProblemDetails, PROBLEM_CONTENT_TYPE, PROBLEM_TYPE_BASE,
+};
+
+/// Rendered as an RFC 9457 `application/problem+json` document: `error_type`
+/// selects the `type` URI and title, `message` becomes `detail`, and the
+/// attributes become extension members.
+#[derive(Debug)]
 pub struct ApiError {
     pub message: String,
     pub code: u16,
-    #[serde(skip)]
     pub error_type: Option<String>,
-    #[serde(skip)]
     pub error_attributes: HashMap<String, String>,
+    pub field_errors: Vec<FieldError>,
+}
+
+impl ApiError {
+    /// Every invalid field of a request, reported together as a single 422.
+    pub fn validation(field_errors: Vec<FieldError>) -> Self {
+        warn!(fields = ?field_errors, "request failed validation");
+        ApiError {
+            message: format!("{} field(s) failed validation", field_errors.len()),
+            code: 422,
+            error_type: Some("ValidationFailed".to_string()),
+            error_attributes: HashMap::new(),
+            field_errors,
+        }
+    }
+
+    pub fn problem(&self) -> ProblemDetails {
+        let (type_uri, title) = match &self.error_type {
+            Some(error_type) => (
+                format!("{}{}", PROBLEM_TYPE_BASE, type_slug(error_type)),
+                type_title(error_type),
+            ),
+            None => (
+                "about:blank".to_string(),
+                StatusCode::from_u16(self.code)
+                    .ok()
+                    .and_then(|s| s.canonical_reason())
+                    .unwrap_or("Error")
+                    .to_string(),
+            ),
+        };
+
+        ProblemDetails {
+            type_uri,
+            title,
+            status: self.code,
+            detail: self.message.clone(),
+            instance: None,
+            errors: self.field_errors.clone(),
+            extensions: self
+                .error_attributes
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        warn!(error = %rejection, "request body rejected");
+        match rejection {
+            JsonRejection::JsonDataError(e) => {
+                // axum reports the failing path as `field: reason`.
+                let text = e.body_text();
+                let text = text.trim_start_matches(
+                    "Failed to deserialize the JSON body into the target type: ",
+                );
+                let field_error = match text.split_once(": ") {
+                    Some((field, detail)) if !field.contains(' ') && field != "." => {
+                        FieldError::new(field, detail)
+                    }
+                    _ => FieldError::new("body", text),
+                };
+                ApiError::validation(vec![field_error])
+            }
+            JsonRejection::MissingJsonContentType(_) => ApiError {
+                message: "Request body must be sent as application/json".into(),
+                code: 415,
+                error_type: Some("UnsupportedMediaType".to_string()),
+                error_attributes: HashMap::new(),
+                field_errors: Vec::new(),
+            },
+            other => ApiError {
+                message: other.body_text(),
+                code: 400,
+                error_type: Some("MalformedBody".to_string()),
+                error_attributes: HashMap::new(),
+                field_errors: Vec::new(),
+            },
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::validation(vec![FieldError::new(
+            "query",
+            rejection
+                .body_text()
+                .trim_start_matches("Failed to deserialize query string: ")
+                .to_string(),
+        )])
+    }
+}
+
+impl From<AccessDenied> for ApiError {
+    fn from(e: AccessDenied) -> Self {
+        let mut attrs = HashMap::new();
+        attrs.insert("permission".to_string(), e.permission.as_str().to_string());
+        if let Some(tenant_id) = &e.tenant_id {
+            attrs.insert("tenant_id".to_string(), tenant_id.to_string());
+        }
+        ApiError {
+            message: format!("Caller lacks permission {}", e.permission),
+            code: 403,
+            error_type: Some("PermissionDenied".to_string()),
+            error_attributes: attrs,
+            field_errors: Vec::new(),
+        }
+    }
+}
+
+impl From<LogFilterError> for ApiError {
+    fn from(e: LogFilterError) -> Self {
+        match &e {
+            LogFilterError::PermissionDenied(denied) => denied.clone().into(),
+            LogFilterError::Invalid(reason) => {
+                ApiError::validation(vec![FieldError::new("directives", reason.clone())])
+            }
+            LogFilterError::RevertRequired => ApiError::validation(vec![FieldError::new(
+                "revert_after_secs",
+                "is required for directives more verbose than the configured filter",
+            )]),
+            LogFilterError::Reload(_) => {
+                error!(error = %e, "log filter change failed");
+                ApiError {
+                    message: "Log filter is unavailable".into(),
+                    code: 503,
+                    error_type: Some("LogFilterUnavailable".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl From<AuthenticationError> for ApiError {
+    fn from(e: AuthenticationError) -> Self {
+        match &e {
+            AuthenticationError::MissingCredentials | AuthenticationError::InvalidCredentials => {
+                warn!(error = %e, "request not authenticated");
+                ApiError {
+                    message: "Missing or invalid credentials".into(),
+                    code: 401,
+                    error_type: Some("Unauthenticated".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+            AuthenticationError::Unexpected(source) => {
+                error!(
+                    error = %source,
+                    "unexpected error during authentication"
+                );
+                ApiError {
+                    message: "Internal server error".into(),
+                    code: 500,
+                    error_type: Some("Unexpected".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl From<IssueApiKeyError> for ApiError {
+    fn from(e: IssueApiKeyError) -> Self {
+        match &e {
+            IssueApiKeyError::PermissionDenied(denied) => denied.clone().into(),
+            IssueApiKeyError::InvalidTenantBinding => {
+                warn!(error = %e, "invalid api key request");
+                ApiError {
+                    message: "Tenant roles require a tenant_id and staff roles must not have one"
+                        .into(),
+                    code: 422,
+                    error_type: Some("InvalidTenantBinding".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: vec![FieldError::new(
+                        "tenant_id",
+                        "required for tenant roles and not allowed for staff roles",
+                    )],
+                }
+            }
+            IssueApiKeyError::Unexpected(source) => {
+                error!(
+                    error = %source,
+                    "unexpected error while issuing api key"
+                );
+                ApiError {
+                    message: "Internal server error".into(),
+                    code: 500,
+                    error_type: Some("Unexpected".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
 }
 
 impl From<CreateSubscriptionError> for ApiError {
     fn from(e: CreateSubscriptionError) -> Self {
+        instruments()
+            .subscription_create_errors
+            .add(1, &[KeyValue::new("error", e.kind())]);
+
         match &e {
+            CreateSubscriptionError::PermissionDenied(denied) => denied.clone().into(),
             CreateSubscriptionError::PlanNotFound(plan_id) => {
                 warn!(
                     error = %e,
@@ -32,6 +251,7 @@ impl From<CreateSubscriptionError> for ApiError {
                     code: 404,
                     error_type: Some("PlanNotFound".to_string()),
                     error_attributes: attrs,
+                    field_errors: Vec::new(),
                 }
             }
             CreateSubscriptionError::PlanNotAllowed(tenant_id, plan_id) => {
@@ -49,6 +269,7 @@ impl From<CreateSubscriptionError> for ApiError {
                     code: 403,
                     error_type: Some("PlanNotAllowed".to_string()),
                     error_attributes: attrs,
+                    field_errors: Vec::new(),
                 }
             }
             CreateSubscriptionError::MissingPaymentMethod(tenant_id) => {
@@ -64,6 +285,7 @@ impl From<CreateSubscriptionError> for ApiError {
                     code: 422,
                     error_type: Some("MissingPaymentMethod".to_string()),
                     error_attributes: attrs,
+                    field_errors: Vec::new(),
                 }
             }
             CreateSubscriptionError::Unexpected(source) => {
@@ -76,6 +298,290 @@ impl From<CreateSubscriptionError> for ApiError {
                     code: 500,
                     error_type: Some("Unexpected".to_string()),
                     error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl From<ChangeSubscriptionPlanError> for ApiError {
+    fn from(e: ChangeSubscriptionPlanError) -> Self {
+        match &e {
+            ChangeSubscriptionPlanError::PermissionDenied(denied) => denied.clone().into(),
+            ChangeSubscriptionPlanError::SubscriptionNotFound(subscription_id) => {
+                warn!(
+                    error = %e,
+                    subscription_id = %subscription_id,
+                    "subscription not found"
+                );
+                let mut attrs = HashMap::new();
+                attrs.insert("subscription_id".to_string(), subscription_id.to_string());
+                ApiError {
+                    message: format!("Subscription {} not found", subscription_id),
+                    code: 404,
+                    error_type: Some("SubscriptionNotFound".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            ChangeSubscriptionPlanError::PlanNotFound(plan_id) => {
+                warn!(
+                    error = %e,
+                    plan_id = %plan_id,
+                    "plan not found"
+                );
+                let mut attrs = HashMap::new();
+                attrs.insert("plan_id".to_string(), plan_id.to_string());
+                ApiError {
+                    message: format!("Plan {} not found", plan_id),
+                    code: 404,
+                    error_type: Some("PlanNotFound".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            ChangeSubscriptionPlanError::PlanNotAllowed(tenant_id, plan_id) => {
+                warn!(
+                    error = %e,
+                    tenant_id = %tenant_id,
+                    plan_id = %plan_id,
+                    "tenant not allowed on plan"
+                );
+                let mut attrs = HashMap::new();
+                attrs.insert("tenant_id".to_string(), tenant_id.to_string());
+                attrs.insert("plan_id".to_string(), plan_id.to_string());
+                ApiError {
+                    message: format!("Tenant {} is not allowed on plan {}", tenant_id, plan_id),
+                    code: 403,
+                    error_type: Some("PlanNotAllowed".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            ChangeSubscriptionPlanError::MissingPaymentMethod(tenant_id) => {
+                warn!(
+                    error = %e,
+                    tenant_id = %tenant_id,
+                    "missing payment method"
+                );
+                let mut attrs = HashMap::new();
+                attrs.insert("tenant_id".to_string(), tenant_id.to_string());
+                ApiError {
+                    message: format!("Tenant {} has no active payment method on file", tenant_id),
+                    code: 422,
+                    error_type: Some("MissingPaymentMethod".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            ChangeSubscriptionPlanError::ConcurrentChange(subscription_id) => {
+                warn!(
+                    error = %e,
+                    subscription_id = %subscription_id,
+                    "subscription changed concurrently"
+                );
+                let mut attrs = HashMap::new();
+                attrs.insert("subscription_id".to_string(), subscription_id.to_string());
+                ApiError {
+                    message: format!(
+                        "Subscription {} was changed by another request; fetch it and retry",
+                        subscription_id
+                    ),
+                    code: 409,
+                    error_type: Some("SubscriptionChangedConcurrently".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            ChangeSubscriptionPlanError::Unexpected(source) => {
+                error!(
+                    error = %source,
+                    "unexpected error during subscription plan change"
+                );
+                ApiError {
+                    message: "Internal server error".into(),
+                    code: 500,
+                    error_type: Some("Unexpected".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl From<ListSubscriptionsError> for ApiError {
+    fn from(e: ListSubscriptionsError) -> Self {
+        match &e {
+            ListSubscriptionsError::PermissionDenied(denied) => denied.clone().into(),
+            ListSubscriptionsError::PointInTimeUnsupported => {
+                warn!(error = %e, "point-in-time query not supported");
+                ApiError {
+                    message: "Point-in-time queries require the event-sourced subscription store"
+                        .into(),
+                    code: 501,
+                    error_type: Some("PointInTimeUnsupported".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+            ListSubscriptionsError::Unexpected(source) => {
+                error!(
+                    error = %source,
+                    "unexpected error while listing subscriptions"
+                );
+                ApiError {
+                    message: "Internal server error".into(),
+                    code: 500,
+                    error_type: Some("Unexpected".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl From<SubscriptionHistoryError> for ApiError {
+    fn from(e: SubscriptionHistoryError) -> Self {
+        match &e {
+            SubscriptionHistoryError::PermissionDenied(denied) => denied.clone().into(),
+            SubscriptionHistoryError::SubscriptionNotFound(subscription_id) => {
+                warn!(
+                    error = %e,
+                    subscription_id = %subscription_id,
+                    "subscription not found"
+                );
+                let mut attrs = HashMap::new();
+                attrs.insert("subscription_id".to_string(), subscription_id.to_string());
+                ApiError {
+                    message: format!("Subscription {} not found", subscription_id),
+                    code: 404,
+                    error_type: Some("SubscriptionNotFound".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            SubscriptionHistoryError::Unexpected(source) => {
+                error!(
+                    error = %source,
+                    "unexpected error while reading subscription history"
+                );
+                ApiError {
+                    message: "Internal server error".into(),
+                    code: 500,
+                    error_type: Some("Unexpected".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl From<RegisterWebhookError> for ApiError {
+    fn from(e: RegisterWebhookError) -> Self {
+        match &e {
+            RegisterWebhookError::PermissionDenied(denied) => denied.clone().into(),
+            RegisterWebhookError::InvalidUrl(url) => {
+                warn!(error = %e, url = %url, "invalid webhook url");
+                let mut attrs = HashMap::new();
+                attrs.insert("webhook.url".to_string(), url.to_string());
+                ApiError {
+                    message: format!("Webhook url {} must be an absolute http(s) url", url),
+                    code: 422,
+                    error_type: Some("InvalidWebhookUrl".to_string()),
+                    error_attributes: attrs,
+                    field_errors: vec![FieldError::new("url", "must be an absolute http(s) url")],
+                }
+            }
+            RegisterWebhookError::InternalAddress(url) => {
+                warn!(error = %e, url = %url, "internal webhook url");
+                let mut attrs = HashMap::new();
+                attrs.insert("webhook.url".to_string(), url.to_string());
+                ApiError {
+                    message: format!("Webhook url {} points at an internal address", url),
+                    code: 422,
+                    error_type: Some("InternalWebhookAddress".to_string()),
+                    error_attributes: attrs,
+                    field_errors: vec![FieldError::new(
+                        "url",
+                        "must not point at a loopback, private or link-local address",
+                    )],
+                }
+            }
+            RegisterWebhookError::Unexpected(source) => {
+                error!(
+                    error = %source,
+                    "unexpected error during webhook registration"
+                );
+                ApiError {
+                    message: "Internal server error".into(),
+                    code: 500,
+                    error_type: Some("Unexpected".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl From<WebhookDeliveryError> for ApiError {
+    fn from(e: WebhookDeliveryError) -> Self {
+        match &e {
+            WebhookDeliveryError::PermissionDenied(denied) => denied.clone().into(),
+            WebhookDeliveryError::EndpointNotFound(endpoint_id) => {
+                warn!(error = %e, endpoint_id = %endpoint_id, "webhook endpoint not found");
+                let mut attrs = HashMap::new();
+                attrs.insert("webhook.endpoint_id".to_string(), endpoint_id.to_string());
+                ApiError {
+                    message: format!("Webhook endpoint {} not found", endpoint_id),
+                    code: 404,
+                    error_type: Some("WebhookEndpointNotFound".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            WebhookDeliveryError::DeliveryNotFound(delivery_id) => {
+                warn!(error = %e, delivery_id = %delivery_id, "webhook delivery not found");
+                let mut attrs = HashMap::new();
+                attrs.insert("webhook.delivery_id".to_string(), delivery_id.to_string());
+                ApiError {
+                    message: format!("Webhook delivery {} not found", delivery_id),
+                    code: 404,
+                    error_type: Some("WebhookDeliveryNotFound".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            WebhookDeliveryError::DeliveryInFlight(delivery_id) => {
+                warn!(error = %e, delivery_id = %delivery_id, "webhook delivery is being sent");
+                let mut attrs = HashMap::new();
+                attrs.insert("webhook.delivery_id".to_string(), delivery_id.to_string());
+                ApiError {
+                    message: format!(
+                        "Webhook delivery {} is being sent; replay it once the attempt is logged",
+                        delivery_id
+                    ),
+                    code: 409,
+                    error_type: Some("WebhookDeliveryInFlight".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+            }
+            WebhookDeliveryError::Unexpected(source) => {
+                error!(
+                    error = %source,
+                    "unexpected error while handling webhook deliveries"
+                );
+                ApiError {
+                    message: "Internal server error".into(),
+                    code: 500,
+                    error_type: Some("Unexpected".to_string()),
+                    error_attributes: HashMap::new(),
+                    field_errors: Vec::new(),
                 }
             }
         }
@@ -88,10 +594,12 @@ impl IntoResponse for ApiError {
 
         let current_span = Span::current();
 
-        opentelemetry::trace::get_active_span(|span| {
-            span.set_status(Status::error(self.message.clone()));
-        });
-
+        // Server errors mark the span's status as an error, which rule-based
+        // sampling keeps regardless of the sampling ratio. Client errors
+        // leave it unset, as HTTP semantic conventions ask for server spans.
+        if self.code >= 500 {
+            current_span.record("otel.status_message", self.message.as_str());
+        }
         current_span.record("http.response.status_code", self.code);
 
         if let Some(error_type) = &self.error_type {
@@ -102,6 +610,14 @@ impl IntoResponse for ApiError {
             current_span.record(key.as_str(), value.as_str());
         }
 
-        (status, Json(self)).into_response()
+        let problem = self.problem();
+        let mut response = (status, problem.to_body()).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_CONTENT_TYPE),
+        );
+        response.extensions_mut().insert(problem);
+
+        response
     }
 }
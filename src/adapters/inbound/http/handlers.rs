@@ -1,59 +1,325 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
 use opentelemetry::trace::Status;
 use std::sync::Arc;
 use tracing::{info, instrument, Span};
 
-use crate::ports::{BillingProfileRepository, PlanRepository, SubscriptionRepository};
-use crate::services::SubscriptionService;
+use crate::domain::{
+    AuditEntry, ChangeSubscriptionPlanRequest, CreateSubscriptionRequest, IssueApiKeyRequest,
+    RegisterWebhookRequest, RequestContext, Subscription, SubscriptionId, TenantId,
+    WebhookDeliveryId, WebhookEndpointId,
+};
+use crate::ports::{
+    ApiKeyRepository, AuditLog, BillingProfileRepository, BusinessEventLog, HealthProbe,
+    LogFilterControl, PlanRepository, SubscriptionEventPublisher, SubscriptionRepository,
+    TokenVerifier, WebhookRepository,
+};
+use crate::services::{HealthService, LogFilterService, SubscriptionService, WebhookService};
 
-use super::dtos::{CreateSubscriptionHttpBody, SubscriptionResponse};
+use super::auth::AuthState;
+use super::dtos::{
+    parse_field, AuditEntryResponse, ChangeSubscriptionPlanHttpBody, CreateSubscriptionHttpBody,
+    IssueApiKeyHttpBody, IssuedApiKeyResponse, ListSubscriptionsQuery, LogFilterResponse,
+    ReadinessResponse, RegisterWebhookHttpBody, SetLogFilterHttpBody, SubscriptionResponse,
+    WebhookDeliveryResponse, WebhookEndpointResponse,
+};
 use super::errors::ApiError;
+use super::extractors::{ApiJson, ApiQuery};
+use super::problem::ProblemDetails;
 
 #[derive(Clone)]
-pub struct AppState<P, B, S>
+pub struct AppState<P, B, S, E, A, L>
 where
     P: PlanRepository,
     B: BillingProfileRepository,
     S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
 {
-    pub subscription_service: Arc<SubscriptionService<P, B, S>>,
+    pub subscription_service: Arc<SubscriptionService<P, B, S, E, A, L>>,
 }
 
-impl<P, B, S> AppState<P, B, S>
+impl<P, B, S, E, A, L> AppState<P, B, S, E, A, L>
 where
     P: PlanRepository,
     B: BillingProfileRepository,
     S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
 {
-    pub fn new(subscription_service: SubscriptionService<P, B, S>) -> Self {
+    pub fn new(subscription_service: SubscriptionService<P, B, S, E, A, L>) -> Self {
         Self {
             subscription_service: Arc::new(subscription_service),
         }
     }
 }
 
+#[derive(Clone)]
+pub struct HealthState<D, M, P, T>
+where
+    D: HealthProbe,
+    M: HealthProbe,
+    P: HealthProbe,
+    T: HealthProbe,
+{
+    pub health_service: Arc<HealthService<D, M, P, T>>,
+}
+
+impl<D, M, P, T> HealthState<D, M, P, T>
+where
+    D: HealthProbe,
+    M: HealthProbe,
+    P: HealthProbe,
+    T: HealthProbe,
+{
+    pub fn new(health_service: HealthService<D, M, P, T>) -> Self {
+        Self {
+            health_service: Arc::new(health_service),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookState<W>
+where
+    W: WebhookRepository,
+{
+    pub webhook_service: Arc<WebhookService<W>>,
+}
+
+impl<W> WebhookState<W>
+where
+    W: WebhookRepository,
+{
+    pub fn new(webhook_service: WebhookService<W>) -> Self {
+        Self {
+            webhook_service: Arc::new(webhook_service),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LogFilterState<F>
+where
+    F: LogFilterControl,
+{
+    pub log_filter_service: Arc<LogFilterService<F>>,
+}
+
+impl<F> LogFilterState<F>
+where
+    F: LogFilterControl,
+{
+    pub fn new(log_filter_service: LogFilterService<F>) -> Self {
+        Self {
+            log_filter_service: Arc::new(log_filter_service),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/subscriptions",
+    tag = "subscriptions",
+    request_body = CreateSubscriptionHttpBody,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the stored response when retried with the same body"),
+    ),
+    responses(
+        (status = 201, description = "Subscription created", body = SubscriptionResponse),
+        (status = 400, description = "Body is not valid JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:create` for the tenant, or the tenant may not use the plan", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Plan not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "A request with the same Idempotency-Key is still in progress", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Body was not sent as application/json", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid fields, missing payment method, or Idempotency-Key reused with a different body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
 #[instrument(
     name = "create_subscription_handler",
-    skip(state, body),
+    skip(state, context, body),
     fields(
         tenant_id = %body.tenant_id,
         plan_id = %body.plan_id,
     )
 )]
-pub async fn create_subscription_handler<P, B, S>(
-    State(state): State<AppState<P, B, S>>,
-    Json(body): Json<CreateSubscriptionHttpBody>,
+pub async fn create_subscription_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<CreateSubscriptionHttpBody>,
 ) -> Result<(StatusCode, Json<SubscriptionResponse>), ApiError>
 where
     P: PlanRepository + 'static,
     B: BillingProfileRepository + 'static,
     S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
 {
-    let request = body.into();
+    let subscription = create_subscription(&state, &context, body).await?;
+
+    Ok((StatusCode::CREATED, Json(subscription.into())))
+}
+
+#[utoipa::path(
+    put,
+    path = "/subscriptions/{subscription_id}/plan",
+    tag = "subscriptions",
+    params(("subscription_id" = String, Path, description = "Subscription id")),
+    request_body = ChangeSubscriptionPlanHttpBody,
+    responses(
+        (status = 200, description = "Plan changed", body = SubscriptionResponse),
+        (status = 400, description = "Body is not valid JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:change_plan` for the tenant, or the tenant may not use the new plan", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Subscription or plan not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Another request changed the subscription's plan first", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Body was not sent as application/json", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid fields or missing payment method", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "change_subscription_plan_handler",
+    skip(state, context, body),
+    fields(
+        subscription_id = %subscription_id,
+        plan_id = %body.plan_id,
+    )
+)]
+pub async fn change_subscription_plan_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    Path(subscription_id): Path<String>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<ChangeSubscriptionPlanHttpBody>,
+) -> Result<Json<SubscriptionResponse>, ApiError>
+where
+    P: PlanRepository + 'static,
+    B: BillingProfileRepository + 'static,
+    S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
+{
+    let subscription = change_subscription_plan(&state, &context, subscription_id, body).await?;
+
+    Ok(Json(subscription.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tenants/{tenant_id}/subscriptions",
+    tag = "subscriptions",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant id"),
+        ListSubscriptionsQuery,
+    ),
+    responses(
+        (status = 200, description = "Subscriptions of the tenant", body = Vec<SubscriptionResponse>),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:list` for the tenant", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid tenant id or query", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 501, description = "Point-in-time queries require the event-sourced store", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "list_subscriptions_handler",
+    skip(state, context, query),
+    fields(tenant_id = %tenant_id, as_of = ?query.as_of)
+)]
+pub async fn list_subscriptions_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    Path(tenant_id): Path<String>,
+    context: RequestContext,
+    ApiQuery(query): ApiQuery<ListSubscriptionsQuery>,
+) -> Result<Json<Vec<SubscriptionResponse>>, ApiError>
+where
+    P: PlanRepository + 'static,
+    B: BillingProfileRepository + 'static,
+    S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
+{
+    let subscriptions = list_subscriptions(&state, &context, tenant_id, query).await?;
+
+    Ok(Json(subscriptions.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/subscriptions/{subscription_id}/history",
+    tag = "subscriptions",
+    params(("subscription_id" = String, Path, description = "Subscription id")),
+    responses(
+        (status = 200, description = "Audit trail of the subscription", body = Vec<AuditEntryResponse>),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:history` for the subscription's tenant", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Subscription not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid subscription id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "subscription_history_handler",
+    skip(state, context),
+    fields(subscription_id = %subscription_id)
+)]
+pub async fn subscription_history_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    Path(subscription_id): Path<String>,
+    context: RequestContext,
+) -> Result<Json<Vec<AuditEntryResponse>>, ApiError>
+where
+    P: PlanRepository + 'static,
+    B: BillingProfileRepository + 'static,
+    S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
+{
+    let history = subscription_history(&state, &context, subscription_id).await?;
+
+    Ok(Json(history.into_iter().map(Into::into).collect()))
+}
+
+// The subscription handlers of every API version share these bodies and only
+// map the result to their own response DTOs.
+
+pub(super) async fn create_subscription<P, B, S, E, A, L>(
+    state: &AppState<P, B, S, E, A, L>,
+    context: &RequestContext,
+    body: CreateSubscriptionHttpBody,
+) -> Result<Subscription, ApiError>
+where
+    P: PlanRepository,
+    B: BillingProfileRepository,
+    S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
+{
+    let request = CreateSubscriptionRequest::try_from(body)?;
 
     let subscription = state
         .subscription_service
-        .create_subscription(&request)
+        .create_subscription(&request, context)
         .await
         .map_err(ApiError::from)?;
 
@@ -72,19 +338,390 @@ where
         span.set_status(Status::Ok);
     });
 
-    let response = SubscriptionResponse::from(subscription);
-    Ok((StatusCode::CREATED, Json(response)))
+    Ok(subscription)
 }
 
-#[instrument(name = "health_check_handler")]
-pub async fn health_check_handler() -> Json<serde_json::Value> {
+pub(super) async fn change_subscription_plan<P, B, S, E, A, L>(
+    state: &AppState<P, B, S, E, A, L>,
+    context: &RequestContext,
+    subscription_id: String,
+    body: ChangeSubscriptionPlanHttpBody,
+) -> Result<Subscription, ApiError>
+where
+    P: PlanRepository,
+    B: BillingProfileRepository,
+    S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
+{
+    let request = ChangeSubscriptionPlanRequest::try_from((subscription_id, body))?;
+
+    let subscription = state
+        .subscription_service
+        .change_subscription_plan(&request, context)
+        .await
+        .map_err(ApiError::from)?;
+
+    info!(
+        subscription_id = %subscription.id,
+        tenant_id = %subscription.tenant_id,
+        plan_id = %subscription.plan_id,
+        "subscription plan changed"
+    );
+
+    Span::current().record("http.response.status_code", 200);
+
+    Ok(subscription)
+}
+
+pub(super) async fn list_subscriptions<P, B, S, E, A, L>(
+    state: &AppState<P, B, S, E, A, L>,
+    context: &RequestContext,
+    tenant_id: String,
+    query: ListSubscriptionsQuery,
+) -> Result<Vec<Subscription>, ApiError>
+where
+    P: PlanRepository,
+    B: BillingProfileRepository,
+    S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
+{
+    let tenant_id = parse_field("tenant_id", TenantId::parse(tenant_id))?;
+
+    state
+        .subscription_service
+        .list_subscriptions(&tenant_id, query.as_of, context)
+        .await
+        .map_err(ApiError::from)
+}
+
+pub(super) async fn subscription_history<P, B, S, E, A, L>(
+    state: &AppState<P, B, S, E, A, L>,
+    context: &RequestContext,
+    subscription_id: String,
+) -> Result<Vec<AuditEntry>, ApiError>
+where
+    P: PlanRepository,
+    B: BillingProfileRepository,
+    S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
+{
+    let subscription_id = parse_field("subscription_id", SubscriptionId::parse(subscription_id))?;
+
+    state
+        .subscription_service
+        .subscription_history(&subscription_id, context)
+        .await
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    post,
+    path = "/tenants/{tenant_id}/webhooks",
+    tag = "webhooks",
+    params(("tenant_id" = String, Path, description = "Tenant id")),
+    request_body = RegisterWebhookHttpBody,
+    responses(
+        (status = 201, description = "Webhook endpoint registered", body = WebhookEndpointResponse),
+        (status = 400, description = "Body is not valid JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `webhooks:manage` for the tenant, which registering an endpoint needs", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Body was not sent as application/json", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid tenant id, or a webhook url that is not http(s) or points at an internal address", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "register_webhook_handler",
+    skip(state, context, body),
+    fields(tenant_id = %tenant_id)
+)]
+pub async fn register_webhook_handler<W>(
+    State(state): State<WebhookState<W>>,
+    Path(tenant_id): Path<String>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<RegisterWebhookHttpBody>,
+) -> Result<(StatusCode, Json<WebhookEndpointResponse>), ApiError>
+where
+    W: WebhookRepository + 'static,
+{
+    let request = RegisterWebhookRequest {
+        tenant_id: parse_field("tenant_id", TenantId::parse(tenant_id))?,
+        url: body.url,
+    };
+
+    let endpoint = state
+        .webhook_service
+        .register_webhook(&request, &context)
+        .await
+        .map_err(ApiError::from)?;
+
+    info!(
+        endpoint_id = %endpoint.id,
+        tenant_id = %endpoint.tenant_id,
+        "webhook endpoint registered"
+    );
+
+    Span::current().record("http.response.status_code", 201);
+
+    Ok((StatusCode::CREATED, Json(endpoint.into())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tenants/{tenant_id}/webhooks/{endpoint_id}/deliveries",
+    tag = "webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant id"),
+        ("endpoint_id" = String, Path, description = "Webhook endpoint id"),
+    ),
+    responses(
+        (status = 200, description = "Deliveries to the endpoint", body = Vec<WebhookDeliveryResponse>),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `webhooks:read` for the tenant", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Webhook endpoint not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid tenant id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "list_webhook_deliveries_handler",
+    skip(state, context),
+    fields(tenant_id = %tenant_id, endpoint_id = %endpoint_id)
+)]
+pub async fn list_webhook_deliveries_handler<W>(
+    State(state): State<WebhookState<W>>,
+    Path((tenant_id, endpoint_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> Result<Json<Vec<WebhookDeliveryResponse>>, ApiError>
+where
+    W: WebhookRepository + 'static,
+{
+    let tenant_id = parse_field("tenant_id", TenantId::parse(tenant_id))?;
+
+    let deliveries = state
+        .webhook_service
+        .list_deliveries(&tenant_id, &WebhookEndpointId::new(endpoint_id), &context)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(deliveries.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/tenants/{tenant_id}/webhook-deliveries/{delivery_id}/replay",
+    tag = "webhooks",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant id"),
+        ("delivery_id" = String, Path, description = "Webhook delivery id"),
+    ),
+    responses(
+        (status = 202, description = "Delivery queued for replay", body = WebhookDeliveryResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `webhooks:manage` for the tenant, which replaying a delivery needs", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Webhook delivery not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "The delivery is being sent right now", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid tenant id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "replay_webhook_delivery_handler",
+    skip(state, context),
+    fields(tenant_id = %tenant_id, delivery_id = %delivery_id)
+)]
+pub async fn replay_webhook_delivery_handler<W>(
+    State(state): State<WebhookState<W>>,
+    Path((tenant_id, delivery_id)): Path<(String, String)>,
+    context: RequestContext,
+) -> Result<(StatusCode, Json<WebhookDeliveryResponse>), ApiError>
+where
+    W: WebhookRepository + 'static,
+{
+    let tenant_id = parse_field("tenant_id", TenantId::parse(tenant_id))?;
+
+    let delivery = state
+        .webhook_service
+        .replay_delivery(&tenant_id, &WebhookDeliveryId::new(delivery_id), &context)
+        .await
+        .map_err(ApiError::from)?;
+
+    info!(delivery_id = %delivery.id, "webhook delivery queued for replay");
+
+    Ok((StatusCode::ACCEPTED, Json(delivery.into())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/api-keys",
+    tag = "admin",
+    request_body = IssueApiKeyHttpBody,
+    responses(
+        (status = 201, description = "Key issued; the plaintext key is only returned here", body = IssuedApiKeyResponse),
+        (status = 400, description = "Body is not valid JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `api_keys:manage`", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Body was not sent as application/json", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid fields or tenant binding", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "issue_api_key_handler",
+    skip(state, context, body),
+    fields(role = %body.role)
+)]
+pub async fn issue_api_key_handler<K, T>(
+    State(state): State<AuthState<K, T>>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<IssueApiKeyHttpBody>,
+) -> Result<(StatusCode, Json<IssuedApiKeyResponse>), ApiError>
+where
+    K: ApiKeyRepository + 'static,
+    T: TokenVerifier + 'static,
+{
+    let issued = state
+        .auth_service
+        .issue_api_key(&IssueApiKeyRequest::try_from(body)?, &context)
+        .await
+        .map_err(ApiError::from)?;
+
+    info!(
+        api_key_id = %issued.0.id,
+        issued_by = %context.actor,
+        "api key issued"
+    );
+
+    Span::current().record("http.response.status_code", 201);
+
+    Ok((StatusCode::CREATED, Json(issued.into())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/log-filter",
+    tag = "admin",
+    responses(
+        (status = 200, description = "The log filter in effect", body = LogFilterResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `logging:manage`, which reading the filter needs", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(name = "get_log_filter_handler", skip(state, context))]
+pub async fn get_log_filter_handler<F>(
+    State(state): State<LogFilterState<F>>,
+    context: RequestContext,
+) -> Result<Json<LogFilterResponse>, ApiError>
+where
+    F: LogFilterControl + 'static,
+{
+    Ok(Json(state.log_filter_service.log_filter(&context)?.into()))
+}
+
+/// Replaces the log filter without a restart. With `revert_after_secs`, the
+/// configured `RUST_LOG` comes back on its own.
+#[utoipa::path(
+    put,
+    path = "/admin/log-filter",
+    tag = "admin",
+    request_body = SetLogFilterHttpBody,
+    responses(
+        (status = 200, description = "Filter installed", body = LogFilterResponse),
+        (status = 400, description = "Body is not valid JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `logging:manage`, which changing the filter needs", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Body was not sent as application/json", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid directives or revert delay", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "The filter could not be reloaded", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "set_log_filter_handler",
+    skip(state, context, body),
+    fields(directives = %body.directives, revert_after_secs = ?body.revert_after_secs)
+)]
+pub async fn set_log_filter_handler<F>(
+    State(state): State<LogFilterState<F>>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<SetLogFilterHttpBody>,
+) -> Result<Json<LogFilterResponse>, ApiError>
+where
+    F: LogFilterControl + 'static,
+{
+    let revert_after = body.validate()?;
+
+    let status =
+        state
+            .log_filter_service
+            .set_log_filter(body.directives.trim(), revert_after, &context)?;
+
+    Ok(Json(status.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses((status = 200, description = "The process is running", body = Object))
+)]
+#[instrument(name = "liveness_handler")]
+pub async fn liveness_handler() -> Json<serde_json::Value> {
     opentelemetry::trace::get_active_span(|span| {
         span.set_status(Status::Ok);
     });
 
     Json(serde_json::json!({
-        "status": "healthy",
+        "status": "alive",
         "service": "ledgercloud",
         "version": env!("CARGO_PKG_VERSION")
     }))
 }
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "All critical dependencies are up", body = ReadinessResponse),
+        (status = 503, description = "A critical dependency is down", body = ReadinessResponse),
+    )
+)]
+#[instrument(name = "readiness_handler", skip(state))]
+pub async fn readiness_handler<D, M, P, T>(
+    State(state): State<HealthState<D, M, P, T>>,
+) -> (StatusCode, Json<ReadinessResponse>)
+where
+    D: HealthProbe + 'static,
+    M: HealthProbe + 'static,
+    P: HealthProbe + 'static,
+    T: HealthProbe + 'static,
+{
+    let report = state.health_service.readiness().await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    Span::current().record("http.response.status_code", status.as_u16());
+
+    (status, Json(report.into()))
+}
@@ -0,0 +1,312 @@
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::domain::Principal;
+use crate::ports::{IdempotencyClaim, IdempotencyStore, StoredResponse};
+
+use super::errors::ApiError;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+pub struct IdempotencyState<I>
+where
+    I: IdempotencyStore,
+{
+    store: I,
+    ttl: Duration,
+    lock_timeout: Duration,
+}
+
+impl<I> IdempotencyState<I>
+where
+    I: IdempotencyStore,
+{
+    /// `lock_timeout` bounds how long a request that never finished (client
+    /// gone, panic, shutdown) blocks retries with its key. It should be longer
+    /// than any request takes, or a slow request can run twice.
+    pub fn new(store: I, ttl: Duration, lock_timeout: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            store,
+            ttl,
+            lock_timeout,
+        })
+    }
+}
+
+/// Hashes the method, path and body. JSON bodies are re-serialized first so
+/// that key order and whitespace do not change the fingerprint.
+fn fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+    let canonical = serde_json::from_slice::<serde_json::Value>(body)
+        .map(|value| value.to_string().into_bytes())
+        .unwrap_or_else(|_| body.to_vec());
+
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(&canonical);
+    hex::encode(hasher.finalize())
+}
+
+fn idempotency_error(code: u16, error_type: &str, message: String, key: &str) -> Response {
+    let mut attrs = HashMap::new();
+    attrs.insert("idempotency_key".to_string(), key.to_string());
+    ApiError {
+        message,
+        code,
+        error_type: Some(error_type.to_string()),
+        error_attributes: attrs,
+        field_errors: Vec::new(),
+    }
+    .into_response()
+}
+
+fn internal_error() -> Response {
+    ApiError {
+        message: "Internal server error".into(),
+        code: 500,
+        error_type: Some("Unexpected".to_string()),
+        error_attributes: HashMap::new(),
+        field_errors: Vec::new(),
+    }
+    .into_response()
+}
+
+/// Headers that describe the connection or the original exchange rather than
+/// the response, and so are not replayed.
+const UNREPLAYED_HEADERS: [HeaderName; 3] = [
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+    header::CONNECTION,
+];
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status_code).unwrap_or(StatusCode::OK);
+    let mut response = (status, Body::from(stored.body)).into_response();
+
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    response
+}
+
+/// Honors the `Idempotency-Key` header: the first request with a key is
+/// processed and its response stored; retries with the same body get the
+/// stored response back, retries with a different body are rejected with 422.
+/// Server errors are not stored so that the client can retry them. That is
+/// only safe because the use cases fail with one before their change commits:
+/// everything a change writes, business events included, shares its
+/// transaction, so a retry cannot repeat a committed change.
+pub async fn idempotency_middleware<I>(
+    State(state): State<Arc<IdempotencyState<I>>>,
+    request: Request,
+    next: Next,
+) -> Response
+where
+    I: IdempotencyStore + 'static,
+{
+    let key = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        None => return next.run(request).await,
+        Some(value) => match value.to_str() {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+            _ => {
+                return idempotency_error(
+                    400,
+                    "InvalidIdempotencyKey",
+                    format!(
+                        "Idempotency-Key must be 1 to {} visible ASCII characters",
+                        MAX_KEY_LENGTH
+                    ),
+                    "",
+                )
+            }
+        },
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            warn!(error = %e, "failed to buffer request body for idempotency check");
+            return idempotency_error(
+                413,
+                "PayloadTooLarge",
+                "Request body is too large".into(),
+                &key,
+            );
+        }
+    };
+
+    // Keys are scoped to the caller so that one tenant can never replay
+    // another tenant's response by guessing its key.
+    let subject = parts
+        .extensions
+        .get::<Principal>()
+        .map(|p| p.subject.as_str())
+        .unwrap_or("anonymous");
+    let scope = format!("{} {} {}", subject, parts.method, parts.uri.path());
+    let fingerprint = fingerprint(parts.method.as_str(), parts.uri.path(), &body);
+    let now = Utc::now();
+    let expires_at =
+        now + chrono::Duration::from_std(state.ttl).unwrap_or_else(|_| chrono::Duration::days(1));
+    let locked_until = now
+        + chrono::Duration::from_std(state.lock_timeout)
+            .unwrap_or_else(|_| chrono::Duration::minutes(1));
+
+    let claim = match state
+        .store
+        .claim(&scope, &key, &fingerprint, locked_until, expires_at)
+        .await
+    {
+        Ok(claim) => claim,
+        Err(_) => return internal_error(),
+    };
+
+    match claim {
+        IdempotencyClaim::Existing(record) if record.fingerprint != fingerprint => {
+            warn!(idempotency_key = %key, "idempotency key reused with a different request");
+            idempotency_error(
+                422,
+                "IdempotencyKeyReused",
+                format!(
+                    "Idempotency-Key {} was already used with a different request body",
+                    key
+                ),
+                &key,
+            )
+        }
+        IdempotencyClaim::Existing(record) => match record.response {
+            Some(stored) => {
+                info!(
+                    idempotency_key = %key,
+                    expires_at = %record.expires_at,
+                    "replaying stored response"
+                );
+                replay(stored)
+            }
+            // Only until the original request's lease runs out; see `claim`.
+            None => idempotency_error(
+                409,
+                "IdempotentRequestInProgress",
+                format!(
+                    "A request with Idempotency-Key {} is still being processed",
+                    key
+                ),
+                &key,
+            ),
+        },
+        IdempotencyClaim::Acquired => {
+            let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+            if response.status().is_server_error() {
+                let _ = state.store.release(&scope, &key).await;
+                return response;
+            }
+
+            let (parts, body) = response.into_parts();
+            let body: Bytes = match to_bytes(body, usize::MAX).await {
+                Ok(body) => body,
+                Err(_) => {
+                    let _ = state.store.release(&scope, &key).await;
+                    return internal_error();
+                }
+            };
+
+            let stored = StoredResponse {
+                status_code: parts.status.as_u16(),
+                headers: parts
+                    .headers
+                    .iter()
+                    .filter(|(name, _)| !UNREPLAYED_HEADERS.contains(name))
+                    .filter_map(|(name, value)| {
+                        Some((name.to_string(), value.to_str().ok()?.to_string()))
+                    })
+                    .collect(),
+                body: body.to_vec(),
+            };
+
+            if state.store.complete(&scope, &key, &stored).await.is_err() {
+                let _ = state.store.release(&scope, &key).await;
+            }
+
+            Response::from_parts(parts, Body::from(body))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_json_formatting() {
+        let a = fingerprint(
+            "POST",
+            "/api/subscriptions",
+            br#"{"tenant_id":"t1","plan_id":"pro"}"#,
+        );
+        let b = fingerprint(
+            "POST",
+            "/api/subscriptions",
+            br#"{ "plan_id": "pro", "tenant_id": "t1" }"#,
+        );
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_fingerprint_changes_with_body() {
+        let a = fingerprint("POST", "/api/subscriptions", br#"{"plan_id":"pro"}"#);
+        let b = fingerprint("POST", "/api/subscriptions", br#"{"plan_id":"free"}"#);
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_replay_restores_stored_headers() {
+        let response = replay(StoredResponse {
+            status_code: 201,
+            headers: vec![
+                ("content-type".to_string(), "application/json".to_string()),
+                (
+                    "location".to_string(),
+                    "/api/v2/subscriptions/sub_1".to_string(),
+                ),
+            ],
+            body: b"{}".to_vec(),
+        });
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "/api/v2/subscriptions/sub_1"
+        );
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+    }
+}
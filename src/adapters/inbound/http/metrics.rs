@@ -0,0 +1,51 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use opentelemetry::KeyValue;
+use std::time::Instant;
+
+use crate::metrics::{instruments, MetricsHandle};
+
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Records the duration of every request, labelled with the route template
+/// rather than the raw path so ids do not blow up cardinality. Requests that
+/// match no route are grouped under `unmatched`.
+pub async fn http_metrics_middleware(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = request.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    instruments().http_request_duration.record(
+        started.elapsed().as_secs_f64(),
+        &[
+            KeyValue::new("http.route", route),
+            KeyValue::new("http.request.method", method),
+            KeyValue::new(
+                "http.response.status_code",
+                i64::from(response.status().as_u16()),
+            ),
+        ],
+    );
+
+    response
+}
+
+pub async fn metrics_handler(State(metrics): State<MetricsHandle>) -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        metrics.render(),
+    )
+}
@@ -1,5 +1,33 @@
+pub mod auth;
 pub mod dtos;
 pub mod errors;
+pub mod extractors;
 pub mod handlers;
+pub mod idempotency;
+pub mod metrics;
+pub mod openapi;
+pub mod problem;
+pub mod rate_limit;
+pub mod request_id;
+pub mod server;
+pub mod trace_context;
+pub mod v2;
+pub mod versioning;
 
-pub use handlers::{create_subscription_handler, health_check_handler, AppState};
+pub use auth::{authenticate, AuthState};
+pub use handlers::{
+    change_subscription_plan_handler, create_subscription_handler, get_log_filter_handler,
+    issue_api_key_handler, list_subscriptions_handler, list_webhook_deliveries_handler,
+    liveness_handler, readiness_handler, register_webhook_handler, replay_webhook_delivery_handler,
+    set_log_filter_handler, subscription_history_handler, AppState, HealthState, LogFilterState,
+    WebhookState,
+};
+pub use idempotency::{idempotency_middleware, IdempotencyState};
+pub use metrics::{http_metrics_middleware, metrics_handler, METRICS_PATH};
+pub use openapi::{openapi_handler, OPENAPI_PATH, SWAGGER_UI_PATH};
+pub use problem::problem_instance_middleware;
+pub use rate_limit::{RateLimitConfig, RateLimitLayer, RateLimitPolicy};
+pub use request_id::RequestIdLayer;
+pub use server::{resolve_bind_addr, ListenerConfig, TlsConfig};
+pub use trace_context::make_request_span;
+pub use versioning::{deprecation_middleware, DeprecationPolicy};
@@ -0,0 +1,168 @@
+use axum::Json;
+use utoipa::{
+    openapi::{
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Deprecated,
+    },
+    Modify, OpenApi,
+};
+
+use super::auth::API_KEY_HEADER;
+use super::dtos::{
+    AuditEntryResponse, ChangeSubscriptionPlanHttpBody, CreateSubscriptionHttpBody,
+    IssueApiKeyHttpBody, IssuedApiKeyResponse, LogFilterResponse, RegisterWebhookHttpBody,
+    SetLogFilterHttpBody, SubscriptionResponse, WebhookDeliveryAttemptResponse,
+    WebhookDeliveryResponse, WebhookEndpointResponse,
+};
+use super::handlers;
+use super::problem::{FieldError, ProblemDetails};
+use super::v2;
+
+pub const OPENAPI_PATH: &str = "/openapi.json";
+pub const SWAGGER_UI_PATH: &str = "/docs";
+
+/// The OpenAPI 3.1 document for the HTTP adapter. Paths come from the
+/// `#[utoipa::path]` attributes on the handlers, nested under each version's
+/// prefix and under the unversioned `/api` alias of v1, and are checked
+/// against the router built in `main.rs` by the drift test there.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "LedgerCloud API",
+        description = "Multi-tenant subscription billing. Errors are RFC 9457 problem details."
+    ),
+    paths(handlers::liveness_handler, handlers::readiness_handler),
+    nest(
+        (path = "/api/v1", api = V1Api),
+        (path = "/api/v2", api = V2Api),
+        (path = "/api", api = V1Api),
+    ),
+    components(schemas(ProblemDetails, FieldError)),
+    modifiers(&SecuritySchemes, &VersionedOperations),
+    tags(
+        (name = "subscriptions", description = "Create, change and list subscriptions"),
+        (name = "webhooks", description = "Tenant webhook endpoints and deliveries"),
+        (name = "admin", description = "Platform administration"),
+        (name = "health", description = "Liveness and readiness probes"),
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        handlers::create_subscription_handler,
+        handlers::change_subscription_plan_handler,
+        handlers::list_subscriptions_handler,
+        handlers::subscription_history_handler,
+        handlers::register_webhook_handler,
+        handlers::list_webhook_deliveries_handler,
+        handlers::replay_webhook_delivery_handler,
+        handlers::issue_api_key_handler,
+        handlers::get_log_filter_handler,
+        handlers::set_log_filter_handler,
+    ),
+    components(schemas(
+        CreateSubscriptionHttpBody,
+        ChangeSubscriptionPlanHttpBody,
+        SubscriptionResponse,
+        AuditEntryResponse,
+        RegisterWebhookHttpBody,
+        WebhookEndpointResponse,
+        WebhookDeliveryResponse,
+        WebhookDeliveryAttemptResponse,
+        IssueApiKeyHttpBody,
+        IssuedApiKeyResponse,
+        SetLogFilterHttpBody,
+        LogFilterResponse,
+    ))
+)]
+struct V1Api;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        v2::handlers::create_subscription_handler,
+        v2::handlers::change_subscription_plan_handler,
+        v2::handlers::list_subscriptions_handler,
+        v2::handlers::subscription_history_handler,
+        handlers::register_webhook_handler,
+        handlers::list_webhook_deliveries_handler,
+        handlers::replay_webhook_delivery_handler,
+        handlers::issue_api_key_handler,
+        handlers::get_log_filter_handler,
+        handlers::set_log_filter_handler,
+    ),
+    components(schemas(
+        v2::dtos::PlanReference,
+        v2::dtos::SubscriptionResponse,
+        v2::dtos::AuditEntryResponse,
+    ))
+)]
+struct V2Api;
+
+/// Handlers shared between versions would otherwise have the same
+/// `operationId` twice, so ids get the version as a prefix, or `unversioned`
+/// for the `/api` alias. Every v1 operation, aliased or not, is marked
+/// deprecated.
+struct VersionedOperations;
+
+impl Modify for VersionedOperations {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            let Some(rest) = path.strip_prefix("/api/") else {
+                continue;
+            };
+            let version = rest.split('/').next().filter(|segment| is_version(segment));
+            let prefix = version.unwrap_or("unversioned");
+            let deprecated = version.is_none_or(|version| version == "v1");
+
+            let operations = [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.patch,
+                &mut item.delete,
+            ];
+            for operation in operations.into_iter().flatten() {
+                if let Some(id) = &operation.operation_id {
+                    operation.operation_id = Some(format!("{}_{}", prefix, id));
+                }
+                if deprecated {
+                    operation.deprecated = Some(Deprecated::True);
+                }
+            }
+        }
+    }
+}
+
+fn is_version(segment: &str) -> bool {
+    segment
+        .strip_prefix('v')
+        .is_some_and(|number| number.parse::<u32>().is_ok())
+}
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+pub async fn openapi_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
@@ -0,0 +1,143 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use super::request_id::REQUEST_ID_HEADER;
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Base of the `type` URIs. The path segment is derived from the error type
+/// name, so it stays stable as long as the `ApiError` mapping does.
+pub const PROBLEM_TYPE_BASE: &str = "https://ledgercloud.dev/problems/";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub detail: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// An RFC 9457 problem details document. Error attributes become extension
+/// members next to the standard ones.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    #[schema(example = "https://ledgercloud.dev/problems/plan-not-found")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    /// Extension members, e.g. `plan_id` or `tenant_id`.
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, String>,
+}
+
+/// `PlanNotFound` -> `plan-not-found`
+pub fn type_slug(error_type: &str) -> String {
+    let mut slug = String::with_capacity(error_type.len() + 4);
+    for (i, c) in error_type.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            slug.push('-');
+        }
+        slug.push(c.to_ascii_lowercase());
+    }
+    slug
+}
+
+/// `PlanNotFound` -> `Plan not found`
+pub fn type_title(error_type: &str) -> String {
+    let words = type_slug(error_type).replace('-', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+impl ProblemDetails {
+    pub fn to_body(&self) -> Body {
+        Body::from(serde_json::to_vec(self).unwrap_or_default())
+    }
+}
+
+/// Fills in `instance` with the request id. `ApiError::into_response` has no
+/// access to the request, so it leaves the problem in the response extensions
+/// and the body is rendered again here.
+pub async fn problem_instance_middleware(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let mut response = next.run(request).await;
+
+    let Some(request_id) = request_id else {
+        return response;
+    };
+
+    if let Some(mut problem) = response.extensions_mut().remove::<ProblemDetails>() {
+        problem.instance = Some(request_id);
+        let (mut parts, _) = response.into_parts();
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_CONTENT_TYPE),
+        );
+        response = Response::from_parts(parts, problem.to_body());
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_names_map_to_slug_and_title() {
+        assert_eq!(type_slug("PlanNotFound"), "plan-not-found");
+        assert_eq!(type_title("MissingPaymentMethod"), "Missing payment method");
+    }
+
+    #[test]
+    fn test_attributes_are_extension_members() {
+        let problem = ProblemDetails {
+            type_uri: format!("{}plan-not-found", PROBLEM_TYPE_BASE),
+            title: "Plan not found".into(),
+            status: 404,
+            detail: "Plan gold not found".into(),
+            instance: Some("req_1".into()),
+            errors: vec![],
+            extensions: BTreeMap::from([("plan_id".to_string(), "gold".to_string())]),
+        };
+
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(
+            json["type"],
+            "https://ledgercloud.dev/problems/plan-not-found"
+        );
+        assert_eq!(json["instance"], "req_1");
+        assert_eq!(json["plan_id"], "gold");
+        assert!(json.get("errors").is_none());
+    }
+}
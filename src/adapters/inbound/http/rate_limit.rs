@@ -0,0 +1,432 @@
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+use tracing::{error, warn};
+
+use crate::domain::{PlanId, Principal, TenantId};
+use crate::ports::TenantPlanLookup;
+
+use super::errors::ApiError;
+
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "ratelimit-limit";
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "ratelimit-remaining";
+pub const RATE_LIMIT_RESET_HEADER: &str = "ratelimit-reset";
+pub const RATE_LIMIT_POLICY_HEADER: &str = "ratelimit-policy";
+pub const RETRY_AFTER_HEADER: &str = "retry-after";
+
+/// Buckets hold up to one minute's worth of requests and refill continuously.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    pub requests_per_minute: u32,
+}
+
+impl RateLimitPolicy {
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.requests_per_minute) / 60.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    pub default_policy: RateLimitPolicy,
+    pub plan_policies: HashMap<PlanId, RateLimitPolicy>,
+    /// How long a tenant's resolved tier is reused before its plans are looked
+    /// up again.
+    pub tier_cache_ttl: Duration,
+}
+
+impl RateLimitConfig {
+    /// A tenant on several plans gets the most generous of their limits.
+    fn policy_for(&self, plans: &[PlanId]) -> RateLimitPolicy {
+        plans
+            .iter()
+            .filter_map(|plan| self.plan_policies.get(plan))
+            .max_by_key(|policy| policy.requests_per_minute)
+            .copied()
+            .unwrap_or(self.default_policy)
+    }
+}
+
+/// A bucket left alone this long has refilled completely, so dropping it
+/// loses nothing: the next request starts a full one.
+const BUCKET_REFILL_WINDOW: Duration = Duration::from_secs(60);
+
+struct Decision {
+    allowed: bool,
+    limit: u32,
+    remaining: u32,
+    reset: Duration,
+    retry_after: Duration,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(policy: RateLimitPolicy, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(policy.requests_per_minute),
+            updated: now,
+        }
+    }
+
+    fn take(&mut self, policy: RateLimitPolicy, now: Instant) -> Decision {
+        let capacity = f64::from(policy.requests_per_minute);
+        let rate = policy.refill_per_sec();
+
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(capacity);
+        self.updated = now;
+
+        let allowed = self.tokens >= 1.0;
+        if allowed {
+            self.tokens -= 1.0;
+        }
+
+        let seconds_until = |tokens: f64| {
+            if rate > 0.0 {
+                Duration::from_secs_f64((tokens.max(0.0) / rate).ceil())
+            } else {
+                Duration::from_secs(60)
+            }
+        };
+
+        Decision {
+            allowed,
+            limit: policy.requests_per_minute,
+            remaining: self.tokens.floor() as u32,
+            reset: seconds_until(capacity - self.tokens),
+            retry_after: seconds_until(1.0 - self.tokens).max(Duration::from_secs(1)),
+        }
+    }
+}
+
+/// Entries for tenants and credentials that stop sending requests would stay
+/// forever, so stale ones are dropped by the first writer after each sweep
+/// interval.
+struct SweptMap<K, V> {
+    entries: HashMap<K, V>,
+    swept: Instant,
+}
+
+impl<K: Eq + Hash, V> SweptMap<K, V> {
+    fn new(now: Instant) -> Self {
+        Self {
+            entries: HashMap::new(),
+            swept: now,
+        }
+    }
+
+    fn sweep(&mut self, now: Instant, interval: Duration, keep: impl Fn(&V) -> bool) {
+        if now.saturating_duration_since(self.swept) < interval {
+            return;
+        }
+        self.entries.retain(|_, value| keep(value));
+        self.swept = now;
+    }
+}
+
+struct Limiter<L> {
+    lookup: L,
+    config: RateLimitConfig,
+    buckets: Mutex<SweptMap<String, Bucket>>,
+    tiers: Mutex<SweptMap<TenantId, (RateLimitPolicy, Instant)>>,
+}
+
+impl<L> Limiter<L>
+where
+    L: TenantPlanLookup,
+{
+    /// Lookup failures fall back to the default tier rather than rejecting
+    /// the request.
+    async fn policy(&self, tenant_id: &TenantId) -> RateLimitPolicy {
+        let now = Instant::now();
+
+        if let Some((policy, resolved_at)) = self.tiers.lock().unwrap().entries.get(tenant_id) {
+            if now.duration_since(*resolved_at) < self.config.tier_cache_ttl {
+                return *policy;
+            }
+        }
+
+        let policy = match self.lookup.plans_for_tenant(tenant_id).await {
+            Ok(plans) => self.config.policy_for(&plans),
+            Err(e) => {
+                error!(error = %e, tenant_id = %tenant_id, "failed to resolve rate limit tier");
+                self.config.default_policy
+            }
+        };
+
+        self.cache_tier(tenant_id, policy, now);
+
+        policy
+    }
+}
+
+impl<L> Limiter<L> {
+    fn cache_tier(&self, tenant_id: &TenantId, policy: RateLimitPolicy, now: Instant) {
+        let ttl = self.config.tier_cache_ttl;
+        let mut tiers = self.tiers.lock().unwrap();
+
+        tiers.sweep(now, ttl, |(_, resolved_at)| {
+            now.saturating_duration_since(*resolved_at) < ttl
+        });
+        tiers.entries.insert(tenant_id.clone(), (policy, now));
+    }
+
+    fn check(&self, key: &str, policy: RateLimitPolicy, now: Instant) -> Decision {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        buckets.sweep(now, BUCKET_REFILL_WINDOW, |bucket| {
+            now.saturating_duration_since(bucket.updated) < BUCKET_REFILL_WINDOW
+        });
+        buckets
+            .entries
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket::full(policy, now))
+            .take(policy, now)
+    }
+}
+
+fn set_headers(headers: &mut HeaderMap, decision: &Decision) {
+    let mut set = |name: &'static str, value: String| {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    };
+
+    set(RATE_LIMIT_LIMIT_HEADER, decision.limit.to_string());
+    set(RATE_LIMIT_REMAINING_HEADER, decision.remaining.to_string());
+    set(
+        RATE_LIMIT_RESET_HEADER,
+        decision.reset.as_secs().to_string(),
+    );
+    set(RATE_LIMIT_POLICY_HEADER, format!("{};w=60", decision.limit));
+
+    if !decision.allowed {
+        set(
+            RETRY_AFTER_HEADER,
+            decision.retry_after.as_secs().to_string(),
+        );
+    }
+}
+
+/// Token-bucket rate limiting per tenant. Must run after authentication: the
+/// bucket is chosen from the `Principal` in the request extensions, shared by
+/// all keys of a tenant, and sized by the tenant's plan. Staff principals get
+/// a bucket per credential at the default tier.
+pub struct RateLimitLayer<L> {
+    limiter: Arc<Limiter<L>>,
+}
+
+impl<L> Clone for RateLimitLayer<L> {
+    fn clone(&self) -> Self {
+        Self {
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+impl<L> RateLimitLayer<L>
+where
+    L: TenantPlanLookup,
+{
+    pub fn new(lookup: L, config: RateLimitConfig) -> Self {
+        Self {
+            limiter: Arc::new(Limiter {
+                lookup,
+                config,
+                buckets: Mutex::new(SweptMap::new(Instant::now())),
+                tiers: Mutex::new(SweptMap::new(Instant::now())),
+            }),
+        }
+    }
+}
+
+impl<S, L> Layer<S> for RateLimitLayer<L> {
+    type Service = RateLimit<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+pub struct RateLimit<S, L> {
+    inner: S,
+    limiter: Arc<Limiter<L>>,
+}
+
+impl<S: Clone, L> Clone for RateLimit<S, L> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+impl<S, L> Service<Request> for RateLimit<S, L>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    L: TenantPlanLookup + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The clone is not ready yet; keep the instance that was polled.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            let Some(principal) = request.extensions().get::<Principal>().cloned() else {
+                return inner.call(request).await;
+            };
+
+            let (key, policy) = match &principal.tenant_id {
+                Some(tenant_id) => (
+                    format!("tenant:{}", tenant_id),
+                    limiter.policy(tenant_id).await,
+                ),
+                None => (principal.subject.clone(), limiter.config.default_policy),
+            };
+
+            let decision = limiter.check(&key, policy, Instant::now());
+
+            if !decision.allowed {
+                warn!(
+                    rate_limit.key = %key,
+                    rate_limit.limit = decision.limit,
+                    retry_after_secs = decision.retry_after.as_secs(),
+                    "rate limit exceeded"
+                );
+
+                let mut attrs = HashMap::new();
+                attrs.insert("rate_limit.key".to_string(), key);
+                let mut response = ApiError {
+                    message: format!(
+                        "Rate limit of {} requests per minute exceeded",
+                        decision.limit
+                    ),
+                    code: 429,
+                    error_type: Some("RateLimited".to_string()),
+                    error_attributes: attrs,
+                    field_errors: Vec::new(),
+                }
+                .into_response();
+                set_headers(response.headers_mut(), &decision);
+                return Ok(response);
+            }
+
+            let mut response = inner.call(request).await?;
+            set_headers(response.headers_mut(), &decision);
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(requests_per_minute: u32) -> RateLimitPolicy {
+        RateLimitPolicy {
+            requests_per_minute,
+        }
+    }
+
+    #[test]
+    fn test_bucket_rejects_when_empty_and_refills() {
+        let start = Instant::now();
+        let mut bucket = Bucket::full(policy(2), start);
+
+        assert!(bucket.take(policy(2), start).allowed);
+        let second = bucket.take(policy(2), start);
+        assert!(second.allowed);
+        assert_eq!(second.remaining, 0);
+
+        let rejected = bucket.take(policy(2), start);
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.retry_after, Duration::from_secs(30));
+
+        assert!(
+            bucket
+                .take(policy(2), start + Duration::from_secs(30))
+                .allowed
+        );
+    }
+
+    #[test]
+    fn test_most_generous_plan_wins() {
+        let config = RateLimitConfig {
+            default_policy: policy(10),
+            plan_policies: HashMap::from([
+                (PlanId::new("free"), policy(60)),
+                (PlanId::new("pro"), policy(300)),
+            ]),
+            tier_cache_ttl: Duration::from_secs(60),
+        };
+
+        assert_eq!(
+            config.policy_for(&[PlanId::new("free"), PlanId::new("pro")]),
+            policy(300)
+        );
+        assert_eq!(config.policy_for(&[PlanId::new("legacy")]), policy(10));
+        assert_eq!(config.policy_for(&[]), policy(10));
+    }
+
+    #[test]
+    fn test_idle_buckets_and_expired_tiers_are_dropped() {
+        let start = Instant::now();
+        let limiter = Limiter {
+            lookup: (),
+            config: RateLimitConfig {
+                default_policy: policy(10),
+                plan_policies: HashMap::new(),
+                tier_cache_ttl: Duration::from_secs(30),
+            },
+            buckets: Mutex::new(SweptMap::new(start)),
+            tiers: Mutex::new(SweptMap::new(start)),
+        };
+
+        for n in 0..100 {
+            limiter.check(&format!("key_{n}"), policy(10), start);
+            limiter.cache_tier(&TenantId::new(format!("tenant_{n}")), policy(10), start);
+        }
+        let later = start + Duration::from_secs(45);
+        let busy = limiter.check("key_0", policy(10), later);
+        assert_eq!(busy.remaining, 9);
+        limiter.cache_tier(&TenantId::new("tenant_0"), policy(10), later);
+
+        // Not yet idle for a full refill window: the buckets stay.
+        assert_eq!(limiter.buckets.lock().unwrap().entries.len(), 100);
+        assert_eq!(limiter.tiers.lock().unwrap().entries.len(), 1);
+
+        limiter.check("key_1", policy(10), start + Duration::from_secs(61));
+        let buckets = limiter.buckets.lock().unwrap();
+        let mut kept: Vec<_> = buckets.entries.keys().map(String::as_str).collect();
+        kept.sort();
+        assert_eq!(kept, ["key_0", "key_1"]);
+    }
+}
@@ -0,0 +1,109 @@
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue},
+    response::Response,
+};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+use crate::observability;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The caller's `X-Request-Id` if it is a plausible id, otherwise a new UUID.
+/// Anything longer than 128 characters or outside `[A-Za-z0-9._:-]` is
+/// replaced, so the id is always safe to log and echo back.
+pub fn request_id(headers: &HeaderMap) -> String {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | ':' | '-'))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Accepts or generates the request id before anything else runs. The id is
+/// written back into the request headers (where the request span, the audit
+/// context and problem `instance` read it), kept as the current request id
+/// for outbound calls, and returned in the response headers.
+#[derive(Clone, Default)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestId<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestId { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequestId<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for RequestId<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        // The clone is not ready yet; keep the instance that was polled.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let id = request_id(request.headers());
+        // Only characters that are valid in a header value get this far.
+        let value = HeaderValue::from_str(&id).expect("request id is a valid header value");
+        request
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER, value.clone());
+
+        Box::pin(async move {
+            let mut response =
+                observability::with_request_id(id, async move { inner.call(request).await })
+                    .await?;
+            response.headers_mut().insert(REQUEST_ID_HEADER, value);
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_ids_are_kept_and_others_replaced() {
+        let with = |id: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(REQUEST_ID_HEADER, HeaderValue::from_str(id).unwrap());
+            request_id(&headers)
+        };
+
+        assert_eq!(with("req_7f3a:retry-1"), "req_7f3a:retry-1");
+        for rejected in ["", "has space", "<script>", &"a".repeat(129)] {
+            let replaced = with(rejected);
+            assert!(uuid::Uuid::parse_str(&replaced).is_ok(), "{}", replaced);
+        }
+        assert!(uuid::Uuid::parse_str(&request_id(&HeaderMap::new())).is_ok());
+    }
+}
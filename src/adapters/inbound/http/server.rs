@@ -0,0 +1,201 @@
+use anyhow::Context;
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+/// PEM files for native TLS termination.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// Where the HTTP adapter listens. The TCP listener is always bound; the Unix
+/// socket is an extra listener for sidecars on the same host and never uses
+/// TLS.
+#[derive(Debug, Clone)]
+pub struct ListenerConfig {
+    pub addr: SocketAddr,
+    pub tls: Option<TlsConfig>,
+    pub unix_socket: Option<PathBuf>,
+}
+
+/// Resolves `HOST` and `PORT` into a bind address. IP literals are used as
+/// they are (IPv6 may be bracketed, e.g. `[::1]`); anything else is looked up
+/// and the first address wins.
+pub async fn resolve_bind_addr(host: &str, port: u16) -> anyhow::Result<SocketAddr> {
+    let host = host.trim();
+    let literal = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+
+    if let Ok(ip) = literal.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, port));
+    }
+
+    tokio::net::lookup_host((literal, port))
+        .await
+        .with_context(|| format!("failed to resolve HOST `{}`", host))?
+        .next()
+        .with_context(|| format!("HOST `{}` did not resolve to any address", host))
+}
+
+/// Serves `app` on every configured listener until `shutdown` is cancelled,
+/// then lets in-flight requests finish. The caller bounds how long that takes.
+pub async fn serve(
+    app: Router,
+    config: ListenerConfig,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    let tcp = serve_tcp(app.clone(), config.addr, config.tls, shutdown.clone());
+
+    match config.unix_socket {
+        Some(path) => {
+            let unix = serve_unix(app, path, shutdown);
+            tokio::try_join!(tcp, unix).map(|_| ())
+        }
+        None => tcp.await,
+    }
+}
+
+async fn serve_tcp(
+    app: Router,
+    addr: SocketAddr,
+    tls: Option<TlsConfig>,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    let Some(tls) = tls else {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to bind to {}", addr))?;
+        info!(address = %addr, "starting HTTP server");
+
+        return axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown.cancelled_owned())
+            .await
+            .context("server error");
+    };
+
+    // rustls needs a process-wide crypto provider; a second install only
+    // fails because one is already set, which is fine.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let rustls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+        .await
+        .with_context(|| {
+            format!(
+                "failed to load TLS certificate `{}` and key `{}`",
+                tls.cert_path.display(),
+                tls.key_path.display()
+            )
+        })
+        .inspect_err(|e| error!(error = %e, "tls configuration failed"))?;
+
+    let handle = axum_server::Handle::new();
+    let graceful = handle.clone();
+    tokio::spawn(async move {
+        shutdown.cancelled().await;
+        graceful.graceful_shutdown(None);
+    });
+
+    info!(address = %addr, cert = %tls.cert_path.display(), "starting HTTPS server");
+
+    axum_server::bind_rustls(addr, rustls_config)
+        .handle(handle)
+        .serve(app.into_make_service())
+        .await
+        .with_context(|| format!("TLS server on {} failed", addr))
+}
+
+#[cfg(unix)]
+async fn serve_unix(app: Router, path: PathBuf, shutdown: CancellationToken) -> anyhow::Result<()> {
+    use hyper_util::rt::{TokioExecutor, TokioIo};
+    use hyper_util::server::{conn::auto, graceful::GracefulShutdown};
+    use hyper_util::service::TowerToHyperService;
+    use tracing::{debug, warn};
+
+    remove_stale_socket(&path)?;
+    let listener = tokio::net::UnixListener::bind(&path)
+        .with_context(|| format!("failed to bind unix socket `{}`", path.display()))?;
+    info!(path = %path.display(), "starting HTTP server on unix socket");
+
+    let builder = auto::Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+
+    loop {
+        let stream = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!(error = %e, "failed to accept unix socket connection");
+                    continue;
+                }
+            },
+        };
+
+        let service = TowerToHyperService::new(app.clone());
+        let connection = builder
+            .serve_connection_with_upgrades(TokioIo::new(stream), service)
+            .into_owned();
+        let connection = graceful.watch(connection);
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                debug!(error = %e, "unix socket connection closed with error");
+            }
+        });
+    }
+
+    drop(listener);
+    graceful.shutdown().await;
+    let _ = std::fs::remove_file(&path);
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn serve_unix(
+    _app: Router,
+    path: PathBuf,
+    _shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    anyhow::bail!(
+        "unix sockets are not supported on this platform (`{}`)",
+        path.display()
+    )
+}
+
+/// A socket left behind by a previous run would make `bind` fail. Only
+/// sockets are removed; any other file at the path is an error.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)
+            .with_context(|| format!("failed to remove stale socket `{}`", path.display())),
+        Ok(_) => anyhow::bail!("`{}` exists and is not a unix socket", path.display()),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_bind_addr_accepts_ipv4_and_ipv6() {
+        let any = resolve_bind_addr("0.0.0.0", 3000).await.unwrap();
+        assert_eq!(any, "0.0.0.0:3000".parse().unwrap());
+
+        let v6 = resolve_bind_addr("::", 3000).await.unwrap();
+        assert_eq!(v6, "[::]:3000".parse().unwrap());
+
+        let bracketed = resolve_bind_addr("[::1]", 8443).await.unwrap();
+        assert_eq!(bracketed, "[::1]:8443".parse().unwrap());
+    }
+}
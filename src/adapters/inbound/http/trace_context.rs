@@ -0,0 +1,141 @@
+use axum::{extract::Request, http::HeaderMap};
+use opentelemetry::{
+    baggage::{Baggage, BaggageExt},
+    global,
+    propagation::Extractor,
+    Context,
+};
+use tracing::{field::Empty, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::domain::TenantId;
+
+use super::request_id::REQUEST_ID_HEADER;
+
+/// Baggage key carrying the authenticated tenant to downstream services.
+pub const TENANT_BAGGAGE_KEY: &str = "tenant.id";
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Reads `traceparent`, `tracestate` and `baggage` with the global propagator.
+pub fn extract_context(headers: &HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+/// `MakeSpan` for `TraceLayer`: the same fields as tower-http's default span,
+/// continued from the caller's trace when the request carries one, and
+/// tagged with the id set by `RequestIdLayer` so every log line inside the
+/// request carries it. The tenant is filled in by [`attach_tenant`] once the caller authenticates,
+/// and the error fields by `ApiError::into_response`.
+pub fn make_request_span(request: &Request) -> Span {
+    let span = tracing::info_span!(
+        "request",
+        otel.kind = "server",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+        url.path = request.uri().path(),
+        request_id = request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok()),
+        tenant.id = Empty,
+        http.response.status_code = Empty,
+        error.type = Empty,
+        otel.status_message = Empty,
+    );
+    span.set_parent(with_tenant_baggage(
+        &extract_context(request.headers()),
+        None,
+    ));
+    span
+}
+
+/// Replaces any `tenant.id` the caller sent in its baggage with the tenant
+/// the request authenticated as. Baggage arrives from the client, so an
+/// unauthenticated value is dropped rather than forwarded.
+pub fn with_tenant_baggage(cx: &Context, tenant_id: Option<&TenantId>) -> Context {
+    let mut baggage: Baggage = cx
+        .baggage()
+        .iter()
+        .filter(|(key, _)| key.as_str() != TENANT_BAGGAGE_KEY)
+        .map(|(key, entry)| (key.clone(), entry.clone()))
+        .collect();
+    if let Some(tenant_id) = tenant_id {
+        baggage.insert(TENANT_BAGGAGE_KEY, tenant_id.to_string());
+    }
+    cx.with_value(baggage)
+}
+
+/// Called once the caller is authenticated: records the tenant on the
+/// request span and re-parents it on the remote context with the tenant in
+/// its baggage, so spans started from here on, and the outbound calls they
+/// make, carry it.
+pub fn attach_tenant(headers: &HeaderMap, tenant_id: Option<&TenantId>) {
+    let span = Span::current();
+    if let Some(tenant_id) = tenant_id {
+        span.record("tenant.id", tracing::field::display(tenant_id));
+    }
+    span.set_parent(with_tenant_baggage(&extract_context(headers), tenant_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry_sdk::propagation::{
+        BaggagePropagator, TextMapCompositePropagator, TraceContextPropagator,
+    };
+
+    #[test]
+    fn test_extracts_remote_parent_and_replaces_tenant_baggage() {
+        global::set_text_map_propagator(TextMapCompositePropagator::new(vec![
+            Box::new(TraceContextPropagator::new()),
+            Box::new(BaggagePropagator::new()),
+        ]));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        headers.insert(
+            "baggage",
+            HeaderValue::from_static("tenant.id=tenant_spoofed,region=eu"),
+        );
+
+        let cx = extract_context(&headers);
+        let remote = cx.span().span_context().clone();
+        assert!(remote.is_remote());
+        assert_eq!(
+            remote.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+
+        let tenant = TenantId::from("tenant_acme");
+        let cx = with_tenant_baggage(&cx, Some(&tenant));
+        assert_eq!(
+            cx.baggage().get(TENANT_BAGGAGE_KEY).map(|v| v.to_string()),
+            Some("tenant_acme".to_string())
+        );
+        assert_eq!(
+            cx.baggage().get("region").map(|v| v.to_string()),
+            Some("eu".to_string())
+        );
+        assert_eq!(cx.span().span_context().trace_id(), remote.trace_id());
+
+        let cx = with_tenant_baggage(&cx, None);
+        assert!(cx.baggage().get(TENANT_BAGGAGE_KEY).is_none());
+    }
+}
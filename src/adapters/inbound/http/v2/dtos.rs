@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::{AuditEntry, Subscription};
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = v2::PlanReference)]
+pub struct PlanReference {
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = v2::SubscriptionResponse)]
+pub struct SubscriptionResponse {
+    pub id: String,
+    pub tenant_id: String,
+    pub plan: PlanReference,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Subscription> for SubscriptionResponse {
+    fn from(s: Subscription) -> Self {
+        Self {
+            id: s.id.as_ref().to_string(),
+            tenant_id: s.tenant_id.as_ref().to_string(),
+            plan: PlanReference {
+                id: s.plan_id.as_ref().to_string(),
+            },
+            created_at: s.created_at,
+            updated_at: s.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = v2::AuditEntryResponse)]
+pub struct AuditEntryResponse {
+    pub id: String,
+    pub actor: String,
+    pub action: String,
+    pub before: Option<SubscriptionResponse>,
+    pub after: Option<SubscriptionResponse>,
+    pub request_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(e: AuditEntry) -> Self {
+        Self {
+            id: e.id,
+            actor: e.actor,
+            action: e.action.as_str().to_string(),
+            before: e.before.map(Into::into),
+            after: e.after.map(Into::into),
+            request_id: e.request_id,
+            occurred_at: e.occurred_at,
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::instrument;
+
+use crate::domain::RequestContext;
+use crate::ports::{
+    AuditLog, BillingProfileRepository, BusinessEventLog, PlanRepository,
+    SubscriptionEventPublisher, SubscriptionRepository,
+};
+
+use super::super::dtos::{
+    ChangeSubscriptionPlanHttpBody, CreateSubscriptionHttpBody, ListSubscriptionsQuery,
+};
+use super::super::errors::ApiError;
+use super::super::extractors::{ApiJson, ApiQuery};
+use super::super::handlers::{self, AppState};
+use super::super::problem::ProblemDetails;
+use super::dtos::{AuditEntryResponse, SubscriptionResponse};
+
+#[utoipa::path(
+    post,
+    path = "/subscriptions",
+    tag = "subscriptions",
+    request_body = CreateSubscriptionHttpBody,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the stored response when retried with the same body"),
+    ),
+    responses(
+        (status = 201, description = "Subscription created", body = SubscriptionResponse),
+        (status = 400, description = "Body is not valid JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:create` for the tenant, or the tenant may not use the plan", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Plan not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "A request with the same Idempotency-Key is still in progress", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Body was not sent as application/json", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid fields, missing payment method, or Idempotency-Key reused with a different body", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "v2::create_subscription_handler",
+    skip(state, context, body),
+    fields(
+        tenant_id = %body.tenant_id,
+        plan_id = %body.plan_id,
+    )
+)]
+pub async fn create_subscription_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<CreateSubscriptionHttpBody>,
+) -> Result<(StatusCode, Json<SubscriptionResponse>), ApiError>
+where
+    P: PlanRepository + 'static,
+    B: BillingProfileRepository + 'static,
+    S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
+{
+    let subscription = handlers::create_subscription(&state, &context, body).await?;
+
+    Ok((StatusCode::CREATED, Json(subscription.into())))
+}
+
+#[utoipa::path(
+    put,
+    path = "/subscriptions/{subscription_id}/plan",
+    tag = "subscriptions",
+    params(("subscription_id" = String, Path, description = "Subscription id")),
+    request_body = ChangeSubscriptionPlanHttpBody,
+    responses(
+        (status = 200, description = "Plan changed", body = SubscriptionResponse),
+        (status = 400, description = "Body is not valid JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:change_plan` for the tenant, or the tenant may not use the new plan", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Subscription or plan not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Another request changed the subscription's plan first", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Body was not sent as application/json", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid fields or missing payment method", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "v2::change_subscription_plan_handler",
+    skip(state, context, body),
+    fields(
+        subscription_id = %subscription_id,
+        plan_id = %body.plan_id,
+    )
+)]
+pub async fn change_subscription_plan_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    Path(subscription_id): Path<String>,
+    context: RequestContext,
+    ApiJson(body): ApiJson<ChangeSubscriptionPlanHttpBody>,
+) -> Result<Json<SubscriptionResponse>, ApiError>
+where
+    P: PlanRepository + 'static,
+    B: BillingProfileRepository + 'static,
+    S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
+{
+    let subscription =
+        handlers::change_subscription_plan(&state, &context, subscription_id, body).await?;
+
+    Ok(Json(subscription.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tenants/{tenant_id}/subscriptions",
+    tag = "subscriptions",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant id"),
+        ListSubscriptionsQuery,
+    ),
+    responses(
+        (status = 200, description = "Subscriptions of the tenant", body = Vec<SubscriptionResponse>),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:list` for the tenant", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid tenant id or query", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 501, description = "Point-in-time queries require the event-sourced store", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "v2::list_subscriptions_handler",
+    skip(state, context, query),
+    fields(tenant_id = %tenant_id, as_of = ?query.as_of)
+)]
+pub async fn list_subscriptions_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    Path(tenant_id): Path<String>,
+    context: RequestContext,
+    ApiQuery(query): ApiQuery<ListSubscriptionsQuery>,
+) -> Result<Json<Vec<SubscriptionResponse>>, ApiError>
+where
+    P: PlanRepository + 'static,
+    B: BillingProfileRepository + 'static,
+    S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
+{
+    let subscriptions = handlers::list_subscriptions(&state, &context, tenant_id, query).await?;
+
+    Ok(Json(subscriptions.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/subscriptions/{subscription_id}/history",
+    tag = "subscriptions",
+    params(("subscription_id" = String, Path, description = "Subscription id")),
+    responses(
+        (status = 200, description = "Audit trail of the subscription", body = Vec<AuditEntryResponse>),
+        (status = 401, description = "Missing or invalid credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Caller lacks `subscriptions:history` for the subscription's tenant", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Subscription not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid subscription id", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 429, description = "Rate limit exceeded; see Retry-After", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Unexpected error", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+#[instrument(
+    name = "v2::subscription_history_handler",
+    skip(state, context),
+    fields(subscription_id = %subscription_id)
+)]
+pub async fn subscription_history_handler<P, B, S, E, A, L>(
+    State(state): State<AppState<P, B, S, E, A, L>>,
+    Path(subscription_id): Path<String>,
+    context: RequestContext,
+) -> Result<Json<Vec<AuditEntryResponse>>, ApiError>
+where
+    P: PlanRepository + 'static,
+    B: BillingProfileRepository + 'static,
+    S: SubscriptionRepository + 'static,
+    E: SubscriptionEventPublisher + 'static,
+    A: AuditLog + 'static,
+    L: BusinessEventLog + 'static,
+{
+    let history = handlers::subscription_history(&state, &context, subscription_id).await?;
+
+    Ok(Json(history.into_iter().map(Into::into).collect()))
+}
@@ -0,0 +1,11 @@
+//! Version 2 of the subscription resources. Requests are unchanged from v1;
+//! responses nest the plan and expose `updated_at`. Routes that did not
+//! change between versions reuse the v1 handlers.
+
+pub mod dtos;
+pub mod handlers;
+
+pub use handlers::{
+    change_subscription_plan_handler, create_subscription_handler, list_subscriptions_handler,
+    subscription_history_handler,
+};
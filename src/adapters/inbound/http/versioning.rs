@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+pub const DEPRECATION_HEADER: &str = "deprecation";
+pub const SUNSET_HEADER: &str = "sunset";
+pub const LINK_HEADER: &str = "link";
+
+/// Announces that a version is on its way out: `Deprecation` (RFC 9745) once a
+/// deprecation date is set, `Sunset` (RFC 8594) once a removal date is set, and a
+/// `successor-version` link to the version clients should move to.
+#[derive(Debug, Clone)]
+pub struct DeprecationPolicy {
+    pub deprecated_at: Option<DateTime<Utc>>,
+    pub sunset_at: Option<DateTime<Utc>>,
+    pub successor: String,
+}
+
+impl DeprecationPolicy {
+    pub fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        let mut headers = Vec::with_capacity(3);
+
+        if let Some(deprecated_at) = self.deprecated_at {
+            let deprecation = format!("@{}", deprecated_at.timestamp());
+            if let Ok(value) = HeaderValue::from_str(&deprecation) {
+                headers.push((HeaderName::from_static(DEPRECATION_HEADER), value));
+            }
+        }
+
+        if let Some(sunset_at) = self.sunset_at {
+            let sunset = sunset_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+            if let Ok(value) = HeaderValue::from_str(&sunset) {
+                headers.push((HeaderName::from_static(SUNSET_HEADER), value));
+            }
+        }
+
+        let link = format!("<{}>; rel=\"successor-version\"", self.successor);
+        if let Ok(value) = HeaderValue::from_str(&link) {
+            headers.push((HeaderName::from_static(LINK_HEADER), value));
+        }
+
+        headers
+    }
+}
+
+pub async fn deprecation_middleware(
+    State(policy): State<Arc<DeprecationPolicy>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+
+    for (name, value) in policy.headers() {
+        response.headers_mut().insert(name, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_deprecation_headers_use_structured_and_http_dates() {
+        let policy = DeprecationPolicy {
+            deprecated_at: Some(Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap()),
+            sunset_at: Some(Utc.with_ymd_and_hms(2027, 4, 30, 0, 0, 0).unwrap()),
+            successor: "/api/v2".to_string(),
+        };
+
+        let headers = policy.headers();
+
+        assert_eq!(headers[0].1, "@1792281600");
+        assert_eq!(headers[1].1, "Fri, 30 Apr 2027 00:00:00 GMT");
+        assert_eq!(headers[2].1, "</api/v2>; rel=\"successor-version\"");
+    }
+
+    #[test]
+    fn test_no_deprecation_header_without_a_date() {
+        let policy = DeprecationPolicy {
+            deprecated_at: None,
+            sunset_at: None,
+            successor: "/api/v2".to_string(),
+        };
+
+        let names: Vec<_> = policy.headers().into_iter().map(|(name, _)| name).collect();
+
+        assert_eq!(names, [LINK_HEADER]);
+    }
+}
@@ -0,0 +1,386 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{error, instrument, warn};
+
+use crate::domain::RecordedBusinessEvent;
+use crate::ports::BusinessEventChain;
+
+/// `prev` of the first line in a business event log.
+const CHAIN_START: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+const HASH_FIELD: &str = ",\"hash\":\"";
+
+#[derive(Debug, thiserror::Error)]
+pub enum BusinessEventError {
+    #[error("line {line}: not a business event ({reason})")]
+    Malformed { line: u64, reason: String },
+
+    #[error("line {line}: expected seq {expected}, found {found}")]
+    Gap {
+        line: u64,
+        expected: u64,
+        found: u64,
+    },
+
+    #[error("line {line}: prev does not match the hash of the line before")]
+    Unlinked { line: u64 },
+
+    #[error("line {line}: hash does not match the line's contents")]
+    Tampered { line: u64 },
+
+    #[error("line {line}: written only partially, with no line end")]
+    Torn { line: u64 },
+
+    #[error("failed to read business event log: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One line of the business event log, without its `hash`.
+#[derive(Serialize, Deserialize)]
+struct ChainedEvent<E> {
+    seq: u64,
+    ts: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+    /// Id of the event in the outbox it was copied from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    outbox_id: Option<i64>,
+    event: E,
+    prev: String,
+}
+
+/// The last line of a verified chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainHead {
+    pub seq: u64,
+    pub hash: String,
+    /// Highest outbox id in the chain.
+    pub outbox_id: Option<i64>,
+}
+
+impl Default for ChainHead {
+    fn default() -> Self {
+        Self {
+            seq: 0,
+            hash: CHAIN_START.to_string(),
+            outbox_id: None,
+        }
+    }
+}
+
+/// SHA-256 of the line up to its `hash` field, or an HMAC when a key is set
+/// so the chain cannot be rebuilt after an edit without it.
+fn chain_hash(key: Option<&[u8]>, body: &str) -> String {
+    match key {
+        Some(key) => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+            mac.update(body.as_bytes());
+            hex::encode(mac.finalize().into_bytes())
+        }
+        None => hex::encode(Sha256::digest(body.as_bytes())),
+    }
+}
+
+/// Checks every line of a business event log: consecutive `seq` from 1,
+/// `prev` equal to the previous line's `hash`, and `hash` matching the line.
+/// Lines removed from the end are not detectable here; compare the returned
+/// head with one recorded elsewhere for that.
+pub fn verify_business_events(
+    path: &Path,
+    key: Option<&str>,
+) -> Result<ChainHead, BusinessEventError> {
+    let key = key.map(str::as_bytes);
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut head = ChainHead::default();
+    let mut buffer = Vec::new();
+    let mut number = 0;
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            return Ok(head);
+        }
+        number += 1;
+        let malformed = |reason: &str| BusinessEventError::Malformed {
+            line: number,
+            reason: reason.to_string(),
+        };
+
+        let Some(line) = buffer.strip_suffix(b"\n") else {
+            return Err(BusinessEventError::Torn { line: number });
+        };
+        let line = std::str::from_utf8(line).map_err(|_| malformed("not UTF-8"))?;
+
+        // The hash covers the exact bytes before it, so the line is split
+        // rather than parsed and re-serialized.
+        let split = line.rfind(HASH_FIELD).ok_or_else(|| malformed("no hash"))?;
+        let hash = line[split + HASH_FIELD.len()..]
+            .strip_suffix("\"}")
+            .ok_or_else(|| malformed("hash is not the last field"))?;
+        let body = format!("{}}}", &line[..split]);
+        let entry: ChainedEvent<serde::de::IgnoredAny> =
+            serde_json::from_str(&body).map_err(|e| malformed(&e.to_string()))?;
+
+        if entry.seq != head.seq + 1 {
+            return Err(BusinessEventError::Gap {
+                line: number,
+                expected: head.seq + 1,
+                found: entry.seq,
+            });
+        }
+        if entry.prev != head.hash {
+            return Err(BusinessEventError::Unlinked { line: number });
+        }
+        if chain_hash(key, &body) != hash {
+            return Err(BusinessEventError::Tampered { line: number });
+        }
+        head = ChainHead {
+            seq: entry.seq,
+            hash: hash.to_string(),
+            outbox_id: entry.outbox_id.max(head.outbox_id),
+        };
+    }
+}
+
+/// Moves a final line without a line end, left by a crash mid-write, to
+/// `<path>.torn` and cuts it from the log. It was never acknowledged, so the
+/// chain continues from the last complete line.
+fn set_aside_torn_tail(path: &Path, file: &mut File) -> anyhow::Result<()> {
+    let mut contents = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut contents)?;
+    if contents.is_empty() || contents.ends_with(b"\n") {
+        return Ok(());
+    }
+
+    let complete = contents
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let torn_path = PathBuf::from(format!("{}.torn", path.display()));
+    let mut torn = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&torn_path)?;
+    torn.write_all(&contents[complete..])?;
+    torn.write_all(b"\n")?;
+    torn.sync_data()?;
+
+    file.set_len(complete as u64)?;
+    file.sync_data()?;
+    warn!(
+        path = %path.display(),
+        torn_path = %torn_path.display(),
+        bytes = contents.len() - complete,
+        "set aside partially written business event"
+    );
+    Ok(())
+}
+
+struct ChainState {
+    file: File,
+    /// Length of the log up to its last complete line.
+    len: u64,
+    head: ChainHead,
+}
+
+impl ChainState {
+    fn append(&mut self, key: Option<&[u8]>, event: &RecordedBusinessEvent) -> anyhow::Result<()> {
+        let entry = ChainedEvent {
+            seq: self.head.seq + 1,
+            ts: event.recorded_at,
+            request_id: event.request_id.clone(),
+            outbox_id: Some(event.outbox_id),
+            event: &event.event,
+            prev: self.head.hash.clone(),
+        };
+        let body = serde_json::to_string(&entry)?;
+        let hash = chain_hash(key, &body);
+        let line = format!("{}{}{}\"}}\n", &body[..body.len() - 1], HASH_FIELD, hash);
+
+        if let Err(e) = self
+            .file
+            .write_all(line.as_bytes())
+            .and_then(|_| self.file.sync_data())
+        {
+            // Drop whatever part of the line made it out, so the next append
+            // does not follow a broken line.
+            let _ = self.file.set_len(self.len);
+            return Err(e.into());
+        }
+
+        self.len += line.len() as u64;
+        self.head = ChainHead {
+            seq: entry.seq,
+            hash,
+            outbox_id: entry.outbox_id.max(self.head.outbox_id),
+        };
+        Ok(())
+    }
+}
+
+/// Append-only JSON lines of business events, apart from the diagnostic logs:
+/// no filter, sampling, redaction or rotation applies. Each line carries
+/// `seq`, the `prev` line's hash and its own `hash`, and is synced to disk
+/// before `append` returns. Events reach it from the outbox through
+/// `BusinessEventRelay`, and each line keeps the `outbox_id` it came from.
+#[derive(Clone)]
+pub struct FileBusinessEventLog {
+    key: Option<Arc<[u8]>>,
+    state: Arc<Mutex<ChainState>>,
+}
+
+impl FileBusinessEventLog {
+    /// Continues the chain in `path`. A partially written last line is set
+    /// aside (see [`set_aside_torn_tail`]); any other break fails, so it stays
+    /// visible instead of being built over.
+    pub fn open(path: &Path, key: Option<&str>) -> anyhow::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        set_aside_torn_tail(path, &mut file)?;
+        let head = verify_business_events(path, key)
+            .context("refusing to append to a business event log that fails verification")?;
+        let len = file.metadata()?.len();
+
+        Ok(Self {
+            key: key.map(|key| Arc::from(key.as_bytes())),
+            state: Arc::new(Mutex::new(ChainState { file, len, head })),
+        })
+    }
+}
+
+impl BusinessEventChain for FileBusinessEventLog {
+    async fn last_outbox_id(&self) -> Result<Option<i64>, anyhow::Error> {
+        let state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("business event log lock poisoned"))?;
+        Ok(state.head.outbox_id)
+    }
+
+    #[instrument(
+        name = "append_business_event",
+        skip(self, event),
+        fields(event_type = event.event.event_type(), outbox_id = event.outbox_id)
+    )]
+    async fn append(&self, event: &RecordedBusinessEvent) -> Result<(), anyhow::Error> {
+        let (state, key, event) = (self.state.clone(), self.key.clone(), event.clone());
+
+        tokio::task::spawn_blocking(move || {
+            let mut state = state
+                .lock()
+                .map_err(|_| anyhow::anyhow!("business event log lock poisoned"))?;
+            state.append(key.as_deref(), &event)
+        })
+        .await
+        .context("business event append task failed")?
+        .context("failed to append business event")
+        .inspect_err(|e| {
+            error!(error = %e, "business event append failed");
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{PaymentEvent, PlanId, TenantId};
+
+    fn payment(outbox_id: i64, plan: &str) -> RecordedBusinessEvent {
+        RecordedBusinessEvent {
+            outbox_id,
+            event: PaymentEvent::checked(&TenantId::new("tenant_1"), &PlanId::new(plan), true)
+                .into(),
+            request_id: Some("req_123".to_string()),
+            recorded_at: Utc::now(),
+        }
+    }
+
+    fn temp_log() -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("business-events-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("events.jsonl");
+        (dir, path)
+    }
+
+    #[tokio::test]
+    async fn test_chain_detects_edits_and_gaps() {
+        let (dir, path) = temp_log();
+        let key = Some("finance-key");
+
+        let log = FileBusinessEventLog::open(&path, key).unwrap();
+        log.append(&payment(1, "basic")).await.unwrap();
+        log.append(&payment(2, "pro")).await.unwrap();
+        drop(log);
+        // Reopening continues the chain rather than starting a new one.
+        let log = FileBusinessEventLog::open(&path, key).unwrap();
+        assert_eq!(log.last_outbox_id().await.unwrap(), Some(2));
+        log.append(&payment(3, "enterprise")).await.unwrap();
+        drop(log);
+        let head = verify_business_events(&path, key).unwrap();
+        assert_eq!((head.seq, head.outbox_id), (3, Some(3)));
+
+        let original = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = original.lines().collect();
+
+        fs::write(&path, original.replace("\"pro\"", "\"free\"")).unwrap();
+        assert!(matches!(
+            verify_business_events(&path, key),
+            Err(BusinessEventError::Tampered { line: 2 })
+        ));
+        assert!(matches!(
+            verify_business_events(&path, None),
+            Err(BusinessEventError::Tampered { line: 1 })
+        ));
+
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(matches!(
+            verify_business_events(&path, key),
+            Err(BusinessEventError::Gap {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        ));
+        assert!(FileBusinessEventLog::open(&path, key).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_torn_last_line_is_set_aside_on_open() {
+        let (dir, path) = temp_log();
+
+        let log = FileBusinessEventLog::open(&path, None).unwrap();
+        log.append(&payment(1, "basic")).await.unwrap();
+        drop(log);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"seq":2,"ts":"2026-"#).unwrap();
+
+        assert!(matches!(
+            verify_business_events(&path, None),
+            Err(BusinessEventError::Torn { line: 2 })
+        ));
+
+        let log = FileBusinessEventLog::open(&path, None).unwrap();
+        log.append(&payment(2, "pro")).await.unwrap();
+
+        assert_eq!(verify_business_events(&path, None).unwrap().seq, 2);
+        let torn = fs::read_to_string(dir.join("events.jsonl.torn")).unwrap();
+        assert_eq!(torn, "{\"seq\":2,\"ts\":\"2026-\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
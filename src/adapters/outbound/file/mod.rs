@@ -0,0 +1,3 @@
+pub mod business_event_log;
+
+pub use business_event_log::{verify_business_events, FileBusinessEventLog};
@@ -0,0 +1,357 @@
+use anyhow::Context;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, instrument, warn};
+
+use crate::ports::{TokenClaims, TokenVerifier};
+
+#[derive(Debug, Clone)]
+pub struct JwtConfig {
+    pub jwks_path: PathBuf,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    pub tenant_claim: String,
+    pub roles_claim: String,
+    pub leeway_secs: u64,
+}
+
+struct VerificationKey {
+    key: DecodingKey,
+    algorithm: Option<Algorithm>,
+}
+
+#[derive(Default)]
+struct KeyCache {
+    keys: HashMap<String, VerificationKey>,
+    /// Keys without a `kid`; only usable when the JWKS holds exactly one key.
+    anonymous: Option<VerificationKey>,
+    modified: Option<SystemTime>,
+}
+
+/// Verifies bearer tokens against a JWKS document on the local filesystem.
+/// The file is re-read whenever its modification time changes, so keys can be
+/// rotated by replacing the file without restarting the service.
+#[derive(Clone)]
+pub struct JwksTokenVerifier {
+    config: Arc<JwtConfig>,
+    cache: Arc<RwLock<KeyCache>>,
+}
+
+impl JwksTokenVerifier {
+    pub fn new(config: JwtConfig) -> Result<Self, anyhow::Error> {
+        let verifier = Self {
+            config: Arc::new(config),
+            cache: Arc::new(RwLock::new(KeyCache::default())),
+        };
+        verifier.reload()?;
+
+        Ok(verifier)
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.config.jwks_path)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    /// Reads the JWKS file and swaps the key set. On failure the previous keys
+    /// stay active.
+    #[instrument(name = "reload_jwks", skip(self), fields(path = %self.config.jwks_path.display()))]
+    pub fn reload(&self) -> Result<usize, anyhow::Error> {
+        let modified = self.modified();
+        let contents = std::fs::read_to_string(&self.config.jwks_path)
+            .with_context(|| {
+                format!(
+                    "failed to read JWKS file {}",
+                    self.config.jwks_path.display()
+                )
+            })
+            .inspect_err(|e| error!(error = %e, "jwks reload failed"))?;
+
+        let jwks: JwkSet = serde_json::from_str(&contents)
+            .context("failed to parse JWKS file")
+            .inspect_err(|e| error!(error = %e, "jwks reload failed"))?;
+
+        let mut cache = KeyCache {
+            modified,
+            ..KeyCache::default()
+        };
+        let single_key = jwks.keys.len() == 1;
+
+        for jwk in &jwks.keys {
+            let key = match DecodingKey::from_jwk(jwk) {
+                Ok(key) => key,
+                Err(e) => {
+                    warn!(error = %e, kid = ?jwk.common.key_id, "skipping unusable jwk");
+                    continue;
+                }
+            };
+            let algorithm = jwk
+                .common
+                .key_algorithm
+                .and_then(|alg| Algorithm::from_str(&format!("{:?}", alg)).ok());
+            let key = VerificationKey { key, algorithm };
+
+            match &jwk.common.key_id {
+                Some(kid) => {
+                    cache.keys.insert(kid.clone(), key);
+                }
+                None if single_key => cache.anonymous = Some(key),
+                None => warn!("skipping jwk without kid in a multi-key set"),
+            }
+        }
+
+        let loaded = cache.keys.len() + usize::from(cache.anonymous.is_some());
+        *self.cache.write().expect("jwks cache lock poisoned") = cache;
+        info!(keys = loaded, "jwks loaded");
+
+        Ok(loaded)
+    }
+
+    /// Polls the JWKS file and reloads it when it changes. Runs until
+    /// `shutdown` is cancelled.
+    pub async fn watch(&self, interval: Duration, shutdown: CancellationToken) {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = ticker.tick() => {}
+            }
+
+            let current = self.modified();
+            let known = self
+                .cache
+                .read()
+                .expect("jwks cache lock poisoned")
+                .modified;
+
+            if current.is_some() && current != known {
+                let _ = self.reload();
+            }
+        }
+    }
+
+    fn claims_from(&self, subject: String, claims: &Map<String, Value>) -> TokenClaims {
+        let tenant_id = claims
+            .get(&self.config.tenant_claim)
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        let roles = match claims.get(&self.config.roles_claim) {
+            Some(Value::Array(values)) => values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+            Some(Value::String(value)) => value.split_whitespace().map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+
+        TokenClaims {
+            subject,
+            tenant_id,
+            roles,
+        }
+    }
+}
+
+impl TokenVerifier for JwksTokenVerifier {
+    #[instrument(name = "verify_jwt", skip(self, token))]
+    async fn verify(&self, token: &str) -> Result<Option<TokenClaims>, anyhow::Error> {
+        let header = match decode_header(token) {
+            Ok(header) => header,
+            Err(e) => {
+                warn!(error = %e, "malformed bearer token");
+                return Ok(None);
+            }
+        };
+
+        let cache = self.cache.read().expect("jwks cache lock poisoned");
+        let key = match &header.kid {
+            Some(kid) => cache.keys.get(kid),
+            None => cache.anonymous.as_ref(),
+        };
+
+        let Some(key) = key else {
+            warn!(kid = ?header.kid, "bearer token signed with unknown key");
+            return Ok(None);
+        };
+
+        if key.algorithm.is_some_and(|alg| alg != header.alg) {
+            warn!(alg = ?header.alg, "bearer token algorithm does not match key");
+            return Ok(None);
+        }
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = self.config.leeway_secs;
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        match &self.config.issuer {
+            Some(issuer) => validation.set_issuer(&[issuer]),
+            None => validation.iss = None,
+        }
+        match &self.config.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let data = match decode::<Map<String, Value>>(token, &key.key, &validation) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(error = %e, "bearer token rejected");
+                return Ok(None);
+            }
+        };
+
+        let subject = data
+            .claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(Some(self.claims_from(subject, &data.claims)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outbound::sqlite::SqliteApiKeyRepository;
+    use crate::domain::{Principal, Role, TenantId};
+    use crate::services::AuthService;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+    use sqlx::SqlitePool;
+
+    const SECRET: &str = "c2VjcmV0LWZvci10ZXN0cy1vbmx5";
+
+    /// A directory for a test's JWKS file, removed when the test ends.
+    struct TempJwks {
+        dir: PathBuf,
+    }
+
+    impl TempJwks {
+        fn new(kid: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("jwks-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let jwks = Self { dir };
+            write_jwks(&jwks.path(), kid);
+            jwks
+        }
+
+        fn path(&self) -> PathBuf {
+            self.dir.join("jwks.json")
+        }
+    }
+
+    impl Drop for TempJwks {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn write_jwks(path: &std::path::Path, kid: &str) {
+        let jwks = json!({
+            "keys": [{ "kty": "oct", "kid": kid, "alg": "HS256", "k": SECRET }]
+        });
+        std::fs::write(path, jwks.to_string()).unwrap();
+    }
+
+    fn token(kid: &str, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(kid.to_string());
+        let secret = b"secret-for-tests-only";
+        encode(&header, &claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    fn verifier(path: PathBuf) -> JwksTokenVerifier {
+        JwksTokenVerifier::new(JwtConfig {
+            jwks_path: path,
+            issuer: Some("https://auth.ledgercloud.test".to_string()),
+            audience: Some("ledgercloud-api".to_string()),
+            tenant_claim: "tenant_id".to_string(),
+            roles_claim: "roles".to_string(),
+            leeway_secs: 0,
+        })
+        .unwrap()
+    }
+
+    fn claims() -> Value {
+        json!({
+            "sub": "user_42",
+            "iss": "https://auth.ledgercloud.test",
+            "aud": "ledgercloud-api",
+            "exp": chrono::Utc::now().timestamp() + 300,
+            "tenant_id": "tenant_with_payment",
+            "roles": ["tenant_admin"]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_maps_claims() {
+        let jwks = TempJwks::new("key-1");
+
+        let verified = verifier(jwks.path())
+            .verify(&token("key-1", claims()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(verified.subject, "user_42");
+        assert_eq!(verified.tenant_id.as_deref(), Some("tenant_with_payment"));
+        assert_eq!(verified.roles, vec!["tenant_admin".to_string()]);
+
+        // Bearer tokens never reach the key store, so it is not connected.
+        let api_keys =
+            SqliteApiKeyRepository::new(SqlitePool::connect_lazy("sqlite::memory:").unwrap());
+        let principal = AuthService::new(api_keys, Some(verifier(jwks.path())))
+            .authenticate_bearer_token(&token("key-1", claims()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            principal,
+            Principal::new(
+                "jwt:user_42",
+                Some(TenantId::new("tenant_with_payment")),
+                vec![Role::TenantAdmin],
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_audience_and_rotated_key_are_rejected() {
+        let jwks = TempJwks::new("key-1");
+        let path = jwks.path();
+        let verifier = verifier(path.clone());
+
+        let mut wrong_audience = claims();
+        wrong_audience["aud"] = json!("another-api");
+        assert!(verifier
+            .verify(&token("key-1", wrong_audience))
+            .await
+            .unwrap()
+            .is_none());
+
+        write_jwks(&path, "key-2");
+        verifier.reload().unwrap();
+
+        assert!(verifier
+            .verify(&token("key-1", claims()))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(verifier
+            .verify(&token("key-2", claims()))
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod jwks_verifier;
+
+pub use jwks_verifier::{JwksTokenVerifier, JwtConfig};
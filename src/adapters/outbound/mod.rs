@@ -1,2 +1,6 @@
+pub mod file;
+pub mod jwt;
 pub mod payment;
 pub mod sqlite;
+pub mod trace_context;
+pub mod webhook;
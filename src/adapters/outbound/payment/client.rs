@@ -1,8 +1,16 @@
 #![allow(dead_code)]
 
 use anyhow::Context;
+use opentelemetry::KeyValue;
 use serde::Serialize;
-use tracing::{error, instrument};
+use std::time::{Duration, Instant};
+use tracing::{error, instrument, warn};
+
+use crate::adapters::outbound::trace_context::InjectTraceContext;
+use crate::metrics;
+use crate::ports::{HealthProbe, ProbeReport, ProbeStatus};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(Serialize)]
 struct CreateCustomerRequest<'a> {
@@ -15,6 +23,7 @@ struct AddPaymentMethodRequest<'a> {
     token: &'a str,
 }
 
+#[derive(Clone)]
 pub struct PaymentClient {
     http: reqwest::Client,
     base_url: String,
@@ -44,13 +53,18 @@ impl PaymentClient {
 
         let request = CreateCustomerRequest { email };
 
+        let started = Instant::now();
         let response = self
             .http
             .post(&url)
             .bearer_auth(&self.api_key)
             .json(&request)
+            .inject_trace_context()
             .send()
-            .await
+            .await;
+        record_call("create_customer", started, &response);
+
+        let response = response
             .context("failed to call payment provider /customers endpoint")
             .inspect_err(|e| {
                 error!(error = %e, email = %email, "payment provider /customers request failed");
@@ -103,13 +117,18 @@ impl PaymentClient {
             token: payment_token,
         };
 
+        let started = Instant::now();
         let response = self
             .http
             .post(&url)
             .bearer_auth(&self.api_key)
             .json(&request)
+            .inject_trace_context()
             .send()
-            .await
+            .await;
+        record_call("add_payment_method", started, &response);
+
+        let response = response
             .context("failed to call payment provider /payment_methods endpoint")
             .inspect_err(|e| {
                 error!(error = %e, customer_id = %customer_id, "payment provider /payment_methods request failed");
@@ -141,3 +160,57 @@ impl PaymentClient {
         Ok(id.to_string())
     }
 }
+
+/// Records the call latency, labelled with the operation and whether the
+/// provider answered with a success status, an error status or not at all.
+fn record_call(
+    operation: &'static str,
+    started: Instant,
+    response: &Result<reqwest::Response, reqwest::Error>,
+) {
+    let outcome = match response {
+        Ok(response) if response.status().is_success() => "success",
+        Ok(_) => "http_error",
+        Err(_) => "transport_error",
+    };
+    metrics::instruments().payment_request_duration.record(
+        started.elapsed().as_secs_f64(),
+        &[
+            KeyValue::new("operation", operation),
+            KeyValue::new("outcome", outcome),
+        ],
+    );
+}
+
+/// Reachability only: any HTTP response means the provider can be reached,
+/// while connection errors and timeouts mean it cannot.
+impl HealthProbe for PaymentClient {
+    #[instrument(
+        name = "probe_payment_provider",
+        skip(self),
+        fields(http.method = "HEAD", http.url = %self.base_url)
+    )]
+    async fn probe(&self) -> ProbeReport {
+        let started = Instant::now();
+        let result = self
+            .http
+            .head(&self.base_url)
+            .timeout(PROBE_TIMEOUT)
+            .inject_trace_context()
+            .send()
+            .await;
+        let latency = started.elapsed();
+
+        match result {
+            Ok(response) => ProbeReport::new(ProbeStatus::Up)
+                .with_latency(latency)
+                .with_detail("http_status", response.status().as_u16()),
+            Err(e) => {
+                warn!(error = %e, "payment provider unreachable");
+                ProbeReport::new(ProbeStatus::Down)
+                    .with_latency(latency)
+                    .with_detail("error", e.to_string())
+            }
+        }
+    }
+}
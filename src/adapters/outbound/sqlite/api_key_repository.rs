@@ -0,0 +1,163 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{error, instrument};
+
+use crate::domain::{ApiKey, Role, TenantId};
+use crate::ports::ApiKeyRepository;
+
+struct ApiKeyRow {
+    id: String,
+    key_hash: String,
+    tenant_id: Option<String>,
+    role: String,
+    created_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ApiKeyRow) -> Result<Self, Self::Error> {
+        let role = Role::parse(&row.role)
+            .with_context(|| format!("unknown api key role `{}`", row.role))?;
+
+        Ok(Self {
+            id: row.id,
+            key_hash: row.key_hash,
+            tenant_id: row.tenant_id.map(TenantId::new),
+            role,
+            created_at: row.created_at,
+            revoked_at: row.revoked_at,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SqliteApiKeyRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteApiKeyRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl ApiKeyRepository for SqliteApiKeyRepository {
+    #[instrument(
+        name = "insert_api_key",
+        skip(self, api_key),
+        fields(db.system = "sqlite", api_key_id = %api_key.id, role = api_key.role.as_str())
+    )]
+    async fn insert_api_key(&self, api_key: &ApiKey) -> Result<(), anyhow::Error> {
+        let tenant_id = api_key.tenant_id.as_ref().map(|t| t.as_ref());
+        let role = api_key.role.as_str();
+
+        sqlx::query!(
+            "INSERT INTO api_keys (id, key_hash, tenant_id, role, created_at, revoked_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6) ON CONFLICT(key_hash) DO NOTHING",
+            api_key.id,
+            api_key.key_hash,
+            tenant_id,
+            role,
+            api_key.created_at,
+            api_key.revoked_at
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to insert api key into database")
+        .inspect_err(|e| {
+            error!(error = %e, api_key_id = %api_key.id, "api key insert failed");
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(
+        name = "upsert_api_key",
+        skip(self, api_key),
+        fields(db.system = "sqlite", api_key_id = %api_key.id, role = api_key.role.as_str())
+    )]
+    async fn upsert_api_key(&self, api_key: &ApiKey) -> Result<(), anyhow::Error> {
+        let tenant_id = api_key.tenant_id.as_ref().map(|t| t.as_ref());
+        let role = api_key.role.as_str();
+
+        sqlx::query!(
+            "INSERT INTO api_keys (id, key_hash, tenant_id, role, created_at, revoked_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6) ON CONFLICT(id) DO UPDATE SET key_hash = excluded.key_hash, tenant_id = excluded.tenant_id, role = excluded.role, revoked_at = NULL",
+            api_key.id,
+            api_key.key_hash,
+            tenant_id,
+            role,
+            api_key.created_at,
+            api_key.revoked_at
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to upsert api key into database")
+        .inspect_err(|e| {
+            error!(error = %e, api_key_id = %api_key.id, "api key upsert failed");
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "find_api_key", skip(self, key_hash), fields(db.system = "sqlite"))]
+    async fn find_active_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, anyhow::Error> {
+        let row = sqlx::query_as!(
+            ApiKeyRow,
+            r#"SELECT id as "id!", key_hash, tenant_id, role, created_at as "created_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>" FROM api_keys WHERE key_hash = ?1 AND revoked_at IS NULL"#,
+            key_hash
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to fetch api key from database")
+        .inspect_err(|e| {
+            error!(error = %e, "api key query failed");
+        })?;
+
+        row.map(ApiKey::try_from).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outbound::jwt::JwksTokenVerifier;
+    use crate::services::{auth_service::hash_api_key, AuthService};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_rotating_bootstrap_admin_key_replaces_the_old_one() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let repo = SqliteApiKeyRepository::new(pool);
+        let auth = AuthService::<_, JwksTokenVerifier>::new(repo.clone(), None);
+
+        auth.register_bootstrap_admin_key("first-admin-key")
+            .await
+            .unwrap();
+        auth.register_bootstrap_admin_key("first-admin-key")
+            .await
+            .unwrap();
+        auth.register_bootstrap_admin_key("second-admin-key")
+            .await
+            .unwrap();
+
+        assert!(repo
+            .find_active_by_hash(&hash_api_key("first-admin-key"))
+            .await
+            .unwrap()
+            .is_none());
+        let current = repo
+            .find_active_by_hash(&hash_api_key("second-admin-key"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(current.id, "bootstrap-admin");
+        assert_eq!(current.role, Role::PlatformAdmin);
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{SqliteConnection, SqlitePool};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::domain::{
+    AuditAction, AuditEntry, RequestContext, Subscription, SubscriptionId, TenantId,
+};
+use crate::ports::AuditLog;
+
+struct AuditRow {
+    id: String,
+    subscription_id: String,
+    tenant_id: String,
+    actor: String,
+    action: String,
+    before_state: Option<String>,
+    after_state: Option<String>,
+    request_id: Option<String>,
+    occurred_at: DateTime<Utc>,
+}
+
+fn parse_state(state: Option<String>) -> Result<Option<Subscription>, anyhow::Error> {
+    state
+        .map(|s| serde_json::from_str(&s).context("failed to deserialize audit snapshot"))
+        .transpose()
+}
+
+fn serialize_state(state: Option<&Subscription>) -> Result<Option<String>, anyhow::Error> {
+    state
+        .map(|s| serde_json::to_string(s).context("failed to serialize audit snapshot"))
+        .transpose()
+}
+
+impl TryFrom<AuditRow> for AuditEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(row: AuditRow) -> Result<Self, Self::Error> {
+        let action = AuditAction::parse(&row.action)
+            .with_context(|| format!("unknown audit action `{}`", row.action))?;
+
+        Ok(Self {
+            id: row.id,
+            subscription_id: SubscriptionId::new(row.subscription_id),
+            tenant_id: TenantId::new(row.tenant_id),
+            actor: row.actor,
+            action,
+            before: parse_state(row.before_state)?,
+            after: parse_state(row.after_state)?,
+            request_id: row.request_id,
+            occurred_at: row.occurred_at,
+        })
+    }
+}
+
+/// Inserts the audit entry for a subscription change. `conn` is the
+/// transaction that makes the change, so the entry commits or rolls back
+/// with it.
+pub(super) async fn record_change(
+    conn: &mut SqliteConnection,
+    context: &RequestContext,
+    action: AuditAction,
+    before: Option<&Subscription>,
+    after: &Subscription,
+) -> Result<(), anyhow::Error> {
+    let id = Uuid::new_v4().to_string();
+    let subscription_id = after.id.as_ref();
+    let tenant_id = after.tenant_id.as_ref();
+    let action_str = action.as_str();
+    let before_state = serialize_state(before)?;
+    let after_state = serialize_state(Some(after))?;
+    let occurred_at = Utc::now();
+
+    sqlx::query!(
+        "INSERT INTO audit_log (id, subscription_id, tenant_id, actor, action, before_state, after_state, request_id, occurred_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        id,
+        subscription_id,
+        tenant_id,
+        context.actor,
+        action_str,
+        before_state,
+        after_state,
+        context.request_id,
+        occurred_at
+    )
+    .execute(conn)
+    .await
+    .context("failed to insert audit entry into database")
+    .inspect_err(|e| {
+        error!(error = %e, subscription_id = %after.id, action = action_str, "audit entry insert failed");
+    })?;
+
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct SqliteAuditLog {
+    pool: SqlitePool,
+}
+
+impl SqliteAuditLog {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl AuditLog for SqliteAuditLog {
+    #[instrument(
+        name = "list_audit_entries",
+        skip(self),
+        fields(db.system = "sqlite", subscription_id = %subscription_id)
+    )]
+    async fn list_for_subscription(
+        &self,
+        subscription_id: &SubscriptionId,
+    ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+        let subscription_id_str = subscription_id.as_ref();
+
+        let rows = sqlx::query_as!(
+            AuditRow,
+            r#"SELECT id as "id!", subscription_id, tenant_id, actor, action, before_state, after_state, request_id, occurred_at as "occurred_at: DateTime<Utc>" FROM audit_log WHERE subscription_id = ?1 ORDER BY occurred_at"#,
+            subscription_id_str
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list audit entries from database")
+        .inspect_err(|e| {
+            error!(error = %e, subscription_id = %subscription_id, "audit log query failed");
+        })?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+}
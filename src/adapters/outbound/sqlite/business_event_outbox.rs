@@ -0,0 +1,179 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{SqliteConnection, SqlitePool};
+use tracing::{error, instrument};
+
+use crate::domain::{BusinessEvent, RecordedBusinessEvent, RequestContext};
+use crate::ports::{BusinessEventLog, BusinessEventOutbox};
+
+struct OutboxRow {
+    id: i64,
+    payload: String,
+    request_id: Option<String>,
+    recorded_at: DateTime<Utc>,
+}
+
+impl TryFrom<OutboxRow> for RecordedBusinessEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(row: OutboxRow) -> Result<Self, Self::Error> {
+        let event = serde_json::from_str(&row.payload)
+            .with_context(|| format!("failed to deserialize business event {}", row.id))?;
+
+        Ok(Self {
+            outbox_id: row.id,
+            event,
+            request_id: row.request_id,
+            recorded_at: row.recorded_at,
+        })
+    }
+}
+
+/// Inserts `event` into the outbox. `conn` is the transaction that makes the
+/// change the event describes, so the event commits or rolls back with it.
+pub(super) async fn record_business_event(
+    conn: &mut SqliteConnection,
+    context: &RequestContext,
+    event: &BusinessEvent,
+) -> Result<(), anyhow::Error> {
+    let event_type = event.event_type();
+    let payload = serde_json::to_string(event).context("failed to serialize business event")?;
+    let recorded_at = Utc::now();
+
+    sqlx::query!(
+        "INSERT INTO business_event_outbox (event_type, payload, request_id, recorded_at) VALUES (?1, ?2, ?3, ?4)",
+        event_type,
+        payload,
+        context.request_id,
+        recorded_at
+    )
+    .execute(&mut *conn)
+    .await
+    .context("failed to insert business event into outbox")
+    .inspect_err(|e| {
+        error!(error = %e, event_type, "business event insert failed");
+    })?;
+
+    Ok(())
+}
+
+/// Business events waiting to be copied to the chained log. Subscription
+/// repositories write to it inside their change transactions; events that go
+/// with no change, such as card-on-file checks, go through
+/// [`BusinessEventLog`].
+#[derive(Clone)]
+pub struct SqliteBusinessEventOutbox {
+    pool: SqlitePool,
+}
+
+impl SqliteBusinessEventOutbox {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl BusinessEventLog for SqliteBusinessEventOutbox {
+    #[instrument(
+        name = "record_business_event",
+        skip(self, event, context),
+        fields(db.system = "sqlite", event_type = event.event_type())
+    )]
+    async fn append(
+        &self,
+        event: &BusinessEvent,
+        context: &RequestContext,
+    ) -> Result<(), anyhow::Error> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .context("failed to acquire connection for business event")?;
+
+        record_business_event(&mut conn, context, event).await
+    }
+}
+
+impl BusinessEventOutbox for SqliteBusinessEventOutbox {
+    #[instrument(
+        name = "pending_business_events",
+        skip(self),
+        fields(db.system = "sqlite")
+    )]
+    async fn pending(&self, limit: u32) -> Result<Vec<RecordedBusinessEvent>, anyhow::Error> {
+        let rows = sqlx::query_as!(
+            OutboxRow,
+            r#"SELECT id as "id!", payload, request_id, recorded_at as "recorded_at: DateTime<Utc>" FROM business_event_outbox ORDER BY id LIMIT ?1"#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to fetch pending business events")
+        .inspect_err(|e| {
+            error!(error = %e, "business event outbox query failed");
+        })?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(
+        name = "acknowledge_business_events",
+        skip(self),
+        fields(db.system = "sqlite")
+    )]
+    async fn acknowledge(&self, outbox_id: i64) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "DELETE FROM business_event_outbox WHERE id <= ?1",
+            outbox_id
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to acknowledge business events")
+        .inspect_err(|e| {
+            error!(error = %e, outbox_id, "business event outbox delete failed");
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{PaymentEvent, PlanId, Principal, Role, TenantId};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_events_are_pending_until_acknowledged() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let outbox = SqliteBusinessEventOutbox::new(pool);
+        let context = RequestContext::new(
+            Principal::new("support@ledgercloud.test", None, vec![Role::PlatformAdmin]),
+            Some("req_123".to_string()),
+        );
+
+        for plan in ["basic", "pro"] {
+            let event = PaymentEvent::checked(&TenantId::new("tenant_1"), &PlanId::new(plan), true);
+            outbox.append(&event.into(), &context).await.unwrap();
+        }
+
+        let pending = outbox.pending(10).await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert!(pending[0].outbox_id < pending[1].outbox_id);
+        assert_eq!(pending[0].event.event_type(), "payment.method_verified");
+        assert_eq!(pending[0].request_id.as_deref(), Some("req_123"));
+
+        outbox.acknowledge(pending[0].outbox_id).await.unwrap();
+        let pending = outbox.pending(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(matches!(
+            &pending[0].event,
+            BusinessEvent::Payment(PaymentEvent::MethodVerified { plan_id, .. })
+                if plan_id.as_ref() == "pro"
+        ));
+    }
+}
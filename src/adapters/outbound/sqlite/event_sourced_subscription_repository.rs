@@ -0,0 +1,586 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::collections::BTreeMap;
+use tracing::{debug, error, instrument};
+use uuid::Uuid;
+
+use crate::domain::{
+    AuditAction, PlanId, RequestContext, Subscription, SubscriptionEvent, SubscriptionId, TenantId,
+};
+use crate::ports::{SubscriptionRepository, SubscriptionStats};
+
+use super::audit_log::record_change;
+use super::business_event_outbox::record_business_event;
+
+const DEFAULT_SNAPSHOT_INTERVAL: u32 = 20;
+
+struct EventRow {
+    subscription_id: String,
+    payload: String,
+}
+
+struct SnapshotRow {
+    version: i64,
+    state: String,
+}
+
+struct ProjectionRow {
+    id: String,
+    tenant_id: String,
+    plan_id: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ProjectionRow> for Subscription {
+    fn from(row: ProjectionRow) -> Self {
+        Self {
+            id: SubscriptionId::new(row.id),
+            tenant_id: TenantId::new(row.tenant_id),
+            plan_id: PlanId::new(row.plan_id),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// Stores every subscription as an append-only stream of [`SubscriptionEvent`]s
+/// and rebuilds the aggregate by folding them. A snapshot is written every
+/// `snapshot_interval` events so loads only replay the tail of the stream, and
+/// `subscription_projection` keeps the current state for list queries.
+#[derive(Clone)]
+pub struct EventSourcedSubscriptionRepository {
+    pool: SqlitePool,
+    snapshot_interval: u32,
+}
+
+impl EventSourcedSubscriptionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+        }
+    }
+
+    pub fn with_snapshot_interval(mut self, snapshot_interval: u32) -> Self {
+        self.snapshot_interval = snapshot_interval.max(1);
+        self
+    }
+
+    /// Returns the current state of the aggregate together with its version.
+    async fn load(
+        &self,
+        subscription_id: &SubscriptionId,
+    ) -> Result<Option<(Subscription, i64)>, anyhow::Error> {
+        let id = subscription_id.as_ref();
+
+        let snapshot = sqlx::query_as!(
+            SnapshotRow,
+            "SELECT version, state FROM subscription_snapshots WHERE subscription_id = ?1",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to fetch subscription snapshot from database")?;
+
+        let (mut state, mut version) = match snapshot {
+            Some(row) => {
+                let state: Subscription = serde_json::from_str(&row.state)
+                    .context("failed to deserialize subscription snapshot")?;
+                (Some(state), row.version)
+            }
+            None => (None, 0),
+        };
+
+        let events = sqlx::query!(
+            r#"SELECT version as "version!", payload FROM subscription_events WHERE subscription_id = ?1 AND version > ?2 ORDER BY version"#,
+            id,
+            version
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to fetch subscription events from database")?;
+
+        debug!(
+            snapshot_version = version,
+            replayed_events = events.len(),
+            "rebuilding subscription aggregate"
+        );
+
+        for row in events {
+            let event: SubscriptionEvent = serde_json::from_str(&row.payload)
+                .context("failed to deserialize subscription event")?;
+            state = event.apply(state);
+            version = row.version;
+        }
+
+        Ok(state.map(|s| (s, version)))
+    }
+
+    /// Appends the event at `version` and refreshes the projection (and, on
+    /// the snapshot interval, the snapshot) in the same transaction, along
+    /// with the audit entry for the change from `before` to `state` and the
+    /// event's copy in the business event outbox. The
+    /// `(subscription_id, version)` primary key rejects concurrent writers:
+    /// returns `false`, writing nothing, when `version` is already taken.
+    async fn append(
+        &self,
+        event: &SubscriptionEvent,
+        before: Option<&Subscription>,
+        state: &Subscription,
+        version: i64,
+        context: &RequestContext,
+        action: AuditAction,
+    ) -> Result<bool, anyhow::Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin subscription event transaction")?;
+
+        let subscription_id = event.subscription_id().as_ref();
+        let tenant_id = event.tenant_id().as_ref();
+        let event_type = event.event_type();
+        let payload =
+            serde_json::to_string(event).context("failed to serialize subscription event")?;
+        let occurred_at = event.occurred_at();
+
+        let appended = sqlx::query!(
+            "INSERT INTO subscription_events (subscription_id, version, tenant_id, event_type, payload, occurred_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            subscription_id,
+            version,
+            tenant_id,
+            event_type,
+            payload,
+            occurred_at
+        )
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(sqlx::Error::Database(e)) = &appended {
+            if e.is_unique_violation() {
+                debug!(
+                    subscription_id,
+                    version, "subscription event version already taken"
+                );
+                return Ok(false);
+            }
+        }
+
+        appended.with_context(|| {
+            format!("failed to append event version {version} to subscription {subscription_id}")
+        })?;
+
+        Self::project(&mut tx, state, version).await?;
+
+        if version % i64::from(self.snapshot_interval) == 0 {
+            Self::snapshot(&mut tx, state, version).await?;
+        }
+
+        record_change(&mut tx, context, action, before, state).await?;
+        record_business_event(&mut tx, context, &event.clone().into()).await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit subscription event transaction")?;
+
+        Ok(true)
+    }
+
+    async fn project(
+        tx: &mut Transaction<'_, Sqlite>,
+        state: &Subscription,
+        version: i64,
+    ) -> Result<(), anyhow::Error> {
+        let id = state.id.as_ref();
+        let tenant_id = state.tenant_id.as_ref();
+        let plan_id = state.plan_id.as_ref();
+
+        sqlx::query!(
+            "INSERT INTO subscription_projection (id, tenant_id, plan_id, version, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET plan_id = excluded.plan_id, version = excluded.version, updated_at = excluded.updated_at",
+            id,
+            tenant_id,
+            plan_id,
+            version,
+            state.created_at,
+            state.updated_at
+        )
+        .execute(&mut **tx)
+        .await
+        .context("failed to update subscription projection")?;
+
+        Ok(())
+    }
+
+    async fn snapshot(
+        tx: &mut Transaction<'_, Sqlite>,
+        state: &Subscription,
+        version: i64,
+    ) -> Result<(), anyhow::Error> {
+        let id = state.id.as_ref();
+        let serialized =
+            serde_json::to_string(state).context("failed to serialize subscription snapshot")?;
+        let taken_at = Utc::now();
+
+        sqlx::query!(
+            "INSERT INTO subscription_snapshots (subscription_id, version, state, taken_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(subscription_id) DO UPDATE SET version = excluded.version, state = excluded.state, taken_at = excluded.taken_at",
+            id,
+            version,
+            serialized,
+            taken_at
+        )
+        .execute(&mut **tx)
+        .await
+        .context("failed to write subscription snapshot")?;
+
+        debug!(subscription_id = %state.id, version, "subscription snapshot written");
+
+        Ok(())
+    }
+
+    /// Folds every event of the tenant up to and including `at`.
+    async fn subscriptions_at(
+        &self,
+        tenant_id: &TenantId,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<Subscription>, anyhow::Error> {
+        let tenant_id_str = tenant_id.as_ref();
+
+        let rows = sqlx::query_as!(
+            EventRow,
+            "SELECT subscription_id, payload FROM subscription_events WHERE tenant_id = ?1 AND occurred_at <= ?2 ORDER BY subscription_id, version",
+            tenant_id_str,
+            at
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to fetch subscription events from database")?;
+
+        let mut states: BTreeMap<String, Option<Subscription>> = BTreeMap::new();
+        for row in rows {
+            let event: SubscriptionEvent = serde_json::from_str(&row.payload)
+                .context("failed to deserialize subscription event")?;
+            let state = states.entry(row.subscription_id).or_default();
+            *state = event.apply(state.take());
+        }
+
+        let mut subscriptions: Vec<Subscription> = states.into_values().flatten().collect();
+        subscriptions.sort_by_key(|s| s.created_at);
+
+        Ok(subscriptions)
+    }
+}
+
+impl SubscriptionRepository for EventSourcedSubscriptionRepository {
+    #[instrument(
+        name = "insert_subscription",
+        skip(self, context),
+        fields(
+            db.system = "sqlite",
+            store = "event_sourced",
+            tenant_id = %tenant_id,
+            plan_id = %plan_id
+        )
+    )]
+    async fn insert_subscription(
+        &self,
+        tenant_id: &TenantId,
+        plan_id: &PlanId,
+        context: &RequestContext,
+    ) -> Result<Subscription, anyhow::Error> {
+        let subscription = Subscription::new(
+            SubscriptionId::new(Uuid::new_v4().to_string()),
+            tenant_id.clone(),
+            plan_id.clone(),
+        );
+
+        let appended = self
+            .append(
+                &SubscriptionEvent::Created(subscription.clone()),
+                None,
+                &subscription,
+                1,
+                context,
+                AuditAction::SubscriptionCreated,
+            )
+            .await
+            .inspect_err(|e| {
+                error!(error = %e, subscription_id = %subscription.id, "subscription insert failed");
+            })?;
+        anyhow::ensure!(
+            appended,
+            "subscription {} already has an event stream",
+            subscription.id
+        );
+
+        Ok(subscription)
+    }
+
+    #[instrument(
+        name = "find_subscription",
+        skip(self),
+        fields(db.system = "sqlite", store = "event_sourced", subscription_id = %subscription_id)
+    )]
+    async fn find_subscription(
+        &self,
+        subscription_id: &SubscriptionId,
+    ) -> Result<Option<Subscription>, anyhow::Error> {
+        let loaded = self.load(subscription_id).await.inspect_err(|e| {
+            error!(error = %e, subscription_id = %subscription_id, "subscription load failed");
+        })?;
+
+        Ok(loaded.map(|(subscription, _)| subscription))
+    }
+
+    #[instrument(
+        name = "change_subscription_plan",
+        skip(self, subscription, context),
+        fields(
+            db.system = "sqlite",
+            store = "event_sourced",
+            subscription_id = %subscription.id,
+            plan_id = %plan_id
+        )
+    )]
+    async fn change_plan(
+        &self,
+        subscription: &Subscription,
+        plan_id: &PlanId,
+        context: &RequestContext,
+    ) -> Result<Option<Subscription>, anyhow::Error> {
+        let (current, version) = self
+            .load(&subscription.id)
+            .await?
+            .with_context(|| format!("subscription {} has no event stream", subscription.id))?;
+
+        if current.plan_id != subscription.plan_id {
+            debug!(
+                subscription_id = %subscription.id,
+                previous_plan_id = %subscription.plan_id,
+                "subscription plan changed concurrently"
+            );
+            return Ok(None);
+        }
+
+        let event = SubscriptionEvent::PlanChanged {
+            subscription_id: current.id.clone(),
+            tenant_id: current.tenant_id.clone(),
+            from_plan_id: current.plan_id.clone(),
+            to_plan_id: plan_id.clone(),
+            changed_at: Utc::now(),
+        };
+        let updated = event
+            .apply(Some(current.clone()))
+            .context("plan change did not produce a subscription state")?;
+
+        let appended = self
+            .append(
+                &event,
+                Some(&current),
+                &updated,
+                version + 1,
+                context,
+                AuditAction::SubscriptionPlanChanged,
+            )
+            .await
+            .inspect_err(|e| {
+                error!(error = %e, subscription_id = %subscription.id, "subscription plan change failed");
+            })?;
+
+        Ok(appended.then_some(updated))
+    }
+
+    #[instrument(
+        name = "list_subscriptions",
+        skip(self),
+        fields(db.system = "sqlite", store = "event_sourced", tenant_id = %tenant_id)
+    )]
+    async fn list_subscriptions(
+        &self,
+        tenant_id: &TenantId,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Subscription>, anyhow::Error> {
+        if let Some(at) = as_of {
+            return self.subscriptions_at(tenant_id, at).await.inspect_err(|e| {
+                error!(error = %e, tenant_id = %tenant_id, "point-in-time subscription query failed");
+            });
+        }
+
+        let tenant_id_str = tenant_id.as_ref();
+
+        let rows = sqlx::query_as!(
+            ProjectionRow,
+            r#"SELECT id as "id!", tenant_id, plan_id, created_at as "created_at: DateTime<Utc>", updated_at as "updated_at: DateTime<Utc>" FROM subscription_projection WHERE tenant_id = ?1 ORDER BY created_at"#,
+            tenant_id_str
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list subscriptions from projection")
+        .inspect_err(|e| {
+            error!(error = %e, tenant_id = %tenant_id, "subscription projection query failed");
+        })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    fn supports_point_in_time(&self) -> bool {
+        true
+    }
+}
+
+impl SubscriptionStats for EventSourcedSubscriptionRepository {
+    #[instrument(name = "active_subscriptions_by_plan", skip(self), fields(db.system = "sqlite"))]
+    async fn active_subscriptions_by_plan(&self) -> Result<Vec<(PlanId, u64)>, anyhow::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT plan_id, COUNT(*) as "count!: i64" FROM subscription_projection GROUP BY plan_id"#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to count subscriptions by plan")
+        .inspect_err(|e| {
+            error!(error = %e, "subscription count query failed");
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (PlanId::new(row.plan_id), row.count as u64))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outbound::sqlite::SqliteAuditLog;
+    use crate::domain::{Principal, Role};
+    use crate::ports::AuditLog;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::time::Duration;
+
+    async fn repository() -> EventSourcedSubscriptionRepository {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        EventSourcedSubscriptionRepository::new(pool).with_snapshot_interval(2)
+    }
+
+    fn context() -> RequestContext {
+        RequestContext::new(
+            Principal::new("support@ledgercloud.test", None, vec![Role::PlatformAdmin]),
+            Some("req_123".to_string()),
+        )
+    }
+
+    async fn tick() -> DateTime<Utc> {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let now = Utc::now();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        now
+    }
+
+    #[tokio::test]
+    async fn test_find_subscription_folds_events() {
+        let repo = repository().await;
+        let tenant_id = TenantId::new("tenant_1");
+
+        let created = repo
+            .insert_subscription(&tenant_id, &PlanId::new("free"), &context())
+            .await
+            .unwrap();
+        let changed = repo
+            .change_plan(&created, &PlanId::new("pro"), &context())
+            .await
+            .unwrap()
+            .unwrap();
+        repo.change_plan(&changed, &PlanId::new("enterprise"), &context())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let loaded = repo.find_subscription(&created.id).await.unwrap().unwrap();
+        assert_eq!(loaded.plan_id, PlanId::new("enterprise"));
+        assert_eq!(loaded.created_at, created.created_at);
+
+        let current = repo.list_subscriptions(&tenant_id, None).await.unwrap();
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].plan_id, PlanId::new("enterprise"));
+
+        let history = SqliteAuditLog::new(repo.pool.clone())
+            .list_for_subscription(&created.id)
+            .await
+            .unwrap();
+        let plans: Vec<_> = history
+            .iter()
+            .map(|entry| entry.after.as_ref().unwrap().plan_id.as_ref())
+            .collect();
+        assert_eq!(plans, ["free", "pro", "enterprise"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_subscriptions_as_of_returns_historical_plan() {
+        let repo = repository().await;
+        let tenant_id = TenantId::new("tenant_1");
+
+        let before_creation = tick().await;
+        let created = repo
+            .insert_subscription(&tenant_id, &PlanId::new("free"), &context())
+            .await
+            .unwrap();
+        let on_free = tick().await;
+        repo.change_plan(&created, &PlanId::new("enterprise"), &context())
+            .await
+            .unwrap();
+
+        let at = |t| repo.list_subscriptions(&tenant_id, Some(t));
+        assert!(at(before_creation).await.unwrap().is_empty());
+        assert_eq!(at(on_free).await.unwrap()[0].plan_id, PlanId::new("free"));
+        assert_eq!(
+            at(Utc::now()).await.unwrap()[0].plan_id,
+            PlanId::new("enterprise")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_change_plan_from_a_stale_read_appends_nothing() {
+        let repo = repository().await;
+
+        let created = repo
+            .insert_subscription(&TenantId::new("tenant_1"), &PlanId::new("free"), &context())
+            .await
+            .unwrap();
+        repo.change_plan(&created, &PlanId::new("pro"), &context())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let stale = repo
+            .change_plan(&created, &PlanId::new("enterprise"), &context())
+            .await
+            .unwrap();
+        assert!(stale.is_none());
+        let (loaded, version) = repo.load(&created.id).await.unwrap().unwrap();
+        assert_eq!(loaded.plan_id, PlanId::new("pro"));
+        assert_eq!(version, 2);
+    }
+
+    #[tokio::test]
+    async fn test_events_are_append_only() {
+        let repo = repository().await;
+
+        repo.insert_subscription(&TenantId::new("tenant_1"), &PlanId::new("free"), &context())
+            .await
+            .unwrap();
+
+        let result = sqlx::query("UPDATE subscription_events SET event_type = 'tampered'")
+            .execute(&repo.pool)
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use sqlx::{migrate::Migrator, SqlitePool};
+use std::time::Instant;
+use tracing::{instrument, warn};
+
+use crate::ports::{HealthProbe, ProbeReport, ProbeStatus};
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Round-trips a trivial query through the pool.
+#[derive(Clone)]
+pub struct SqlitePingProbe {
+    pool: SqlitePool,
+}
+
+impl SqlitePingProbe {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl HealthProbe for SqlitePingProbe {
+    #[instrument(name = "probe_database", skip(self), fields(db.system = "sqlite"))]
+    async fn probe(&self) -> ProbeReport {
+        let started = Instant::now();
+        let result = sqlx::query("SELECT 1").execute(&self.pool).await;
+        let latency = started.elapsed();
+
+        let report = match result {
+            Ok(_) => ProbeReport::new(ProbeStatus::Up),
+            Err(e) => {
+                warn!(error = %e, "database ping failed");
+                ProbeReport::new(ProbeStatus::Down).with_detail("error", e.to_string())
+            }
+        };
+
+        report
+            .with_latency(latency)
+            .with_detail("pool_size", self.pool.size())
+            .with_detail("pool_idle", self.pool.num_idle() as u64)
+    }
+}
+
+/// Compares the applied migrations with the ones embedded in the binary. A
+/// database that is behind, or has a migration that failed halfway, is down.
+#[derive(Clone)]
+pub struct SqliteMigrationProbe {
+    pool: SqlitePool,
+}
+
+impl SqliteMigrationProbe {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl HealthProbe for SqliteMigrationProbe {
+    #[instrument(name = "probe_migrations", skip(self), fields(db.system = "sqlite"))]
+    async fn probe(&self) -> ProbeReport {
+        let expected = MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0);
+
+        let applied: Result<(Option<i64>, i64), _> = sqlx::query_as(
+            "SELECT MAX(CASE WHEN success THEN version END), \
+             COUNT(CASE WHEN NOT success THEN 1 END) FROM _sqlx_migrations",
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        match applied {
+            Ok((version, failed)) => {
+                let version = version.unwrap_or(0);
+                let status = if version >= expected && failed == 0 {
+                    ProbeStatus::Up
+                } else {
+                    warn!(
+                        version,
+                        expected_version = expected,
+                        failed,
+                        "database migrations are not current"
+                    );
+                    ProbeStatus::Down
+                };
+                ProbeReport::new(status)
+                    .with_detail("version", version)
+                    .with_detail("expected_version", expected)
+                    .with_detail("failed", failed)
+            }
+            Err(e) => {
+                warn!(error = %e, "failed to read applied migrations");
+                ProbeReport::new(ProbeStatus::Down)
+                    .with_detail("expected_version", expected)
+                    .with_detail("error", e.to_string())
+            }
+        }
+    }
+}
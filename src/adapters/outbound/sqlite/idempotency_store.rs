@@ -0,0 +1,280 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{error, instrument};
+
+use crate::ports::{IdempotencyClaim, IdempotencyRecord, IdempotencyStore, StoredResponse};
+
+struct IdempotencyRow {
+    fingerprint: String,
+    status_code: Option<i64>,
+    content_type: Option<String>,
+    response_headers: Option<String>,
+    response_body: Option<Vec<u8>>,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<IdempotencyRow> for IdempotencyRecord {
+    fn from(row: IdempotencyRow) -> Self {
+        // Rows stored before headers were kept only have the content type.
+        let headers = match row.response_headers {
+            Some(json) => serde_json::from_str(&json).unwrap_or_default(),
+            None => row
+                .content_type
+                .map(|ct| vec![("content-type".to_string(), ct)])
+                .unwrap_or_default(),
+        };
+        let response = row.status_code.map(|code| StoredResponse {
+            status_code: code as u16,
+            headers,
+            body: row.response_body.unwrap_or_default(),
+        });
+
+        Self {
+            fingerprint: row.fingerprint,
+            response,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SqliteIdempotencyStore {
+    pool: SqlitePool,
+}
+
+impl SqliteIdempotencyStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl IdempotencyStore for SqliteIdempotencyStore {
+    #[instrument(
+        name = "claim_idempotency_key",
+        skip(self, fingerprint),
+        fields(db.system = "sqlite", scope = %scope, idempotency_key = %key)
+    )]
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<IdempotencyClaim, anyhow::Error> {
+        let now = Utc::now();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin idempotency transaction")?;
+
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE scope = ?1 AND key = ?2 AND (expires_at <= ?3 OR (status_code IS NULL AND (locked_until IS NULL OR locked_until <= ?3)))",
+            scope,
+            key,
+            now
+        )
+        .execute(&mut *tx)
+        .await
+        .context("failed to drop expired idempotency key")?;
+
+        let inserted = sqlx::query!(
+            "INSERT INTO idempotency_keys (scope, key, fingerprint, created_at, locked_until, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6) ON CONFLICT(scope, key) DO NOTHING",
+            scope,
+            key,
+            fingerprint,
+            now,
+            locked_until,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .context("failed to insert idempotency key")?
+        .rows_affected();
+
+        let claim = if inserted == 1 {
+            IdempotencyClaim::Acquired
+        } else {
+            let row = sqlx::query_as!(
+                IdempotencyRow,
+                r#"SELECT fingerprint, status_code, content_type, response_headers, response_body, expires_at as "expires_at: DateTime<Utc>" FROM idempotency_keys WHERE scope = ?1 AND key = ?2"#,
+                scope,
+                key
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .context("failed to fetch idempotency key")?;
+
+            IdempotencyClaim::Existing(row.into())
+        };
+
+        tx.commit()
+            .await
+            .context("failed to commit idempotency transaction")
+            .inspect_err(|e| {
+                error!(error = %e, idempotency_key = %key, "idempotency claim failed");
+            })?;
+
+        Ok(claim)
+    }
+
+    #[instrument(
+        name = "complete_idempotency_key",
+        skip(self, response),
+        fields(db.system = "sqlite", scope = %scope, idempotency_key = %key, status_code = response.status_code)
+    )]
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        response: &StoredResponse,
+    ) -> Result<(), anyhow::Error> {
+        let status_code = i64::from(response.status_code);
+        let headers = serde_json::to_string(&response.headers)
+            .context("failed to serialize idempotent response headers")?;
+
+        sqlx::query!(
+            "UPDATE idempotency_keys SET status_code = ?1, response_headers = ?2, response_body = ?3, locked_until = NULL WHERE scope = ?4 AND key = ?5",
+            status_code,
+            headers,
+            response.body,
+            scope,
+            key
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to store idempotent response")
+        .inspect_err(|e| {
+            error!(error = %e, idempotency_key = %key, "idempotency completion failed");
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(
+        name = "release_idempotency_key",
+        skip(self),
+        fields(db.system = "sqlite", scope = %scope, idempotency_key = %key)
+    )]
+    async fn release(&self, scope: &str, key: &str) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE scope = ?1 AND key = ?2 AND status_code IS NULL",
+            scope,
+            key
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to release idempotency key")
+        .inspect_err(|e| {
+            error!(error = %e, idempotency_key = %key, "idempotency release failed");
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "purge_idempotency_keys", skip(self), fields(db.system = "sqlite"))]
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<u64, anyhow::Error> {
+        let result = sqlx::query!("DELETE FROM idempotency_keys WHERE expires_at <= ?1", now)
+            .execute(&self.pool)
+            .await
+            .context("failed to purge expired idempotency keys")
+            .inspect_err(|e| {
+                error!(error = %e, "idempotency purge failed");
+            })?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_abandoned_claim_is_taken_over_after_its_lease() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let store = SqliteIdempotencyStore::new(pool);
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::hours(24);
+
+        // The first request never completes and its lease has already run out.
+        let first = store
+            .claim(
+                "s",
+                "k",
+                "fp",
+                now - chrono::Duration::seconds(1),
+                expires_at,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(first, IdempotencyClaim::Acquired));
+
+        let retry = store
+            .claim(
+                "s",
+                "k",
+                "fp",
+                now + chrono::Duration::seconds(60),
+                expires_at,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(retry, IdempotencyClaim::Acquired));
+
+        let concurrent = store
+            .claim(
+                "s",
+                "k",
+                "fp",
+                now + chrono::Duration::seconds(60),
+                expires_at,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            concurrent,
+            IdempotencyClaim::Existing(IdempotencyRecord { response: None, .. })
+        ));
+
+        let response = StoredResponse {
+            status_code: 201,
+            headers: vec![
+                ("content-type".to_string(), "application/json".to_string()),
+                (
+                    "location".to_string(),
+                    "/api/v2/subscriptions/1".to_string(),
+                ),
+            ],
+            body: b"{}".to_vec(),
+        };
+        store.complete("s", "k", &response).await.unwrap();
+
+        // A completed key is replayed, however long ago its lease ran out.
+        let replay = store
+            .claim(
+                "s",
+                "k",
+                "fp",
+                now - chrono::Duration::seconds(1),
+                expires_at,
+            )
+            .await
+            .unwrap();
+        match replay {
+            IdempotencyClaim::Existing(record) => {
+                assert_eq!(record.response.unwrap().headers, response.headers)
+            }
+            other => panic!("expected the stored response, got {:?}", other),
+        }
+    }
+}
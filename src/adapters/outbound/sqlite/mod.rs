@@ -1,7 +1,25 @@
+pub mod api_key_repository;
+pub mod audit_log;
 pub mod billing_repository;
+pub mod business_event_outbox;
+pub mod event_sourced_subscription_repository;
+pub mod health_probe;
+pub mod idempotency_store;
 pub mod plan_repository;
+pub mod pool_metrics;
 pub mod subscription_repository;
+pub mod subscription_store;
+pub mod webhook_repository;
 
+pub use api_key_repository::SqliteApiKeyRepository;
+pub use audit_log::SqliteAuditLog;
 pub use billing_repository::SqliteBillingProfileRepository;
+pub use business_event_outbox::SqliteBusinessEventOutbox;
+pub use event_sourced_subscription_repository::EventSourcedSubscriptionRepository;
+pub use health_probe::{SqliteMigrationProbe, SqlitePingProbe};
+pub use idempotency_store::SqliteIdempotencyStore;
 pub use plan_repository::SqlitePlanRepository;
+pub use pool_metrics::register_pool_metrics;
 pub use subscription_repository::SqliteSubscriptionRepository;
+pub use subscription_store::SqliteSubscriptionStore;
+pub use webhook_repository::SqliteWebhookRepository;
@@ -0,0 +1,26 @@
+use opentelemetry::metrics::{MetricsError, ObservableGauge};
+use opentelemetry::{global, KeyValue};
+use sqlx::SqlitePool;
+
+/// Reports the pool's connections by state (`idle`, `used`) and its
+/// configured maximum. The gauge is read at collection time, so the returned
+/// handle only needs to be kept alive.
+pub fn register_pool_metrics(pool: SqlitePool) -> Result<ObservableGauge<u64>, MetricsError> {
+    global::meter("hexagonal-rust")
+        .u64_observable_gauge("db.client.connections.usage")
+        .with_description("Database pool connections by state")
+        .with_callback(move |observer| {
+            let size = u64::from(pool.size());
+            let idle = pool.num_idle() as u64;
+            let max = u64::from(pool.options().get_max_connections());
+            let pool_name = KeyValue::new("pool.name", "sqlite");
+
+            observer.observe(idle, &[pool_name.clone(), KeyValue::new("state", "idle")]);
+            observer.observe(
+                size.saturating_sub(idle),
+                &[pool_name.clone(), KeyValue::new("state", "used")],
+            );
+            observer.observe(max, &[pool_name, KeyValue::new("state", "max")]);
+        })
+        .try_init()
+}
@@ -1,10 +1,36 @@
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
-use tracing::{error, instrument};
+use tracing::{debug, error, instrument};
 use uuid::Uuid;
 
-use crate::domain::{PlanId, Subscription, SubscriptionId, TenantId};
-use crate::ports::SubscriptionRepository;
+use crate::domain::{
+    AuditAction, PlanId, RequestContext, Subscription, SubscriptionEvent, SubscriptionId, TenantId,
+};
+use crate::ports::{SubscriptionRepository, SubscriptionStats};
+
+use super::audit_log::record_change;
+use super::business_event_outbox::record_business_event;
+
+struct SubscriptionRow {
+    id: String,
+    tenant_id: String,
+    plan_id: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SubscriptionRow> for Subscription {
+    fn from(row: SubscriptionRow) -> Self {
+        Self {
+            id: SubscriptionId::new(row.id),
+            tenant_id: TenantId::new(row.tenant_id),
+            plan_id: PlanId::new(row.plan_id),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct SqliteSubscriptionRepository {
@@ -20,7 +46,7 @@ impl SqliteSubscriptionRepository {
 impl SubscriptionRepository for SqliteSubscriptionRepository {
     #[instrument(
         name = "insert_subscription",
-        skip(self),
+        skip(self, context),
         fields(
             db.system = "sqlite",
             tenant_id = %tenant_id,
@@ -31,28 +57,311 @@ impl SubscriptionRepository for SqliteSubscriptionRepository {
         &self,
         tenant_id: &TenantId,
         plan_id: &PlanId,
+        context: &RequestContext,
     ) -> Result<Subscription, anyhow::Error> {
-        let id = Uuid::new_v4().to_string();
+        let subscription = Subscription::new(
+            SubscriptionId::new(Uuid::new_v4().to_string()),
+            tenant_id.clone(),
+            plan_id.clone(),
+        );
+        let id = subscription.id.as_ref();
         let tenant_id_str = tenant_id.as_ref();
         let plan_id_str = plan_id.as_ref();
 
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin subscription insert transaction")?;
+
         sqlx::query!(
-            "INSERT INTO subscriptions (id, tenant_id, plan_id, created_at) VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+            "INSERT INTO subscriptions (id, tenant_id, plan_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
             id,
             tenant_id_str,
-            plan_id_str
+            plan_id_str,
+            subscription.created_at,
+            subscription.updated_at
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .context("failed to insert subscription into database")
         .inspect_err(|e| {
             error!(error = %e, subscription_id = %id, tenant_id = %tenant_id, plan_id = %plan_id, "subscription insert failed");
         })?;
 
-        Ok(Subscription::new(
-            SubscriptionId::new(id),
-            tenant_id.clone(),
-            plan_id.clone(),
-        ))
+        record_change(
+            &mut tx,
+            context,
+            AuditAction::SubscriptionCreated,
+            None,
+            &subscription,
+        )
+        .await?;
+        record_business_event(
+            &mut tx,
+            context,
+            &SubscriptionEvent::Created(subscription.clone()).into(),
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit subscription insert transaction")?;
+
+        Ok(subscription)
+    }
+
+    #[instrument(
+        name = "find_subscription",
+        skip(self),
+        fields(db.system = "sqlite", subscription_id = %subscription_id)
+    )]
+    async fn find_subscription(
+        &self,
+        subscription_id: &SubscriptionId,
+    ) -> Result<Option<Subscription>, anyhow::Error> {
+        let id = subscription_id.as_ref();
+
+        let row = sqlx::query_as!(
+            SubscriptionRow,
+            r#"SELECT id as "id!", tenant_id, plan_id, created_at as "created_at: DateTime<Utc>", COALESCE(updated_at, created_at) as "updated_at!: DateTime<Utc>" FROM subscriptions WHERE id = ?1"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to fetch subscription from database")
+        .inspect_err(|e| {
+            error!(error = %e, subscription_id = %subscription_id, "subscription query failed");
+        })?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[instrument(
+        name = "change_subscription_plan",
+        skip(self, subscription, context),
+        fields(
+            db.system = "sqlite",
+            subscription_id = %subscription.id,
+            plan_id = %plan_id
+        )
+    )]
+    async fn change_plan(
+        &self,
+        subscription: &Subscription,
+        plan_id: &PlanId,
+        context: &RequestContext,
+    ) -> Result<Option<Subscription>, anyhow::Error> {
+        let mut updated = subscription.clone();
+        updated.plan_id = plan_id.clone();
+        updated.updated_at = Utc::now();
+
+        let id = updated.id.as_ref();
+        let plan_id_str = plan_id.as_ref();
+        let previous_plan_id = subscription.plan_id.as_ref();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin subscription update transaction")?;
+
+        let result = sqlx::query!(
+            "UPDATE subscriptions SET plan_id = ?1, updated_at = ?2 WHERE id = ?3 AND plan_id = ?4",
+            plan_id_str,
+            updated.updated_at,
+            id,
+            previous_plan_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context("failed to update subscription plan in database")
+        .inspect_err(|e| {
+            error!(error = %e, subscription_id = %id, plan_id = %plan_id, "subscription update failed");
+        })?;
+
+        if result.rows_affected() == 0 {
+            debug!(
+                subscription_id = %id,
+                previous_plan_id = %previous_plan_id,
+                "subscription plan changed concurrently"
+            );
+            return Ok(None);
+        }
+
+        record_change(
+            &mut tx,
+            context,
+            AuditAction::SubscriptionPlanChanged,
+            Some(subscription),
+            &updated,
+        )
+        .await?;
+        record_business_event(
+            &mut tx,
+            context,
+            &SubscriptionEvent::plan_changed(subscription, &updated).into(),
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .context("failed to commit subscription update transaction")?;
+
+        Ok(Some(updated))
+    }
+
+    #[instrument(
+        name = "list_subscriptions",
+        skip(self),
+        fields(db.system = "sqlite", tenant_id = %tenant_id)
+    )]
+    async fn list_subscriptions(
+        &self,
+        tenant_id: &TenantId,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Subscription>, anyhow::Error> {
+        if as_of.is_some() {
+            anyhow::bail!("the state-based subscription store keeps no history");
+        }
+
+        let tenant_id_str = tenant_id.as_ref();
+
+        let rows = sqlx::query_as!(
+            SubscriptionRow,
+            r#"SELECT id as "id!", tenant_id, plan_id, created_at as "created_at: DateTime<Utc>", COALESCE(updated_at, created_at) as "updated_at!: DateTime<Utc>" FROM subscriptions WHERE tenant_id = ?1 ORDER BY created_at"#,
+            tenant_id_str
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list subscriptions from database")
+        .inspect_err(|e| {
+            error!(error = %e, tenant_id = %tenant_id, "subscriptions query failed");
+        })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+impl SubscriptionStats for SqliteSubscriptionRepository {
+    #[instrument(name = "active_subscriptions_by_plan", skip(self), fields(db.system = "sqlite"))]
+    async fn active_subscriptions_by_plan(&self) -> Result<Vec<(PlanId, u64)>, anyhow::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT plan_id, COUNT(*) as "count!: i64" FROM subscriptions GROUP BY plan_id"#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to count subscriptions by plan")
+        .inspect_err(|e| {
+            error!(error = %e, "subscription count query failed");
+        })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (PlanId::new(row.plan_id), row.count as u64))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::outbound::sqlite::{SqliteAuditLog, SqliteBusinessEventOutbox};
+    use crate::domain::{Principal, Role};
+    use crate::ports::{AuditLog, BusinessEventOutbox};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn context() -> RequestContext {
+        RequestContext::new(
+            Principal::new("support@ledgercloud.test", None, vec![Role::PlatformAdmin]),
+            Some("req_123".to_string()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_plan_change_and_its_business_event_roll_back_when_audit_entry_fails() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let repo = SqliteSubscriptionRepository::new(pool.clone());
+        let audit_log = SqliteAuditLog::new(pool.clone());
+        let outbox = SqliteBusinessEventOutbox::new(pool.clone());
+
+        let created = repo
+            .insert_subscription(&TenantId::new("tenant_1"), &PlanId::new("free"), &context())
+            .await
+            .unwrap();
+        let history = audit_log.list_for_subscription(&created.id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].action, AuditAction::SubscriptionCreated);
+        assert_eq!(history[0].actor, "support@ledgercloud.test");
+        assert_eq!(history[0].request_id.as_deref(), Some("req_123"));
+        let pending = outbox.pending(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event.event_type(), "subscription.created");
+        assert_eq!(pending[0].request_id.as_deref(), Some("req_123"));
+
+        sqlx::query(
+            "CREATE TRIGGER reject_audit BEFORE INSERT ON audit_log BEGIN SELECT RAISE(ABORT, 'audit log unavailable'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(repo
+            .change_plan(&created, &PlanId::new("pro"), &context())
+            .await
+            .is_err());
+        let stored = repo.find_subscription(&created.id).await.unwrap().unwrap();
+        assert_eq!(stored.plan_id, PlanId::new("free"));
+        assert_eq!(
+            audit_log
+                .list_for_subscription(&created.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(outbox.pending(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_change_plan_from_a_stale_read_changes_nothing() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let repo = SqliteSubscriptionRepository::new(pool.clone());
+        let audit_log = SqliteAuditLog::new(pool.clone());
+
+        let created = repo
+            .insert_subscription(&TenantId::new("tenant_1"), &PlanId::new("free"), &context())
+            .await
+            .unwrap();
+        repo.change_plan(&created, &PlanId::new("pro"), &context())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let stale = repo
+            .change_plan(&created, &PlanId::new("enterprise"), &context())
+            .await
+            .unwrap();
+        assert!(stale.is_none());
+        let stored = repo.find_subscription(&created.id).await.unwrap().unwrap();
+        assert_eq!(stored.plan_id, PlanId::new("pro"));
+        assert_eq!(
+            audit_log
+                .list_for_subscription(&created.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
     }
 }
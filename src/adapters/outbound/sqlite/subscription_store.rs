@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::{PlanId, RequestContext, Subscription, SubscriptionId, TenantId};
+use crate::ports::{SubscriptionRepository, SubscriptionStats, TenantPlanLookup};
+
+use super::{EventSourcedSubscriptionRepository, SqliteSubscriptionRepository};
+
+/// The subscription store selected at startup. Dispatches to either the
+/// state-based table or the event-sourced stream.
+#[derive(Clone)]
+pub enum SqliteSubscriptionStore {
+    State(SqliteSubscriptionRepository),
+    EventSourced(EventSourcedSubscriptionRepository),
+}
+
+impl SubscriptionRepository for SqliteSubscriptionStore {
+    async fn insert_subscription(
+        &self,
+        tenant_id: &TenantId,
+        plan_id: &PlanId,
+        context: &RequestContext,
+    ) -> Result<Subscription, anyhow::Error> {
+        match self {
+            Self::State(repo) => repo.insert_subscription(tenant_id, plan_id, context).await,
+            Self::EventSourced(repo) => repo.insert_subscription(tenant_id, plan_id, context).await,
+        }
+    }
+
+    async fn find_subscription(
+        &self,
+        subscription_id: &SubscriptionId,
+    ) -> Result<Option<Subscription>, anyhow::Error> {
+        match self {
+            Self::State(repo) => repo.find_subscription(subscription_id).await,
+            Self::EventSourced(repo) => repo.find_subscription(subscription_id).await,
+        }
+    }
+
+    async fn change_plan(
+        &self,
+        subscription: &Subscription,
+        plan_id: &PlanId,
+        context: &RequestContext,
+    ) -> Result<Option<Subscription>, anyhow::Error> {
+        match self {
+            Self::State(repo) => repo.change_plan(subscription, plan_id, context).await,
+            Self::EventSourced(repo) => repo.change_plan(subscription, plan_id, context).await,
+        }
+    }
+
+    async fn list_subscriptions(
+        &self,
+        tenant_id: &TenantId,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Subscription>, anyhow::Error> {
+        match self {
+            Self::State(repo) => repo.list_subscriptions(tenant_id, as_of).await,
+            Self::EventSourced(repo) => repo.list_subscriptions(tenant_id, as_of).await,
+        }
+    }
+
+    fn supports_point_in_time(&self) -> bool {
+        match self {
+            Self::State(repo) => repo.supports_point_in_time(),
+            Self::EventSourced(repo) => repo.supports_point_in_time(),
+        }
+    }
+}
+
+impl TenantPlanLookup for SqliteSubscriptionStore {
+    async fn plans_for_tenant(&self, tenant_id: &TenantId) -> Result<Vec<PlanId>, anyhow::Error> {
+        let subscriptions = self.list_subscriptions(tenant_id, None).await?;
+
+        Ok(subscriptions.into_iter().map(|s| s.plan_id).collect())
+    }
+}
+
+impl SubscriptionStats for SqliteSubscriptionStore {
+    async fn active_subscriptions_by_plan(&self) -> Result<Vec<(PlanId, u64)>, anyhow::Error> {
+        match self {
+            Self::State(repo) => repo.active_subscriptions_by_plan().await,
+            Self::EventSourced(repo) => repo.active_subscriptions_by_plan().await,
+        }
+    }
+}
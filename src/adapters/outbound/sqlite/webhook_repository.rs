@@ -0,0 +1,597 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::domain::{
+    SubscriptionEvent, TenantId, WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryId,
+    WebhookDeliveryStatus, WebhookEndpoint, WebhookEndpointId,
+};
+use crate::ports::{SubscriptionEventPublisher, WebhookRepository};
+
+struct EndpointRow {
+    id: String,
+    tenant_id: String,
+    url: String,
+    secret: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<EndpointRow> for WebhookEndpoint {
+    fn from(row: EndpointRow) -> Self {
+        Self {
+            id: WebhookEndpointId::new(row.id),
+            tenant_id: TenantId::new(row.tenant_id),
+            url: row.url,
+            secret: row.secret,
+            created_at: row.created_at,
+        }
+    }
+}
+
+struct DeliveryRow {
+    id: String,
+    endpoint_id: String,
+    tenant_id: String,
+    event_type: String,
+    payload: String,
+    status: String,
+    attempts: i64,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_response_code: Option<i64>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DeliveryRow> for WebhookDelivery {
+    type Error = anyhow::Error;
+
+    fn try_from(row: DeliveryRow) -> Result<Self, Self::Error> {
+        let status = WebhookDeliveryStatus::parse(&row.status)
+            .with_context(|| format!("unknown webhook delivery status `{}`", row.status))?;
+
+        Ok(Self {
+            id: WebhookDeliveryId::new(row.id),
+            endpoint_id: WebhookEndpointId::new(row.endpoint_id),
+            tenant_id: TenantId::new(row.tenant_id),
+            event_type: row.event_type,
+            payload: row.payload,
+            status,
+            attempts: row.attempts as u32,
+            next_attempt_at: row.next_attempt_at,
+            last_response_code: row.last_response_code.map(|c| c as u16),
+            created_at: row.created_at,
+        })
+    }
+}
+
+struct DueDeliveryRow {
+    id: String,
+    endpoint_id: String,
+    tenant_id: String,
+    event_type: String,
+    payload: String,
+    status: String,
+    attempts: i64,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_response_code: Option<i64>,
+    created_at: DateTime<Utc>,
+    endpoint_url: String,
+    endpoint_secret: String,
+    endpoint_created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DueDeliveryRow> for (WebhookDelivery, WebhookEndpoint) {
+    type Error = anyhow::Error;
+
+    fn try_from(row: DueDeliveryRow) -> Result<Self, Self::Error> {
+        let endpoint = WebhookEndpoint {
+            id: WebhookEndpointId::new(row.endpoint_id.clone()),
+            tenant_id: TenantId::new(row.tenant_id.clone()),
+            url: row.endpoint_url,
+            secret: row.endpoint_secret,
+            created_at: row.endpoint_created_at,
+        };
+
+        let delivery = DeliveryRow {
+            id: row.id,
+            endpoint_id: row.endpoint_id,
+            tenant_id: row.tenant_id,
+            event_type: row.event_type,
+            payload: row.payload,
+            status: row.status,
+            attempts: row.attempts,
+            next_attempt_at: row.next_attempt_at,
+            last_response_code: row.last_response_code,
+            created_at: row.created_at,
+        }
+        .try_into()?;
+
+        Ok((delivery, endpoint))
+    }
+}
+
+struct AttemptRow {
+    delivery_id: String,
+    attempt: i64,
+    response_code: Option<i64>,
+    error: Option<String>,
+    duration_ms: i64,
+    attempted_at: DateTime<Utc>,
+}
+
+impl From<AttemptRow> for WebhookDeliveryAttempt {
+    fn from(row: AttemptRow) -> Self {
+        Self {
+            delivery_id: WebhookDeliveryId::new(row.delivery_id),
+            attempt: row.attempt as u32,
+            response_code: row.response_code.map(|c| c as u16),
+            error: row.error,
+            duration_ms: row.duration_ms as u64,
+            attempted_at: row.attempted_at,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SqliteWebhookRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteWebhookRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl WebhookRepository for SqliteWebhookRepository {
+    #[instrument(
+        name = "insert_webhook_endpoint",
+        skip(self, endpoint),
+        fields(db.system = "sqlite", endpoint_id = %endpoint.id, tenant_id = %endpoint.tenant_id)
+    )]
+    async fn insert_endpoint(&self, endpoint: &WebhookEndpoint) -> Result<(), anyhow::Error> {
+        let id = endpoint.id.as_ref();
+        let tenant_id = endpoint.tenant_id.as_ref();
+
+        sqlx::query!(
+            "INSERT INTO webhook_endpoints (id, tenant_id, url, secret, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            id,
+            tenant_id,
+            endpoint.url,
+            endpoint.secret,
+            endpoint.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to insert webhook endpoint into database")
+        .inspect_err(|e| {
+            error!(error = %e, endpoint_id = %endpoint.id, "webhook endpoint insert failed");
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(
+        name = "find_webhook_endpoint",
+        skip(self),
+        fields(db.system = "sqlite", tenant_id = %tenant_id, endpoint_id = %endpoint_id)
+    )]
+    async fn find_endpoint(
+        &self,
+        tenant_id: &TenantId,
+        endpoint_id: &WebhookEndpointId,
+    ) -> Result<Option<WebhookEndpoint>, anyhow::Error> {
+        let tenant_id_str = tenant_id.as_ref();
+        let endpoint_id_str = endpoint_id.as_ref();
+
+        let row = sqlx::query_as!(
+            EndpointRow,
+            r#"SELECT id as "id!", tenant_id, url, secret, created_at as "created_at: DateTime<Utc>" FROM webhook_endpoints WHERE id = ?1 AND tenant_id = ?2"#,
+            endpoint_id_str,
+            tenant_id_str
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to fetch webhook endpoint from database")
+        .inspect_err(|e| {
+            error!(error = %e, endpoint_id = %endpoint_id, "webhook endpoint query failed");
+        })?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[instrument(
+        name = "list_webhook_endpoints",
+        skip(self),
+        fields(db.system = "sqlite", tenant_id = %tenant_id)
+    )]
+    async fn list_endpoints(
+        &self,
+        tenant_id: &TenantId,
+    ) -> Result<Vec<WebhookEndpoint>, anyhow::Error> {
+        let tenant_id_str = tenant_id.as_ref();
+
+        let rows = sqlx::query_as!(
+            EndpointRow,
+            r#"SELECT id as "id!", tenant_id, url, secret, created_at as "created_at: DateTime<Utc>" FROM webhook_endpoints WHERE tenant_id = ?1 ORDER BY created_at"#,
+            tenant_id_str
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list webhook endpoints from database")
+        .inspect_err(|e| {
+            error!(error = %e, tenant_id = %tenant_id, "webhook endpoints query failed");
+        })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(
+        name = "insert_webhook_delivery",
+        skip(self, delivery),
+        fields(db.system = "sqlite", delivery_id = %delivery.id, endpoint_id = %delivery.endpoint_id)
+    )]
+    async fn insert_delivery(&self, delivery: &WebhookDelivery) -> Result<(), anyhow::Error> {
+        let id = delivery.id.as_ref();
+        let endpoint_id = delivery.endpoint_id.as_ref();
+        let tenant_id = delivery.tenant_id.as_ref();
+        let status = delivery.status.as_str();
+        let attempts = delivery.attempts as i64;
+        let last_response_code = delivery.last_response_code.map(i64::from);
+
+        sqlx::query!(
+            "INSERT INTO webhook_deliveries (id, endpoint_id, tenant_id, event_type, payload, status, attempts, next_attempt_at, last_response_code, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            id,
+            endpoint_id,
+            tenant_id,
+            delivery.event_type,
+            delivery.payload,
+            status,
+            attempts,
+            delivery.next_attempt_at,
+            last_response_code,
+            delivery.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to insert webhook delivery into database")
+        .inspect_err(|e| {
+            error!(error = %e, delivery_id = %delivery.id, "webhook delivery insert failed");
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(
+        name = "find_webhook_delivery",
+        skip(self),
+        fields(db.system = "sqlite", tenant_id = %tenant_id, delivery_id = %delivery_id)
+    )]
+    async fn find_delivery(
+        &self,
+        tenant_id: &TenantId,
+        delivery_id: &WebhookDeliveryId,
+    ) -> Result<Option<WebhookDelivery>, anyhow::Error> {
+        let tenant_id_str = tenant_id.as_ref();
+        let delivery_id_str = delivery_id.as_ref();
+
+        let row = sqlx::query_as!(
+            DeliveryRow,
+            r#"SELECT id as "id!", endpoint_id, tenant_id, event_type, payload, status, attempts, next_attempt_at as "next_attempt_at: DateTime<Utc>", last_response_code, created_at as "created_at: DateTime<Utc>" FROM webhook_deliveries WHERE id = ?1 AND tenant_id = ?2"#,
+            delivery_id_str,
+            tenant_id_str
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to fetch webhook delivery from database")
+        .inspect_err(|e| {
+            error!(error = %e, delivery_id = %delivery_id, "webhook delivery query failed");
+        })?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    #[instrument(
+        name = "list_webhook_deliveries",
+        skip(self),
+        fields(db.system = "sqlite", tenant_id = %tenant_id, endpoint_id = %endpoint_id)
+    )]
+    async fn list_deliveries(
+        &self,
+        tenant_id: &TenantId,
+        endpoint_id: &WebhookEndpointId,
+    ) -> Result<Vec<WebhookDelivery>, anyhow::Error> {
+        let tenant_id_str = tenant_id.as_ref();
+        let endpoint_id_str = endpoint_id.as_ref();
+
+        let rows = sqlx::query_as!(
+            DeliveryRow,
+            r#"SELECT id as "id!", endpoint_id, tenant_id, event_type, payload, status, attempts, next_attempt_at as "next_attempt_at: DateTime<Utc>", last_response_code, created_at as "created_at: DateTime<Utc>" FROM webhook_deliveries WHERE endpoint_id = ?1 AND tenant_id = ?2 ORDER BY created_at DESC"#,
+            endpoint_id_str,
+            tenant_id_str
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list webhook deliveries from database")
+        .inspect_err(|e| {
+            error!(error = %e, endpoint_id = %endpoint_id, "webhook deliveries query failed");
+        })?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(
+        name = "list_webhook_delivery_attempts",
+        skip(self),
+        fields(db.system = "sqlite", delivery_id = %delivery_id)
+    )]
+    async fn list_attempts(
+        &self,
+        delivery_id: &WebhookDeliveryId,
+    ) -> Result<Vec<WebhookDeliveryAttempt>, anyhow::Error> {
+        let delivery_id_str = delivery_id.as_ref();
+
+        let rows = sqlx::query_as!(
+            AttemptRow,
+            r#"SELECT delivery_id, attempt, response_code, error, duration_ms, attempted_at as "attempted_at: DateTime<Utc>" FROM webhook_delivery_attempts WHERE delivery_id = ?1 ORDER BY attempt"#,
+            delivery_id_str
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list webhook delivery attempts from database")
+        .inspect_err(|e| {
+            error!(error = %e, delivery_id = %delivery_id, "webhook attempts query failed");
+        })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    #[instrument(name = "claim_due_webhook_deliveries", skip(self), fields(db.system = "sqlite"))]
+    async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<(WebhookDelivery, WebhookEndpoint)>, anyhow::Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin webhook claim transaction")?;
+
+        // A single statement, so two workers never claim the same delivery.
+        let claimed = sqlx::query_scalar!(
+            r#"UPDATE webhook_deliveries SET locked_until = ?2
+               WHERE id IN (
+                   SELECT id FROM webhook_deliveries
+                   WHERE status = 'pending' AND next_attempt_at <= ?1
+                     AND (locked_until IS NULL OR locked_until <= ?1)
+                   ORDER BY next_attempt_at
+                   LIMIT ?3
+               )
+               RETURNING id as "id!""#,
+            now,
+            locked_until,
+            limit
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("failed to claim due webhook deliveries in database")?;
+
+        let mut rows = Vec::with_capacity(claimed.len());
+        for id in claimed {
+            let row = sqlx::query_as!(
+                DueDeliveryRow,
+                r#"SELECT d.id as "id!", d.endpoint_id, d.tenant_id, d.event_type, d.payload, d.status, d.attempts, d.next_attempt_at as "next_attempt_at: DateTime<Utc>", d.last_response_code, d.created_at as "created_at: DateTime<Utc>", e.url as endpoint_url, e.secret as endpoint_secret, e.created_at as "endpoint_created_at: DateTime<Utc>"
+                   FROM webhook_deliveries d
+                   JOIN webhook_endpoints e ON e.id = d.endpoint_id
+                   WHERE d.id = ?1"#,
+                id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .context("failed to fetch claimed webhook delivery from database")?;
+            rows.push(row);
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit webhook claim transaction")
+            .inspect_err(|e| {
+                error!(error = %e, "due webhook deliveries claim failed");
+            })?;
+
+        rows.sort_by_key(|row| row.next_attempt_at);
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    #[instrument(
+        name = "record_webhook_attempt",
+        skip(self, attempt),
+        fields(db.system = "sqlite", delivery_id = %attempt.delivery_id, status = status.as_str())
+    )]
+    async fn record_attempt(
+        &self,
+        attempt: &WebhookDeliveryAttempt,
+        status: WebhookDeliveryStatus,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<(), anyhow::Error> {
+        let delivery_id = attempt.delivery_id.as_ref();
+        let attempt_number = attempt.attempt as i64;
+        let response_code = attempt.response_code.map(i64::from);
+        let duration_ms = attempt.duration_ms as i64;
+        let status_str = status.as_str();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin webhook attempt transaction")?;
+
+        sqlx::query!(
+            "INSERT INTO webhook_delivery_attempts (delivery_id, attempt, response_code, error, duration_ms, attempted_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            delivery_id,
+            attempt_number,
+            response_code,
+            attempt.error,
+            duration_ms,
+            attempt.attempted_at
+        )
+        .execute(&mut *tx)
+        .await
+        .context("failed to insert webhook delivery attempt into database")?;
+
+        sqlx::query!(
+            "UPDATE webhook_deliveries SET status = ?1, attempts = ?2, next_attempt_at = ?3, last_response_code = ?4, locked_until = NULL WHERE id = ?5",
+            status_str,
+            attempt_number,
+            next_attempt_at,
+            response_code,
+            delivery_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context("failed to update webhook delivery in database")?;
+
+        tx.commit()
+            .await
+            .context("failed to commit webhook attempt transaction")
+            .inspect_err(|e| {
+                error!(error = %e, delivery_id = %attempt.delivery_id, "webhook attempt record failed");
+            })
+    }
+
+    #[instrument(
+        name = "schedule_webhook_delivery",
+        skip(self),
+        fields(db.system = "sqlite", delivery_id = %delivery_id)
+    )]
+    async fn schedule_delivery(
+        &self,
+        delivery_id: &WebhookDeliveryId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, anyhow::Error> {
+        let delivery_id_str = delivery_id.as_ref();
+
+        let result = sqlx::query!(
+            "UPDATE webhook_deliveries SET status = 'pending', next_attempt_at = ?1 WHERE id = ?2 AND (locked_until IS NULL OR locked_until <= ?1)",
+            at,
+            delivery_id_str
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to schedule webhook delivery in database")
+        .inspect_err(|e| {
+            error!(error = %e, delivery_id = %delivery_id, "webhook delivery schedule failed");
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Fans a subscription event out into one pending delivery per endpoint the
+/// tenant has registered. The worker picks them up from the same tables.
+impl SubscriptionEventPublisher for SqliteWebhookRepository {
+    #[instrument(
+        name = "enqueue_webhook_deliveries",
+        skip(self, event),
+        fields(db.system = "sqlite", event_type = event.event_type(), tenant_id = %event.tenant_id())
+    )]
+    async fn publish(&self, event: &SubscriptionEvent) -> Result<(), anyhow::Error> {
+        let endpoints = self.list_endpoints(event.tenant_id()).await?;
+
+        for endpoint in endpoints {
+            let now = Utc::now();
+            let id = WebhookDeliveryId::new(Uuid::new_v4().to_string());
+
+            let mut payload =
+                serde_json::to_value(event).context("failed to serialize subscription event")?;
+            payload["id"] = serde_json::Value::String(id.to_string());
+            payload["created_at"] = serde_json::Value::String(now.to_rfc3339());
+
+            let delivery = WebhookDelivery {
+                id,
+                endpoint_id: endpoint.id,
+                tenant_id: endpoint.tenant_id,
+                event_type: event.event_type().to_string(),
+                payload: payload.to_string(),
+                status: WebhookDeliveryStatus::Pending,
+                attempts: 0,
+                next_attempt_at: Some(now),
+                last_response_code: None,
+                created_at: now,
+            };
+
+            self.insert_delivery(&delivery).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{PlanId, SubscriptionId};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_claimed_deliveries_are_skipped_until_the_lease_passes() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let repo = SqliteWebhookRepository::new(pool);
+
+        let tenant_id = TenantId::new("tenant_1");
+        repo.insert_endpoint(&WebhookEndpoint {
+            id: WebhookEndpointId::new("endpoint_1"),
+            tenant_id: tenant_id.clone(),
+            url: "https://hooks.example.test".to_string(),
+            secret: "whsec_test".to_string(),
+            created_at: Utc::now(),
+        })
+        .await
+        .unwrap();
+        repo.publish(&SubscriptionEvent::PlanChanged {
+            subscription_id: SubscriptionId::new("sub_1"),
+            tenant_id,
+            from_plan_id: PlanId::new("free"),
+            to_plan_id: PlanId::new("pro"),
+            changed_at: Utc::now(),
+        })
+        .await
+        .unwrap();
+
+        let now = Utc::now();
+        let lease = chrono::Duration::minutes(5);
+        let claimed = repo
+            .claim_due_deliveries(now, now + lease, 10)
+            .await
+            .unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].1.url, "https://hooks.example.test");
+
+        // Another worker polling meanwhile finds nothing to send.
+        assert!(repo
+            .claim_due_deliveries(now, now + lease, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        // Nor is it put back in the queue by a replay.
+        assert!(!repo.schedule_delivery(&claimed[0].0.id, now).await.unwrap());
+
+        // A claim that was never recorded lapses.
+        let later = now + lease;
+        assert_eq!(
+            repo.claim_due_deliveries(later, later + lease, 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use opentelemetry::{global, propagation::Injector};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::observability::current_request_id;
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if value.is_empty() {
+            return;
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Adds `traceparent`, `tracestate` and `baggage` for the current span so the
+/// callee continues our trace. Without an OTLP tracer there is no span
+/// context and nothing is added. Inside a request, its `X-Request-Id` is
+/// forwarded too, whether or not tracing is on.
+pub trait InjectTraceContext {
+    fn inject_trace_context(self) -> Self;
+}
+
+impl InjectTraceContext for reqwest::RequestBuilder {
+    fn inject_trace_context(self) -> Self {
+        let cx = Span::current().context();
+        let mut headers = HeaderMap::new();
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, &mut HeaderInjector(&mut headers))
+        });
+        if let Some(request_id) = current_request_id() {
+            HeaderInjector(&mut headers).set(REQUEST_ID_HEADER, request_id);
+        }
+        self.headers(headers)
+    }
+}
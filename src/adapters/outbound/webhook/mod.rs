@@ -0,0 +1,3 @@
+pub mod sender;
+
+pub use sender::HttpWebhookSender;
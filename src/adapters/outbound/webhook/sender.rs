@@ -0,0 +1,174 @@
+use anyhow::Context;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tracing::{error, instrument, warn};
+use url::{Host, Url};
+
+use crate::adapters::outbound::trace_context::InjectTraceContext;
+use crate::domain::{is_internal_address, WebhookDelivery, WebhookEndpoint};
+use crate::ports::WebhookSender;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const DELIVERY_ID_HEADER: &str = "X-Webhook-Id";
+pub const EVENT_TYPE_HEADER: &str = "X-Webhook-Event";
+
+/// Signs `"{timestamp}.{payload}"` with HMAC-SHA256 so receivers can verify both
+/// the body and its freshness. Returned as `v1=<hex digest>`.
+pub fn sign_payload(secret: &str, timestamp: i64, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+
+    format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Resolves the endpoint's host and fails if any address it resolves to is
+/// internal (see [`is_internal_address`]). Registration only catches
+/// literal addresses; a host name can point anywhere, and can change.
+async fn public_addresses(url: &Url) -> Result<Vec<SocketAddr>, anyhow::Error> {
+    let port = url
+        .port_or_known_default()
+        .context("webhook url has no port")?;
+    let addresses: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(ip.into(), port)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .with_context(|| format!("failed to resolve webhook host {}", domain))?
+            .collect(),
+        None => anyhow::bail!("webhook url has no host"),
+    };
+
+    if let Some(internal) = addresses.iter().find(|a| is_internal_address(a.ip())) {
+        anyhow::bail!(
+            "webhook host resolves to internal address {}",
+            internal.ip()
+        );
+    }
+    Ok(addresses)
+}
+
+#[derive(Clone)]
+pub struct HttpWebhookSender {
+    timeout: Duration,
+}
+
+impl HttpWebhookSender {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// A client bound to the addresses that were checked, so the request
+    /// cannot be sent elsewhere by a second lookup, and that does not follow
+    /// redirects, which could lead anywhere.
+    fn client(
+        &self,
+        url: &Url,
+        addresses: &[SocketAddr],
+    ) -> Result<reqwest::Client, anyhow::Error> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .redirect(reqwest::redirect::Policy::none());
+        if let Some(Host::Domain(domain)) = url.host() {
+            builder = builder.resolve_to_addrs(domain, addresses);
+        }
+        builder
+            .build()
+            .context("failed to build webhook HTTP client")
+    }
+}
+
+impl WebhookSender for HttpWebhookSender {
+    #[instrument(
+        name = "webhook_send",
+        skip(self, endpoint, delivery),
+        fields(
+            http.method = "POST",
+            http.url = %endpoint.url,
+            delivery_id = %delivery.id
+        )
+    )]
+    async fn send(
+        &self,
+        endpoint: &WebhookEndpoint,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, anyhow::Error> {
+        let url = Url::parse(&endpoint.url).context("webhook url is not a valid url")?;
+        let addresses = public_addresses(&url).await.inspect_err(|e| {
+            warn!(error = %e, delivery_id = %delivery.id, "webhook endpoint refused");
+        })?;
+
+        let timestamp = Utc::now().timestamp();
+        let signature = sign_payload(&endpoint.secret, timestamp, &delivery.payload);
+
+        let response = self
+            .client(&url, &addresses)?
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(DELIVERY_ID_HEADER, delivery.id.as_ref())
+            .header(EVENT_TYPE_HEADER, delivery.event_type.as_str())
+            .body(delivery.payload.clone())
+            .inject_trace_context()
+            .send()
+            .await
+            .context("failed to call webhook endpoint")
+            .inspect_err(|e| {
+                error!(error = %e, delivery_id = %delivery.id, "webhook request failed");
+            })?;
+
+        Ok(response.status().as_u16())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload_matches_known_digest() {
+        let signature = sign_payload("whsec_test", 1_700_000_000, r#"{"type":"ping"}"#);
+
+        assert_eq!(
+            signature,
+            "v1=bc08c591847b765241711bcbe7067e3869a219e424d3fdd9d00b3b6f915baf97"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_internal_targets_are_refused() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://[::1]/hook",
+            "http://localhost/hook",
+        ] {
+            assert!(
+                public_addresses(&Url::parse(url).unwrap()).await.is_err(),
+                "{}",
+                url
+            );
+        }
+        assert!(
+            public_addresses(&Url::parse("https://93.184.215.14/hook").unwrap())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_sign_payload_depends_on_secret() {
+        let payload = r#"{"type":"ping"}"#;
+
+        assert_ne!(
+            sign_payload("whsec_a", 1_700_000_000, payload),
+            sign_payload("whsec_b", 1_700_000_000, payload)
+        );
+    }
+}
@@ -0,0 +1,1323 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+use crate::adapters::inbound::http::{
+    DeprecationPolicy, RateLimitConfig, RateLimitPolicy, TlsConfig,
+};
+use crate::adapters::outbound::jwt::JwtConfig;
+use crate::domain::PlanId;
+use crate::log_file::{FileLogFormat, RotationPolicy, RotationSettings};
+use crate::observability::{LogFormat, ObservabilityConfig, RedactAction, RedactionConfig};
+use crate::otlp::{BatchSettings, OtlpCompression, OtlpProtocol};
+use crate::sampling::{SamplingConfig, SamplingStrategy};
+
+const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Default, Parser)]
+#[command(version, about = "LedgerCloud subscription billing API")]
+pub struct Cli {
+    /// TOML configuration file. Falls back to `CONFIG_FILE`.
+    #[arg(long, short = 'c', value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Address to bind, overrides `server.host`.
+    #[arg(long)]
+    pub host: Option<String>,
+
+    /// Port to bind, overrides `server.port`.
+    #[arg(long)]
+    pub port: Option<String>,
+
+    /// Overrides `database.url`.
+    #[arg(long, value_name = "URL")]
+    pub database_url: Option<String>,
+
+    /// Overrides `observability.log_format` (`json` or `pretty`).
+    #[arg(long, value_name = "FORMAT")]
+    pub log_format: Option<String>,
+
+    /// Overrides any setting by key, e.g. `--set webhooks.max_attempts=5`.
+    #[arg(long = "set", short = 's', value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+
+    /// Prints the effective configuration, with secrets redacted, and exits.
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Verifies the hash chain of the business event log and exits.
+    #[arg(long)]
+    pub verify_business_events: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Text,
+    Number,
+    Bool,
+    /// `key=value` pairs: comma separated in env and flags, a table in TOML.
+    Map,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Secret {
+    No,
+    Yes,
+    /// The values of a map, keeping its keys visible.
+    MapValues,
+    /// The password part of a URL.
+    UrlPassword,
+}
+
+struct Setting {
+    key: &'static str,
+    env: &'static str,
+    kind: Kind,
+    secret: Secret,
+    /// `None` marks a required setting.
+    default: Option<&'static str>,
+}
+
+const fn setting(
+    key: &'static str,
+    env: &'static str,
+    kind: Kind,
+    default: &'static str,
+) -> Setting {
+    Setting {
+        key,
+        env,
+        kind,
+        secret: Secret::No,
+        default: Some(default),
+    }
+}
+
+const fn secret(key: &'static str, env: &'static str, kind: Kind, secret: Secret) -> Setting {
+    Setting {
+        key,
+        env,
+        kind,
+        secret,
+        default: Some(""),
+    }
+}
+
+/// Every setting the service reads, in the order `--print-config` shows them.
+/// The part of the key before the dot is the TOML table it lives in.
+const SETTINGS: &[Setting] = &[
+    setting("server.host", "HOST", Kind::Text, "127.0.0.1"),
+    setting("server.port", "PORT", Kind::Number, "3000"),
+    setting("server.tls_cert_path", "TLS_CERT_PATH", Kind::Text, ""),
+    setting("server.tls_key_path", "TLS_KEY_PATH", Kind::Text, ""),
+    setting(
+        "server.unix_socket_path",
+        "UNIX_SOCKET_PATH",
+        Kind::Text,
+        "",
+    ),
+    setting(
+        "server.shutdown_readiness_delay_secs",
+        "SHUTDOWN_READINESS_DELAY_SECS",
+        Kind::Number,
+        "5",
+    ),
+    setting(
+        "server.shutdown_drain_timeout_secs",
+        "SHUTDOWN_DRAIN_TIMEOUT_SECS",
+        Kind::Number,
+        "30",
+    ),
+    setting(
+        "server.swagger_ui_enabled",
+        "SWAGGER_UI_ENABLED",
+        Kind::Bool,
+        "false",
+    ),
+    Setting {
+        key: "database.url",
+        env: "DATABASE_URL",
+        kind: Kind::Text,
+        secret: Secret::UrlPassword,
+        default: None,
+    },
+    secret(
+        "auth.admin_api_key",
+        "ADMIN_API_KEY",
+        Kind::Text,
+        Secret::Yes,
+    ),
+    setting("auth.jwt_jwks_path", "JWT_JWKS_PATH", Kind::Text, ""),
+    setting(
+        "auth.jwt_jwks_reload_secs",
+        "JWT_JWKS_RELOAD_SECS",
+        Kind::Number,
+        "30",
+    ),
+    setting("auth.jwt_issuer", "JWT_ISSUER", Kind::Text, ""),
+    setting("auth.jwt_audience", "JWT_AUDIENCE", Kind::Text, ""),
+    setting(
+        "auth.jwt_tenant_claim",
+        "JWT_TENANT_CLAIM",
+        Kind::Text,
+        "tenant_id",
+    ),
+    setting(
+        "auth.jwt_roles_claim",
+        "JWT_ROLES_CLAIM",
+        Kind::Text,
+        "roles",
+    ),
+    setting(
+        "auth.jwt_leeway_secs",
+        "JWT_LEEWAY_SECS",
+        Kind::Number,
+        "30",
+    ),
+    setting(
+        "subscriptions.store",
+        "SUBSCRIPTION_STORE",
+        Kind::Text,
+        "state",
+    ),
+    setting(
+        "subscriptions.snapshot_interval",
+        "SUBSCRIPTION_SNAPSHOT_INTERVAL",
+        Kind::Number,
+        "20",
+    ),
+    setting(
+        "idempotency.ttl_secs",
+        "IDEMPOTENCY_TTL_SECS",
+        Kind::Number,
+        "86400",
+    ),
+    setting(
+        "idempotency.lock_timeout_secs",
+        "IDEMPOTENCY_LOCK_TIMEOUT_SECS",
+        Kind::Number,
+        "60",
+    ),
+    setting(
+        "rate_limit.default_per_minute",
+        "RATE_LIMIT_DEFAULT_PER_MINUTE",
+        Kind::Number,
+        "60",
+    ),
+    setting(
+        "rate_limit.plans",
+        "RATE_LIMIT_PLANS",
+        Kind::Map,
+        "free=60,pro=300,enterprise=1200",
+    ),
+    setting(
+        "rate_limit.tier_cache_secs",
+        "RATE_LIMIT_TIER_CACHE_SECS",
+        Kind::Number,
+        "60",
+    ),
+    setting(
+        "webhooks.poll_interval_secs",
+        "WEBHOOK_POLL_INTERVAL_SECS",
+        Kind::Number,
+        "5",
+    ),
+    setting(
+        "webhooks.timeout_secs",
+        "WEBHOOK_TIMEOUT_SECS",
+        Kind::Number,
+        "10",
+    ),
+    setting(
+        "webhooks.max_attempts",
+        "WEBHOOK_MAX_ATTEMPTS",
+        Kind::Number,
+        "8",
+    ),
+    secret(
+        "payment_provider.url",
+        "PAYMENT_PROVIDER_URL",
+        Kind::Text,
+        Secret::UrlPassword,
+    ),
+    secret(
+        "payment_provider.api_key",
+        "PAYMENT_PROVIDER_API_KEY",
+        Kind::Text,
+        Secret::Yes,
+    ),
+    setting(
+        "business_events.path",
+        "BUSINESS_EVENTS_PATH",
+        Kind::Text,
+        "./logs/business-events.jsonl",
+    ),
+    secret(
+        "business_events.key",
+        "BUSINESS_EVENTS_KEY",
+        Kind::Text,
+        Secret::Yes,
+    ),
+    setting(
+        "business_events.relay_interval_secs",
+        "BUSINESS_EVENTS_RELAY_INTERVAL_SECS",
+        Kind::Number,
+        "1",
+    ),
+    setting(
+        "api.v1_deprecated_at",
+        "API_V1_DEPRECATED_AT",
+        Kind::Text,
+        "",
+    ),
+    setting("api.v1_sunset_at", "API_V1_SUNSET_AT", Kind::Text, ""),
+    setting(
+        "observability.service_name",
+        "OTEL_SERVICE_NAME",
+        Kind::Text,
+        "hexagonal-rust",
+    ),
+    setting(
+        "observability.log_filter",
+        "RUST_LOG",
+        Kind::Text,
+        "hexagonal_rust=debug,tower_http=debug,audit=info",
+    ),
+    setting(
+        "observability.log_format",
+        "LOG_FORMAT",
+        Kind::Text,
+        "pretty",
+    ),
+    setting(
+        "observability.log_file_enabled",
+        "LOG_FILE_ENABLED",
+        Kind::Bool,
+        "false",
+    ),
+    setting(
+        "observability.log_file_path",
+        "LOG_FILE_PATH",
+        Kind::Text,
+        "./logs/hexagonal-rust.log",
+    ),
+    setting(
+        "observability.log_file_format",
+        "LOG_FILE_FORMAT",
+        Kind::Text,
+        "json",
+    ),
+    setting(
+        "observability.log_file_rotation",
+        "LOG_FILE_ROTATION",
+        Kind::Text,
+        "daily",
+    ),
+    setting(
+        "observability.log_file_max_size_mb",
+        "LOG_FILE_MAX_SIZE_MB",
+        Kind::Number,
+        "100",
+    ),
+    setting(
+        "observability.log_file_max_files",
+        "LOG_FILE_MAX_FILES",
+        Kind::Number,
+        "7",
+    ),
+    setting(
+        "observability.log_file_compress",
+        "LOG_FILE_COMPRESS",
+        Kind::Bool,
+        "false",
+    ),
+    setting(
+        "observability.otel_tracing_enabled",
+        "OTEL_TRACING_ENABLED",
+        Kind::Bool,
+        "false",
+    ),
+    Setting {
+        key: "observability.otlp_endpoint",
+        env: "OTEL_EXPORTER_OTLP_ENDPOINT",
+        kind: Kind::Text,
+        secret: Secret::UrlPassword,
+        default: Some("http://localhost:4317"),
+    },
+    secret(
+        "observability.otlp_headers",
+        "OTEL_EXPORTER_OTLP_HEADERS",
+        Kind::Map,
+        Secret::MapValues,
+    ),
+    setting(
+        "observability.otlp_protocol",
+        "OTEL_EXPORTER_OTLP_PROTOCOL",
+        Kind::Text,
+        "grpc",
+    ),
+    setting(
+        "observability.otlp_timeout_secs",
+        "OTEL_EXPORTER_OTLP_TIMEOUT",
+        Kind::Number,
+        "10",
+    ),
+    setting(
+        "observability.otlp_compression",
+        "OTEL_EXPORTER_OTLP_COMPRESSION",
+        Kind::Text,
+        "none",
+    ),
+    setting(
+        "observability.batch_max_queue_size",
+        "OTEL_BSP_MAX_QUEUE_SIZE",
+        Kind::Number,
+        "2048",
+    ),
+    setting(
+        "observability.batch_max_export_batch_size",
+        "OTEL_BSP_MAX_EXPORT_BATCH_SIZE",
+        Kind::Number,
+        "512",
+    ),
+    setting(
+        "observability.batch_schedule_delay_ms",
+        "OTEL_BSP_SCHEDULE_DELAY",
+        Kind::Number,
+        "5000",
+    ),
+    setting(
+        "observability.batch_export_timeout_ms",
+        "OTEL_BSP_EXPORT_TIMEOUT",
+        Kind::Number,
+        "30000",
+    ),
+    setting(
+        "observability.traces_sampler",
+        "OTEL_TRACES_SAMPLER",
+        Kind::Text,
+        "always_on",
+    ),
+    setting(
+        "observability.traces_sampler_ratio",
+        "OTEL_TRACES_SAMPLER_ARG",
+        Kind::Number,
+        "1.0",
+    ),
+    setting(
+        "observability.sampling_keep_tenants",
+        "TRACE_SAMPLING_KEEP_TENANTS",
+        Kind::Text,
+        "",
+    ),
+    setting(
+        "observability.sampling_health_checks_per_minute",
+        "TRACE_SAMPLING_HEALTH_CHECKS_PER_MINUTE",
+        Kind::Number,
+        "6",
+    ),
+    setting(
+        "observability.redact_fields",
+        "LOG_REDACT_FIELDS",
+        Kind::Map,
+        "customer.email=mask,email=mask,tenant.id=hash,tenant_id=hash,customer_id=hash",
+    ),
+    secret(
+        "observability.redact_hash_key",
+        "LOG_REDACT_HASH_KEY",
+        Kind::Text,
+        Secret::Yes,
+    ),
+    setting(
+        "observability.metrics_enabled",
+        "METRICS_ENABLED",
+        Kind::Bool,
+        "true",
+    ),
+    setting(
+        "observability.metrics_host",
+        "METRICS_HOST",
+        Kind::Text,
+        "127.0.0.1",
+    ),
+    setting(
+        "observability.metrics_port",
+        "METRICS_PORT",
+        Kind::Number,
+        "9464",
+    ),
+    setting(
+        "observability.business_metrics_interval_secs",
+        "BUSINESS_METRICS_INTERVAL_SECS",
+        Kind::Number,
+        "30",
+    ),
+    setting(
+        "observability.otel_metrics_enabled",
+        "OTEL_METRICS_ENABLED",
+        Kind::Bool,
+        "false",
+    ),
+    setting(
+        "observability.otel_metrics_interval_secs",
+        "OTEL_METRIC_EXPORT_INTERVAL_SECS",
+        Kind::Number,
+        "60",
+    ),
+];
+
+fn find_setting(key: &str) -> Option<&'static Setting> {
+    SETTINGS.iter().find(|s| s.key == key)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+    Cli(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "file {}", path.display()),
+            Source::Env(name) => write!(f, "env {}", name),
+            Source::Cli(flag) => write!(f, "flag {}", flag),
+        }
+    }
+}
+
+/// The raw string value of each setting and where it came from. Later layers
+/// replace earlier ones.
+#[derive(Debug, Default)]
+struct Layers {
+    values: BTreeMap<&'static str, (String, Source)>,
+}
+
+impl Layers {
+    fn set(&mut self, setting: &'static Setting, value: String, source: Source) {
+        self.values.insert(setting.key, (value, source));
+    }
+
+    fn get(&self, key: &str) -> Option<&(String, Source)> {
+        self.values.get(key)
+    }
+
+    fn defaults(&mut self) {
+        for setting in SETTINGS {
+            if let Some(default) = setting.default {
+                self.set(setting, default.to_string(), Source::Default);
+            }
+        }
+    }
+
+    fn file(&mut self, path: &Path, errors: &mut Vec<String>) -> anyhow::Result<()> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file `{}`", path.display()))?;
+        let table: toml::Table = toml::from_str(&contents)
+            .with_context(|| format!("config file `{}` is not valid TOML", path.display()))?;
+
+        for (section, entries) in table {
+            let Some(entries) = entries.as_table() else {
+                errors.push(format!("`{}` in the config file must be a table", section));
+                continue;
+            };
+            for (name, value) in entries {
+                let key = format!("{}.{}", section, name);
+                let Some(setting) = find_setting(&key) else {
+                    errors.push(format!(
+                        "`{}` in the config file is not a known setting",
+                        key
+                    ));
+                    continue;
+                };
+                match toml_to_raw(setting, value) {
+                    Ok(raw) => self.set(setting, raw, Source::File(path.to_path_buf())),
+                    Err(reason) => errors.push(format!("{} (file): {}", key, reason)),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn env(&mut self, env: &impl Fn(&str) -> Option<String>) {
+        for setting in SETTINGS {
+            if let Some(value) = env(setting.env) {
+                self.set(setting, value, Source::Env(setting.env));
+            }
+        }
+    }
+
+    fn cli(&mut self, cli: &Cli, errors: &mut Vec<String>) {
+        let flags = [
+            ("server.host", "--host", &cli.host),
+            ("server.port", "--port", &cli.port),
+            ("database.url", "--database-url", &cli.database_url),
+            ("observability.log_format", "--log-format", &cli.log_format),
+        ];
+        for (key, flag, value) in flags {
+            if let (Some(setting), Some(value)) = (find_setting(key), value) {
+                self.set(setting, value.clone(), Source::Cli(flag.to_string()));
+            }
+        }
+
+        for entry in &cli.overrides {
+            let Some((key, value)) = entry.split_once('=') else {
+                errors.push(format!("--set `{}` is not KEY=VALUE", entry));
+                continue;
+            };
+            match find_setting(key.trim()) {
+                Some(setting) => self.set(
+                    setting,
+                    value.to_string(),
+                    Source::Cli(format!("--set {}", setting.key)),
+                ),
+                None => errors.push(format!("--set `{}` is not a known setting", key.trim())),
+            }
+        }
+    }
+}
+
+fn toml_to_raw(setting: &Setting, value: &toml::Value) -> Result<String, String> {
+    match (setting.kind, value) {
+        (Kind::Map, toml::Value::Table(table)) => table
+            .iter()
+            .map(|(k, v)| match v {
+                toml::Value::String(s) => Ok(format!("{}={}", k, s)),
+                toml::Value::Integer(i) => Ok(format!("{}={}", k, i)),
+                _ => Err(format!("`{}` must be a string or an integer", k)),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|pairs| pairs.join(",")),
+        (Kind::Map, _) => Err("must be a table".to_string()),
+        (_, toml::Value::String(s)) => Ok(s.clone()),
+        (_, toml::Value::Integer(i)) => Ok(i.to_string()),
+        (_, toml::Value::Boolean(b)) => Ok(b.to_string()),
+        (_, toml::Value::Datetime(dt)) => Ok(dt.to_string()),
+        _ => Err("must be a string, number or boolean".to_string()),
+    }
+}
+
+/// Splits `key=value,key=value`. Used for `RATE_LIMIT_PLANS` and
+/// `OTEL_EXPORTER_OTLP_HEADERS`; an entry without `=` is an error rather than
+/// being skipped.
+fn parse_pairs(raw: &str) -> Result<Vec<(String, String)>, String> {
+    raw.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            entry
+                .split_once('=')
+                .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                .filter(|(k, _)| !k.is_empty())
+                .ok_or_else(|| format!("entry `{}` is not key=value", entry.trim()))
+        })
+        .collect()
+}
+
+fn parse_bool(raw: &str) -> Result<bool, String> {
+    match raw.trim().to_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err("must be `true` or `false`".to_string()),
+    }
+}
+
+fn parse_timestamp(raw: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(raw.trim())
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| "must be an RFC 3339 timestamp".to_string())
+}
+
+/// Turns raw layer values into typed ones, collecting every problem so they
+/// can be reported together.
+struct Resolver<'a> {
+    layers: &'a Layers,
+    errors: Vec<String>,
+}
+
+impl Resolver<'_> {
+    fn raw(&self, key: &str) -> &str {
+        self.layers.get(key).map(|(v, _)| v.as_str()).unwrap_or("")
+    }
+
+    fn error(&mut self, key: &str, reason: impl fmt::Display) {
+        let message = match self.layers.get(key) {
+            Some((value, source)) if !value.is_empty() => {
+                let value = find_setting(key)
+                    .map(|setting| redact(setting, value))
+                    .unwrap_or_else(|| value.clone());
+                format!("{} = `{}` ({}): {}", key, value, source, reason)
+            }
+            Some((_, source)) => format!("{} ({}): {}", key, source, reason),
+            None => format!("{}: {}", key, reason),
+        };
+        self.errors.push(message);
+    }
+
+    fn with<T: Default>(&mut self, key: &str, parse: impl FnOnce(&str) -> Result<T, String>) -> T {
+        match parse(self.raw(key)) {
+            Ok(value) => value,
+            Err(reason) => {
+                self.error(key, reason);
+                T::default()
+            }
+        }
+    }
+
+    fn text(&self, key: &str) -> String {
+        self.raw(key).trim().to_string()
+    }
+
+    fn optional(&self, key: &str) -> Option<String> {
+        Some(self.text(key)).filter(|v| !v.is_empty())
+    }
+
+    fn required(&mut self, key: &str) -> String {
+        let value = self.text(key);
+        if value.is_empty() {
+            let env = find_setting(key).map(|s| s.env).unwrap_or_default();
+            self.error(
+                key,
+                format!("is required (set `{}` in the config file or {})", key, env),
+            );
+        }
+        value
+    }
+
+    fn number<T: FromStr + Default>(&mut self, key: &str, expected: &str) -> T {
+        self.with(key, |raw| {
+            raw.trim()
+                .parse::<T>()
+                .map_err(|_| format!("must be {}", expected))
+        })
+    }
+
+    fn secs(&mut self, key: &str) -> Duration {
+        Duration::from_secs(self.number(key, "a number of seconds"))
+    }
+
+    fn positive(&mut self, key: &str) -> u32 {
+        self.with(key, |raw| match raw.trim().parse::<u32>() {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err("must be a positive integer".to_string()),
+        })
+    }
+
+    fn bool(&mut self, key: &str) -> bool {
+        self.with(key, parse_bool)
+    }
+
+    fn pairs(&mut self, key: &str) -> Vec<(String, String)> {
+        self.with(key, parse_pairs)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    pub tls: Option<TlsConfig>,
+    pub unix_socket: Option<PathBuf>,
+    pub shutdown_readiness_delay: Duration,
+    pub shutdown_drain_timeout: Duration,
+    pub swagger_ui_enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubscriptionStoreKind {
+    State,
+    EventSourced { snapshot_interval: u32 },
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookSettings {
+    pub poll_interval: Duration,
+    pub timeout: Duration,
+    pub max_attempts: u32,
+}
+
+/// The tamper-evident business event log (see `FileBusinessEventLog`).
+#[derive(Clone)]
+pub struct BusinessEventSettings {
+    pub path: PathBuf,
+    /// Makes the chain an HMAC chain.
+    pub key: Option<String>,
+    /// How often the outbox is copied to the log.
+    pub relay_interval: Duration,
+}
+
+#[derive(Clone)]
+pub struct PaymentProviderSettings {
+    pub url: String,
+    pub api_key: String,
+}
+
+/// Everything the service needs to start, merged from built-in defaults, an
+/// optional TOML file, environment variables and command-line flags, in that
+/// order of precedence.
+pub struct AppConfig {
+    pub server: ServerSettings,
+    pub database_url: String,
+    pub admin_api_key: Option<String>,
+    pub jwt: Option<JwtConfig>,
+    pub jwks_reload_interval: Duration,
+    pub subscription_store: SubscriptionStoreKind,
+    pub idempotency_ttl: Duration,
+    pub idempotency_lock_timeout: Duration,
+    pub rate_limit: RateLimitConfig,
+    pub webhooks: WebhookSettings,
+    pub payment_provider: Option<PaymentProviderSettings>,
+    pub business_events: BusinessEventSettings,
+    pub v1_deprecation: DeprecationPolicy,
+    pub observability: ObservabilityConfig,
+    layers: Layers,
+}
+
+impl AppConfig {
+    /// Loads the configuration from the process environment and `cli`.
+    pub fn load(cli: &Cli) -> anyhow::Result<Self> {
+        Self::load_with(cli, |name| std::env::var(name).ok())
+    }
+
+    fn load_with(cli: &Cli, env: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let mut errors = Vec::new();
+        let mut layers = Layers::default();
+
+        layers.defaults();
+        let file = cli.config.clone().or_else(|| {
+            env("CONFIG_FILE")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        });
+        if let Some(path) = file {
+            layers.file(&path, &mut errors)?;
+        }
+        layers.env(&env);
+        layers.cli(cli, &mut errors);
+
+        let mut config = Self::resolve(layers);
+        if let Ok(config) = &mut config {
+            errors.append(&mut config.validate());
+        }
+        match config {
+            Ok(config) if errors.is_empty() => Ok(config),
+            Ok(_) => Err(invalid(errors)),
+            Err(mut resolve_errors) => {
+                errors.append(&mut resolve_errors);
+                Err(invalid(errors))
+            }
+        }
+    }
+
+    fn resolve(layers: Layers) -> Result<Self, Vec<String>> {
+        let mut r = Resolver {
+            layers: &layers,
+            errors: Vec::new(),
+        };
+
+        let tls = match (
+            r.optional("server.tls_cert_path"),
+            r.optional("server.tls_key_path"),
+        ) {
+            (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+            }),
+            (None, None) => None,
+            (Some(_), None) => {
+                r.error(
+                    "server.tls_key_path",
+                    "must be set with server.tls_cert_path",
+                );
+                None
+            }
+            (None, Some(_)) => {
+                r.error(
+                    "server.tls_cert_path",
+                    "must be set with server.tls_key_path",
+                );
+                None
+            }
+        };
+        let server = ServerSettings {
+            host: r.required("server.host"),
+            port: r.number("server.port", "a port number (0-65535)"),
+            tls,
+            unix_socket: r.optional("server.unix_socket_path").map(PathBuf::from),
+            shutdown_readiness_delay: r.secs("server.shutdown_readiness_delay_secs"),
+            shutdown_drain_timeout: r.secs("server.shutdown_drain_timeout_secs"),
+            swagger_ui_enabled: r.bool("server.swagger_ui_enabled"),
+        };
+
+        let database_url = r.required("database.url");
+        let admin_api_key = r.optional("auth.admin_api_key");
+
+        let leeway_secs = r.number("auth.jwt_leeway_secs", "a number of seconds");
+        let jwt = r.optional("auth.jwt_jwks_path").map(|path| JwtConfig {
+            jwks_path: path.into(),
+            issuer: r.optional("auth.jwt_issuer"),
+            audience: r.optional("auth.jwt_audience"),
+            tenant_claim: r.required("auth.jwt_tenant_claim"),
+            roles_claim: r.required("auth.jwt_roles_claim"),
+            leeway_secs,
+        });
+        let jwks_reload_interval = r.secs("auth.jwt_jwks_reload_secs");
+
+        let snapshot_interval = r.positive("subscriptions.snapshot_interval");
+        let subscription_store = match r.text("subscriptions.store").as_str() {
+            "state" => SubscriptionStoreKind::State,
+            "event_sourced" => SubscriptionStoreKind::EventSourced { snapshot_interval },
+            _ => {
+                r.error("subscriptions.store", "must be `state` or `event_sourced`");
+                SubscriptionStoreKind::State
+            }
+        };
+
+        let idempotency_ttl = r.secs("idempotency.ttl_secs");
+        let idempotency_lock_timeout =
+            Duration::from_secs(r.positive("idempotency.lock_timeout_secs").into());
+
+        let mut plan_policies = HashMap::new();
+        for (plan, limit) in r.pairs("rate_limit.plans") {
+            match limit.parse::<u32>() {
+                Ok(requests_per_minute) => {
+                    plan_policies.insert(
+                        PlanId::new(plan),
+                        RateLimitPolicy {
+                            requests_per_minute,
+                        },
+                    );
+                }
+                Err(_) => r.error(
+                    "rate_limit.plans",
+                    format!("limit for `{}` must be a number", plan),
+                ),
+            }
+        }
+        let rate_limit = RateLimitConfig {
+            default_policy: RateLimitPolicy {
+                requests_per_minute: r.positive("rate_limit.default_per_minute"),
+            },
+            plan_policies,
+            tier_cache_ttl: r.secs("rate_limit.tier_cache_secs"),
+        };
+
+        let webhooks = WebhookSettings {
+            poll_interval: r.secs("webhooks.poll_interval_secs"),
+            timeout: r.secs("webhooks.timeout_secs"),
+            max_attempts: r.positive("webhooks.max_attempts"),
+        };
+
+        let business_events = BusinessEventSettings {
+            path: PathBuf::from(r.required("business_events.path")),
+            key: r.optional("business_events.key"),
+            relay_interval: Duration::from_secs(
+                r.positive("business_events.relay_interval_secs").into(),
+            ),
+        };
+
+        let payment_provider =
+            r.optional("payment_provider.url")
+                .map(|url| PaymentProviderSettings {
+                    url,
+                    api_key: r.text("payment_provider.api_key"),
+                });
+
+        let v1_deprecation = DeprecationPolicy {
+            deprecated_at: r
+                .optional("api.v1_deprecated_at")
+                .map(|raw| r.with("api.v1_deprecated_at", |_| parse_timestamp(&raw))),
+            sunset_at: r
+                .optional("api.v1_sunset_at")
+                .map(|raw| r.with("api.v1_sunset_at", |_| parse_timestamp(&raw))),
+            successor: "/api/v2".to_string(),
+        };
+
+        let sampler_ratio = r.with("observability.traces_sampler_ratio", |raw| {
+            match raw.trim().parse::<f64>() {
+                Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+                _ => Err("must be a number between 0 and 1".to_string()),
+            }
+        });
+        let sampling = SamplingConfig {
+            strategy: r.with("observability.traces_sampler", |raw| {
+                SamplingStrategy::parse(raw, sampler_ratio)
+                    .ok_or_else(|| format!("must be one of {}", SamplingStrategy::NAMES))
+            }),
+            keep_tenants: r
+                .text("observability.sampling_keep_tenants")
+                .split(',')
+                .map(str::trim)
+                .filter(|tenant| !tenant.is_empty())
+                .map(str::to_string)
+                .collect(),
+            health_checks_per_minute: r.number(
+                "observability.sampling_health_checks_per_minute",
+                "a whole number",
+            ),
+        };
+
+        let redaction = RedactionConfig {
+            fields: r.with("observability.redact_fields", |raw| {
+                parse_pairs(raw)?
+                    .into_iter()
+                    .map(|(field, action)| match RedactAction::parse(&action) {
+                        Some(action) => Ok((field, action)),
+                        None => Err(format!(
+                            "action `{}` for `{}` must be `hash`, `mask` or `drop`",
+                            action, field
+                        )),
+                    })
+                    .collect()
+            }),
+            hash_key: r.optional("observability.redact_hash_key"),
+        };
+
+        let log_file_path = PathBuf::from(r.required("observability.log_file_path"));
+        let max_size_bytes =
+            u64::from(r.positive("observability.log_file_max_size_mb")) * 1024 * 1024;
+        let log_rotation = RotationSettings {
+            policy: r.with("observability.log_file_rotation", |raw| {
+                match raw.trim().to_lowercase().as_str() {
+                    "minutely" => Ok(RotationPolicy::Minutely),
+                    "hourly" => Ok(RotationPolicy::Hourly),
+                    "daily" => Ok(RotationPolicy::Daily),
+                    "size" => Ok(RotationPolicy::Size(max_size_bytes)),
+                    _ => Err("must be `minutely`, `hourly`, `daily` or `size`".to_string()),
+                }
+            }),
+            max_files: r.number(
+                "observability.log_file_max_files",
+                "a number of files (0 keeps all)",
+            ),
+            compress: r.bool("observability.log_file_compress"),
+        };
+        let observability = ObservabilityConfig {
+            service_name: r.required("observability.service_name"),
+            log_filter: r.text("observability.log_filter"),
+            log_format: match r.text("observability.log_format").to_lowercase().as_str() {
+                "json" => LogFormat::Json,
+                "pretty" => LogFormat::Pretty,
+                _ => {
+                    r.error("observability.log_format", "must be `json` or `pretty`");
+                    LogFormat::Pretty
+                }
+            },
+            file_logging_enabled: r.bool("observability.log_file_enabled"),
+            log_file_dir: log_file_path
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| ".".to_string()),
+            log_file_name: log_file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            log_file_format: r.with("observability.log_file_format", |raw| {
+                FileLogFormat::parse(raw).ok_or_else(|| "must be `json` or `logfmt`".to_string())
+            }),
+            log_rotation,
+            otel_enabled: r.bool("observability.otel_tracing_enabled"),
+            otel_endpoint: r.required("observability.otlp_endpoint"),
+            otel_headers: r.pairs("observability.otlp_headers").into_iter().collect(),
+            otel_protocol: r.with("observability.otlp_protocol", |raw| {
+                OtlpProtocol::parse(raw)
+                    .ok_or_else(|| "must be `grpc` or `http/protobuf`".to_string())
+            }),
+            otel_timeout: Duration::from_secs(r.positive("observability.otlp_timeout_secs").into()),
+            otel_compression: r.with("observability.otlp_compression", |raw| {
+                OtlpCompression::parse(raw).ok_or_else(|| "must be `none` or `gzip`".to_string())
+            }),
+            otel_batch: BatchSettings {
+                max_queue_size: r.positive("observability.batch_max_queue_size") as usize,
+                max_export_batch_size: r.positive("observability.batch_max_export_batch_size")
+                    as usize,
+                scheduled_delay: Duration::from_millis(
+                    r.positive("observability.batch_schedule_delay_ms").into(),
+                ),
+                max_export_timeout: Duration::from_millis(
+                    r.positive("observability.batch_export_timeout_ms").into(),
+                ),
+            },
+            sampling,
+            redaction,
+            metrics_enabled: r.bool("observability.metrics_enabled"),
+            metrics_host: r.required("observability.metrics_host"),
+            metrics_port: r.number("observability.metrics_port", "a port number (0-65535)"),
+            business_metrics_interval: Duration::from_secs(
+                r.positive("observability.business_metrics_interval_secs")
+                    .into(),
+            ),
+            otel_metrics_enabled: r.bool("observability.otel_metrics_enabled"),
+            otel_metrics_interval: Duration::from_secs(
+                r.positive("observability.otel_metrics_interval_secs")
+                    .into(),
+            ),
+        };
+
+        if !r.errors.is_empty() {
+            return Err(r.errors);
+        }
+
+        Ok(Self {
+            server,
+            database_url,
+            admin_api_key,
+            jwt,
+            jwks_reload_interval,
+            subscription_store,
+            idempotency_ttl,
+            idempotency_lock_timeout,
+            rate_limit,
+            webhooks,
+            payment_provider,
+            business_events,
+            v1_deprecation,
+            observability,
+            layers,
+        })
+    }
+
+    /// Checks that need more than one setting, or the filesystem.
+    fn validate(&self) -> Vec<String> {
+        let mut r = Resolver {
+            layers: &self.layers,
+            errors: Vec::new(),
+        };
+
+        if let Some(tls) = &self.server.tls {
+            if !tls.cert_path.is_file() {
+                r.error("server.tls_cert_path", "file does not exist");
+            }
+            if !tls.key_path.is_file() {
+                r.error("server.tls_key_path", "file does not exist");
+            }
+        }
+        if let (Some(deprecated_at), Some(sunset_at)) = (
+            self.v1_deprecation.deprecated_at,
+            self.v1_deprecation.sunset_at,
+        ) {
+            if sunset_at <= deprecated_at {
+                r.error("api.v1_sunset_at", "must be after api.v1_deprecated_at");
+            }
+        }
+        if let Some(payment) = &self.payment_provider {
+            if let Err(e) = url::Url::parse(&payment.url) {
+                r.error("payment_provider.url", format!("must be a URL ({})", e));
+            }
+        }
+        if self.observability.metrics_enabled
+            && self.observability.metrics_port != 0
+            && self.observability.metrics_port == self.server.port
+            && self.observability.metrics_host == self.server.host
+        {
+            r.error(
+                "observability.metrics_port",
+                "must differ from server.port; metrics are served on their own listener",
+            );
+        }
+        if self.observability.otel_enabled || self.observability.otel_metrics_enabled {
+            if let Err(e) = url::Url::parse(&self.observability.otel_endpoint) {
+                r.error(
+                    "observability.otlp_endpoint",
+                    format!("must be a URL ({})", e),
+                );
+            }
+        }
+        let batch = &self.observability.otel_batch;
+        if batch.max_export_batch_size > batch.max_queue_size {
+            r.error(
+                "observability.batch_max_export_batch_size",
+                "must not exceed observability.batch_max_queue_size",
+            );
+        }
+        if let Err(e) = EnvFilter::try_new(&self.observability.log_filter) {
+            r.error(
+                "observability.log_filter",
+                format!("is not a valid filter ({})", e),
+            );
+        }
+        if self.observability.log_file_name.is_empty() {
+            r.error("observability.log_file_path", "must name a file");
+        }
+
+        r.errors
+    }
+
+    /// The effective configuration as TOML, with secrets redacted and each
+    /// value annotated with where it came from.
+    pub fn render_effective(&self) -> String {
+        let mut out = String::from("# Effective configuration. Secrets are redacted.\n");
+        let mut current_section = "";
+
+        for setting in SETTINGS {
+            let (section, name) = setting.key.split_once('.').unwrap_or(("", setting.key));
+            if section != current_section {
+                out.push_str(&format!("\n[{}]\n", section));
+                current_section = section;
+            }
+
+            let Some((value, source)) = self.layers.get(setting.key) else {
+                continue;
+            };
+            out.push_str(&format!(
+                "{} = {} # {}\n",
+                name,
+                render_value(setting, &redact(setting, value)),
+                source
+            ));
+        }
+
+        out
+    }
+}
+
+fn invalid(errors: Vec<String>) -> anyhow::Error {
+    let list: Vec<String> = errors.iter().map(|e| format!("  - {}", e)).collect();
+    anyhow::anyhow!("invalid configuration:\n{}", list.join("\n"))
+}
+
+fn redact(setting: &Setting, value: &str) -> String {
+    match setting.secret {
+        Secret::No => value.to_string(),
+        Secret::Yes if value.is_empty() => String::new(),
+        Secret::Yes => REDACTED.to_string(),
+        Secret::MapValues => match parse_pairs(value) {
+            Ok(pairs) => pairs
+                .into_iter()
+                .map(|(k, _)| format!("{}={}", k, REDACTED))
+                .collect::<Vec<_>>()
+                .join(","),
+            Err(_) => REDACTED.to_string(),
+        },
+        Secret::UrlPassword => match url::Url::parse(value) {
+            Ok(mut url) if url.password().is_some() => {
+                let _ = url.set_password(Some("redacted"));
+                url.to_string()
+            }
+            _ => value.to_string(),
+        },
+    }
+}
+
+fn render_value(setting: &Setting, value: &str) -> String {
+    match setting.kind {
+        Kind::Number if value.parse::<i64>().is_ok() => value.to_string(),
+        Kind::Bool if parse_bool(value).is_ok() => value.trim().to_lowercase(),
+        Kind::Map => {
+            let table: toml::Table = parse_pairs(value)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(k, v)| (k, toml::Value::String(v)))
+                .collect();
+            toml::Value::Table(table).to_string()
+        }
+        _ => toml::Value::String(value.to_string()).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_later_layers_override_earlier_ones() {
+        let dir = std::env::temp_dir().join(format!("config-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("config.toml");
+        std::fs::write(
+            &file,
+            "[server]\nport = 4000\nhost = \"0.0.0.0\"\n\n[database]\nurl = \"sqlite:file.db\"\n\n\
+             [rate_limit.plans]\nfree = 10\n",
+        )
+        .unwrap();
+
+        let cli = Cli {
+            config: Some(file),
+            port: Some("5000".to_string()),
+            overrides: vec!["webhooks.max_attempts=3".to_string()],
+            ..Cli::default()
+        };
+        let config = AppConfig::load_with(
+            &cli,
+            env_from(&[("HOST", "::"), ("WEBHOOK_MAX_ATTEMPTS", "5")]),
+        )
+        .unwrap();
+
+        assert_eq!(config.database_url, "sqlite:file.db");
+        assert_eq!(config.server.host, "::");
+        assert_eq!(config.server.port, 5000);
+        assert_eq!(config.webhooks.max_attempts, 3);
+        assert_eq!(config.rate_limit.plan_policies.len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_all_invalid_values_are_reported_together() {
+        let env = env_from(&[
+            ("PORT", "70000"),
+            ("LOG_FORMAT", "yaml"),
+            ("OTEL_TRACING_ENABLED", "yes"),
+            ("RATE_LIMIT_PLANS", "free=ten"),
+        ]);
+
+        let error = AppConfig::load_with(&Cli::default(), env)
+            .err()
+            .unwrap()
+            .to_string();
+
+        assert!(
+            error.contains("server.port = `70000` (env PORT)"),
+            "{}",
+            error
+        );
+        assert!(error.contains("observability.log_format"), "{}", error);
+        assert!(
+            error.contains("observability.otel_tracing_enabled"),
+            "{}",
+            error
+        );
+        assert!(error.contains("limit for `free`"), "{}", error);
+        assert!(error.contains("database.url"), "{}", error);
+    }
+
+    #[test]
+    fn test_effective_config_redacts_secrets() {
+        let env = env_from(&[
+            ("DATABASE_URL", "postgres://app:hunter2@db/ledger"),
+            ("ADMIN_API_KEY", "super-secret"),
+            ("OTEL_EXPORTER_OTLP_HEADERS", "authorization=Bearer abc"),
+        ]);
+        let config = AppConfig::load_with(&Cli::default(), env).unwrap();
+
+        let rendered = config.render_effective();
+
+        assert!(!rendered.contains("hunter2"), "{}", rendered);
+        assert!(!rendered.contains("super-secret"), "{}", rendered);
+        assert!(!rendered.contains("Bearer abc"), "{}", rendered);
+        assert!(
+            rendered.contains("authorization = \"<redacted>\""),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains("port = 3000 # default"), "{}", rendered);
+    }
+}
@@ -0,0 +1,19 @@
+use super::principal::Principal;
+
+/// Who is calling a use case and under which request, as recorded in the audit log.
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub actor: String,
+    pub principal: Principal,
+    pub request_id: Option<String>,
+}
+
+impl RequestContext {
+    pub fn new(principal: Principal, request_id: Option<String>) -> Self {
+        Self {
+            actor: principal.subject.clone(),
+            principal,
+            request_id,
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 
-use super::value_objects::{PlanId, SubscriptionId, TenantId};
+use super::principal::Role;
+use super::value_objects::{
+    PlanId, SubscriptionId, TenantId, WebhookDeliveryId, WebhookEndpointId,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Plan {
@@ -17,16 +21,199 @@ pub struct Subscription {
     pub tenant_id: TenantId,
     pub plan_id: PlanId,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Subscription {
     pub fn new(id: SubscriptionId, tenant_id: TenantId, plan_id: PlanId) -> Self {
+        let now = Utc::now();
         Self {
             id,
             tenant_id,
             plan_id,
-            created_at: Utc::now(),
+            created_at: now,
+            updated_at: now,
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub id: WebhookEndpointId,
+    pub tenant_id: TenantId,
+    pub url: String,
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Addresses webhooks may not be sent to, so a tenant cannot have the worker
+/// send signed requests to internal services: loopback, private, shared
+/// (100.64.0.0/10), link-local (including cloud metadata at 169.254.169.254),
+/// unspecified, broadcast and multicast ranges, IPv4-mapped ones included.
+pub fn is_internal_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || first == 0
+                || (first == 100 && second & 0xc0 == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_internal_address(IpAddr::V4(mapped)),
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local()
+                    || ip.is_multicast()
+            }
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "succeeded" => Some(Self::Succeeded),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: WebhookDeliveryId,
+    pub endpoint_id: WebhookEndpointId,
+    pub tenant_id: TenantId,
+    pub event_type: String,
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_code: Option<u16>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A single HTTP attempt made for a [`WebhookDelivery`], kept as the delivery log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryAttempt {
+    pub delivery_id: WebhookDeliveryId,
+    pub attempt: u32,
+    pub response_code: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub attempted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    #[serde(rename = "subscription.created")]
+    SubscriptionCreated,
+    #[serde(rename = "subscription.plan_changed")]
+    SubscriptionPlanChanged,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SubscriptionCreated => "subscription.created",
+            Self::SubscriptionPlanChanged => "subscription.plan_changed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "subscription.created" => Some(Self::SubscriptionCreated),
+            "subscription.plan_changed" => Some(Self::SubscriptionPlanChanged),
+            _ => None,
+        }
+    }
+}
+
+/// One mutation of a subscription: who did it, under which request, and the
+/// state before and after.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: String,
+    pub subscription_id: SubscriptionId,
+    pub tenant_id: TenantId,
+    pub actor: String,
+    pub action: AuditAction,
+    pub before: Option<Subscription>,
+    pub after: Option<Subscription>,
+    pub request_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A stored API key. Only the SHA-256 hash of the key is kept; the plaintext
+/// is shown once when the key is issued.
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: String,
+    pub key_hash: String,
+    pub tenant_id: Option<TenantId>,
+    pub role: Role,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// The log filter in effect and, after a temporary change, when it reverts.
+#[derive(Debug, Clone)]
+pub struct LogFilterStatus {
+    pub directives: String,
+    pub default_directives: String,
+    pub revert_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_internal_addresses_are_recognised() {
+        for internal in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(
+                is_internal_address(internal.parse().unwrap()),
+                "{}",
+                internal
+            );
+        }
+        for public in ["93.184.215.14", "100.128.0.1", "2606:4700::1111"] {
+            assert!(!is_internal_address(public.parse().unwrap()), "{}", public);
+        }
+    }
+}
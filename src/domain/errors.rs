@@ -1,24 +1,222 @@
 use thiserror::Error;
 
-use super::value_objects::{PlanId, TenantId};
+use super::principal::AccessDenied;
+use super::value_objects::{
+    PlanId, SubscriptionId, TenantId, WebhookDeliveryId, WebhookEndpointId,
+};
+
+/// Why a value from outside the service was rejected by a validating
+/// constructor. Says nothing about which field it was; callers add that.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    #[error("must not be empty")]
+    Empty,
+
+    #[error("must be at most {0} characters")]
+    TooLong(usize),
+
+    #[error("must start with `{0}` followed by at least one character")]
+    MissingPrefix(&'static str),
+
+    #[error("may only contain {0}")]
+    InvalidCharacters(&'static str),
+}
 
 #[derive(Debug, Error)]
 pub enum CreateSubscriptionError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
     #[error("plan {0} does not exist")]
     PlanNotFound(PlanId),
 
-    #[error("tenant {0} is not allowed on plan {1}")]
+    #[error("the tenant is not allowed on plan {1}")]
     PlanNotAllowed(TenantId, PlanId),
 
-    #[error("tenant {0} has no active payment method")]
+    #[error("the tenant has no active payment method")]
     MissingPaymentMethod(TenantId),
 
     #[error("an unexpected error occurred")]
     Unexpected(#[source] anyhow::Error),
 }
 
+impl CreateSubscriptionError {
+    /// The variant name, used as a low-cardinality metric label.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::PermissionDenied(_) => "PermissionDenied",
+            Self::PlanNotFound(_) => "PlanNotFound",
+            Self::PlanNotAllowed(_, _) => "PlanNotAllowed",
+            Self::MissingPaymentMethod(_) => "MissingPaymentMethod",
+            Self::Unexpected(_) => "Unexpected",
+        }
+    }
+}
+
 impl From<anyhow::Error> for CreateSubscriptionError {
     fn from(error: anyhow::Error) -> Self {
         Self::Unexpected(error)
     }
 }
+
+#[derive(Debug, Error)]
+pub enum ChangeSubscriptionPlanError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
+    #[error("subscription {0} does not exist")]
+    SubscriptionNotFound(SubscriptionId),
+
+    #[error("plan {0} does not exist")]
+    PlanNotFound(PlanId),
+
+    #[error("the tenant is not allowed on plan {1}")]
+    PlanNotAllowed(TenantId, PlanId),
+
+    #[error("the tenant has no active payment method")]
+    MissingPaymentMethod(TenantId),
+
+    #[error("subscription {0} was changed by another request")]
+    ConcurrentChange(SubscriptionId),
+
+    #[error("an unexpected error occurred")]
+    Unexpected(#[source] anyhow::Error),
+}
+
+impl From<anyhow::Error> for ChangeSubscriptionPlanError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListSubscriptionsError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
+    #[error("the configured subscription store cannot answer point-in-time queries")]
+    PointInTimeUnsupported,
+
+    #[error("an unexpected error occurred")]
+    Unexpected(#[source] anyhow::Error),
+}
+
+impl From<anyhow::Error> for ListSubscriptionsError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SubscriptionHistoryError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
+    #[error("subscription {0} does not exist")]
+    SubscriptionNotFound(SubscriptionId),
+
+    #[error("an unexpected error occurred")]
+    Unexpected(#[source] anyhow::Error),
+}
+
+impl From<anyhow::Error> for SubscriptionHistoryError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RegisterWebhookError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
+    #[error("webhook url {0} is not a valid http(s) url")]
+    InvalidUrl(String),
+
+    #[error("webhook url {0} points at an internal address")]
+    InternalAddress(String),
+
+    #[error("an unexpected error occurred")]
+    Unexpected(#[source] anyhow::Error),
+}
+
+impl From<anyhow::Error> for RegisterWebhookError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookDeliveryError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
+    #[error("webhook endpoint {0} does not exist")]
+    EndpointNotFound(WebhookEndpointId),
+
+    #[error("webhook delivery {0} does not exist")]
+    DeliveryNotFound(WebhookDeliveryId),
+
+    #[error("webhook delivery {0} is being sent")]
+    DeliveryInFlight(WebhookDeliveryId),
+
+    #[error("an unexpected error occurred")]
+    Unexpected(#[source] anyhow::Error),
+}
+
+impl From<anyhow::Error> for WebhookDeliveryError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AuthenticationError {
+    #[error("no credentials were presented")]
+    MissingCredentials,
+
+    #[error("the presented credentials are invalid or revoked")]
+    InvalidCredentials,
+
+    #[error("an unexpected error occurred")]
+    Unexpected(#[source] anyhow::Error),
+}
+
+impl From<anyhow::Error> for AuthenticationError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum IssueApiKeyError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
+    #[error("tenant-scoped roles must name a tenant and staff roles must not")]
+    InvalidTenantBinding,
+
+    #[error("an unexpected error occurred")]
+    Unexpected(#[source] anyhow::Error),
+}
+
+impl From<anyhow::Error> for IssueApiKeyError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Unexpected(error)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LogFilterError {
+    #[error("{0}")]
+    PermissionDenied(#[from] AccessDenied),
+
+    #[error("invalid filter directives: {0}")]
+    Invalid(String),
+
+    #[error("a filter more verbose than the configured one needs a revert delay")]
+    RevertRequired,
+
+    #[error("log filter could not be reloaded: {0}")]
+    Reload(String),
+}
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::entities::Subscription;
+use super::value_objects::{PlanId, SubscriptionId, TenantId};
+
+/// Domain events emitted by the subscription use cases.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum SubscriptionEvent {
+    #[serde(rename = "subscription.created")]
+    Created(Subscription),
+
+    #[serde(rename = "subscription.plan_changed")]
+    PlanChanged {
+        subscription_id: SubscriptionId,
+        tenant_id: TenantId,
+        from_plan_id: PlanId,
+        to_plan_id: PlanId,
+        changed_at: DateTime<Utc>,
+    },
+}
+
+/// Outcome of the card-on-file check for plans that require a payment
+/// method, recorded in the business event log. It is the only payment outcome
+/// this service sees; charges happen at the payment provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum PaymentEvent {
+    #[serde(rename = "payment.method_verified")]
+    MethodVerified {
+        tenant_id: TenantId,
+        plan_id: PlanId,
+    },
+
+    #[serde(rename = "payment.method_missing")]
+    MethodMissing {
+        tenant_id: TenantId,
+        plan_id: PlanId,
+    },
+}
+
+impl PaymentEvent {
+    pub fn checked(tenant_id: &TenantId, plan_id: &PlanId, has_payment_method: bool) -> Self {
+        let (tenant_id, plan_id) = (tenant_id.clone(), plan_id.clone());
+        if has_payment_method {
+            Self::MethodVerified { tenant_id, plan_id }
+        } else {
+            Self::MethodMissing { tenant_id, plan_id }
+        }
+    }
+}
+
+/// Events finance keeps a tamper-evident record of.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BusinessEvent {
+    Subscription(SubscriptionEvent),
+    Payment(PaymentEvent),
+}
+
+impl BusinessEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::Subscription(event) => event.event_type(),
+            Self::Payment(PaymentEvent::MethodVerified { .. }) => "payment.method_verified",
+            Self::Payment(PaymentEvent::MethodMissing { .. }) => "payment.method_missing",
+        }
+    }
+}
+
+/// A business event waiting in the outbox to be copied to the chained log.
+#[derive(Debug, Clone)]
+pub struct RecordedBusinessEvent {
+    /// Increases with every event recorded and is never reused.
+    pub outbox_id: i64,
+    pub event: BusinessEvent,
+    pub request_id: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl From<SubscriptionEvent> for BusinessEvent {
+    fn from(event: SubscriptionEvent) -> Self {
+        Self::Subscription(event)
+    }
+}
+
+impl From<PaymentEvent> for BusinessEvent {
+    fn from(event: PaymentEvent) -> Self {
+        Self::Payment(event)
+    }
+}
+
+impl SubscriptionEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::Created(_) => "subscription.created",
+            Self::PlanChanged { .. } => "subscription.plan_changed",
+        }
+    }
+
+    /// The plan change that turned `before` into `after`.
+    pub fn plan_changed(before: &Subscription, after: &Subscription) -> Self {
+        Self::PlanChanged {
+            subscription_id: after.id.clone(),
+            tenant_id: after.tenant_id.clone(),
+            from_plan_id: before.plan_id.clone(),
+            to_plan_id: after.plan_id.clone(),
+            changed_at: after.updated_at,
+        }
+    }
+
+    pub fn tenant_id(&self) -> &TenantId {
+        match self {
+            Self::Created(subscription) => &subscription.tenant_id,
+            Self::PlanChanged { tenant_id, .. } => tenant_id,
+        }
+    }
+
+    pub fn subscription_id(&self) -> &SubscriptionId {
+        match self {
+            Self::Created(subscription) => &subscription.id,
+            Self::PlanChanged {
+                subscription_id, ..
+            } => subscription_id,
+        }
+    }
+
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        match self {
+            Self::Created(subscription) => subscription.created_at,
+            Self::PlanChanged { changed_at, .. } => *changed_at,
+        }
+    }
+
+    /// Folds the event into the aggregate state. Events that do not fit the
+    /// current state (e.g. a plan change before creation) leave it untouched.
+    pub fn apply(&self, state: Option<Subscription>) -> Option<Subscription> {
+        match (self, state) {
+            (Self::Created(subscription), None) => Some(subscription.clone()),
+            (
+                Self::PlanChanged {
+                    to_plan_id,
+                    changed_at,
+                    ..
+                },
+                Some(mut subscription),
+            ) => {
+                subscription.plan_id = to_plan_id.clone();
+                subscription.updated_at = *changed_at;
+                Some(subscription)
+            }
+            (_, state) => state,
+        }
+    }
+}
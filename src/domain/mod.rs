@@ -1,9 +1,25 @@
+pub mod context;
 pub mod entities;
 pub mod errors;
+pub mod events;
+pub mod principal;
 pub mod requests;
 pub mod value_objects;
 
-pub use entities::{Plan, Subscription};
-pub use errors::CreateSubscriptionError;
-pub use requests::CreateSubscriptionRequest;
-pub use value_objects::{PlanId, SubscriptionId, TenantId};
+pub use context::RequestContext;
+pub use entities::{
+    is_internal_address, ApiKey, AuditAction, AuditEntry, LogFilterStatus, Plan, Subscription,
+    WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryStatus, WebhookEndpoint,
+};
+pub use errors::{
+    AuthenticationError, ChangeSubscriptionPlanError, CreateSubscriptionError, IssueApiKeyError,
+    ListSubscriptionsError, LogFilterError, RegisterWebhookError, SubscriptionHistoryError,
+    ValidationError, WebhookDeliveryError,
+};
+pub use events::{BusinessEvent, PaymentEvent, RecordedBusinessEvent, SubscriptionEvent};
+pub use principal::{AccessDenied, Permission, Principal, Role};
+pub use requests::{
+    ChangeSubscriptionPlanRequest, CreateSubscriptionRequest, IssueApiKeyRequest,
+    RegisterWebhookRequest,
+};
+pub use value_objects::{PlanId, SubscriptionId, TenantId, WebhookDeliveryId, WebhookEndpointId};
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+use super::value_objects::TenantId;
+
+/// What a caller wants to do. Every use case checks exactly one permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    CreateSubscription,
+    ChangeSubscriptionPlan,
+    ListSubscriptions,
+    ViewSubscriptionHistory,
+    ManageWebhooks,
+    ViewWebhookDeliveries,
+    ManageApiKeys,
+    ManageLogging,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CreateSubscription => "subscriptions:create",
+            Self::ChangeSubscriptionPlan => "subscriptions:change_plan",
+            Self::ListSubscriptions => "subscriptions:list",
+            Self::ViewSubscriptionHistory => "subscriptions:history",
+            Self::ManageWebhooks => "webhooks:manage",
+            Self::ViewWebhookDeliveries => "webhooks:read",
+            Self::ManageApiKeys => "api_keys:manage",
+            Self::ManageLogging => "logging:manage",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    TenantAdmin,
+    TenantViewer,
+    BillingOps,
+    PlatformAdmin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TenantAdmin => "tenant_admin",
+            Self::TenantViewer => "tenant_viewer",
+            Self::BillingOps => "billing_ops",
+            Self::PlatformAdmin => "platform_admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "tenant_admin" => Some(Self::TenantAdmin),
+            "tenant_viewer" => Some(Self::TenantViewer),
+            "billing_ops" => Some(Self::BillingOps),
+            "platform_admin" => Some(Self::PlatformAdmin),
+            _ => None,
+        }
+    }
+
+    /// Tenant roles only apply to the tenant the principal is bound to; the
+    /// other roles are held by staff and apply to every tenant.
+    pub fn is_tenant_scoped(&self) -> bool {
+        matches!(self, Self::TenantAdmin | Self::TenantViewer)
+    }
+
+    pub fn grants(&self, permission: Permission) -> bool {
+        use Permission::*;
+
+        match self {
+            Self::PlatformAdmin => true,
+            Self::TenantAdmin => !matches!(permission, ManageApiKeys | ManageLogging),
+            Self::TenantViewer => matches!(
+                permission,
+                ListSubscriptions | ViewSubscriptionHistory | ViewWebhookDeliveries
+            ),
+            Self::BillingOps => matches!(
+                permission,
+                CreateSubscription
+                    | ChangeSubscriptionPlan
+                    | ListSubscriptions
+                    | ViewSubscriptionHistory
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+#[error("{subject} lacks permission {permission}")]
+pub struct AccessDenied {
+    pub subject: String,
+    pub permission: Permission,
+    pub tenant_id: Option<TenantId>,
+}
+
+/// An authenticated caller. Tenant-scoped roles are bound to the principal's
+/// tenant; staff roles act on behalf of any tenant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub subject: String,
+    pub tenant_id: Option<TenantId>,
+    pub roles: Vec<Role>,
+}
+
+impl Principal {
+    pub fn new(subject: impl Into<String>, tenant_id: Option<TenantId>, roles: Vec<Role>) -> Self {
+        Self {
+            subject: subject.into(),
+            tenant_id,
+            roles,
+        }
+    }
+
+    pub fn can_access_tenant(&self, tenant_id: &TenantId) -> bool {
+        self.roles.iter().any(|r| !r.is_tenant_scoped())
+            || self.tenant_id.as_ref() == Some(tenant_id)
+    }
+
+    /// A role only counts towards a tenant when it applies to that tenant, so
+    /// a tenant admin of one tenant gains nothing on another.
+    pub fn authorize(
+        &self,
+        permission: Permission,
+        tenant_id: Option<&TenantId>,
+    ) -> Result<(), AccessDenied> {
+        let allowed = self.roles.iter().any(|role| {
+            role.grants(permission)
+                && match tenant_id {
+                    _ if !role.is_tenant_scoped() => true,
+                    Some(tenant_id) => self.tenant_id.as_ref() == Some(tenant_id),
+                    None => false,
+                }
+        });
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(AccessDenied {
+                subject: self.subject.clone(),
+                permission,
+                tenant_id: tenant_id.cloned(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tenant(role: Role) -> Principal {
+        Principal::new("api_key:1", Some(TenantId::new("tenant_a")), vec![role])
+    }
+
+    #[test]
+    fn test_tenant_roles_are_bound_to_their_tenant() {
+        let own = TenantId::new("tenant_a");
+        let other = TenantId::new("tenant_b");
+
+        let admin = tenant(Role::TenantAdmin);
+        assert!(admin
+            .authorize(Permission::ChangeSubscriptionPlan, Some(&own))
+            .is_ok());
+        assert!(admin
+            .authorize(Permission::ChangeSubscriptionPlan, Some(&other))
+            .is_err());
+        assert!(admin.authorize(Permission::ManageApiKeys, None).is_err());
+
+        let viewer = tenant(Role::TenantViewer);
+        assert!(viewer
+            .authorize(Permission::ListSubscriptions, Some(&own))
+            .is_ok());
+        assert!(viewer
+            .authorize(Permission::CreateSubscription, Some(&own))
+            .is_err());
+    }
+
+    #[test]
+    fn test_staff_roles_apply_to_every_tenant() {
+        let any = TenantId::new("tenant_z");
+
+        let ops = Principal::new("jwt:ops", None, vec![Role::BillingOps]);
+        assert!(ops
+            .authorize(Permission::ChangeSubscriptionPlan, Some(&any))
+            .is_ok());
+        assert!(ops
+            .authorize(Permission::ManageWebhooks, Some(&any))
+            .is_err());
+
+        let admin = Principal::new("jwt:root", None, vec![Role::PlatformAdmin]);
+        assert!(admin.authorize(Permission::ManageApiKeys, None).is_ok());
+    }
+}
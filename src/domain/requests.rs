@@ -1,4 +1,5 @@
-use super::value_objects::{PlanId, TenantId};
+use super::principal::Role;
+use super::value_objects::{PlanId, SubscriptionId, TenantId};
 
 #[derive(Debug, Clone)]
 pub struct CreateSubscriptionRequest {
@@ -6,3 +7,20 @@ pub struct CreateSubscriptionRequest {
     pub plan_id: PlanId,
 }
 
+#[derive(Debug, Clone)]
+pub struct ChangeSubscriptionPlanRequest {
+    pub subscription_id: SubscriptionId,
+    pub plan_id: PlanId,
+}
+
+#[derive(Debug, Clone)]
+pub struct RegisterWebhookRequest {
+    pub tenant_id: TenantId,
+    pub url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct IssueApiKeyRequest {
+    pub tenant_id: Option<TenantId>,
+    pub role: Role,
+}
@@ -1,13 +1,79 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use super::errors::ValidationError;
+
+struct IdRules {
+    max_len: usize,
+    prefix: Option<&'static str>,
+    allowed: fn(char) -> bool,
+    allowed_description: &'static str,
+}
+
+impl IdRules {
+    fn check(&self, value: &str) -> Result<(), ValidationError> {
+        if value.is_empty() {
+            return Err(ValidationError::Empty);
+        }
+        if value.len() > self.max_len {
+            return Err(ValidationError::TooLong(self.max_len));
+        }
+        if let Some(prefix) = self.prefix {
+            if !value.starts_with(prefix) || value.len() == prefix.len() {
+                return Err(ValidationError::MissingPrefix(prefix));
+            }
+        }
+        if !value.chars().all(self.allowed) {
+            return Err(ValidationError::InvalidCharacters(self.allowed_description));
+        }
+        Ok(())
+    }
+}
+
+fn lowercase_slug_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-'
+}
+
+fn identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+const TENANT_ID_RULES: IdRules = IdRules {
+    max_len: 64,
+    prefix: Some("tenant_"),
+    allowed: lowercase_slug_char,
+    allowed_description: "lowercase letters, digits, `_` and `-`",
+};
+
+const PLAN_ID_RULES: IdRules = IdRules {
+    max_len: 32,
+    prefix: None,
+    allowed: lowercase_slug_char,
+    allowed_description: "lowercase letters, digits, `_` and `-`",
+};
+
+const SUBSCRIPTION_ID_RULES: IdRules = IdRules {
+    max_len: 64,
+    prefix: None,
+    allowed: identifier_char,
+    allowed_description: "letters, digits, `_` and `-`",
+};
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TenantId(pub String);
 
 impl TenantId {
+    /// Wraps an id without checking it, for values that come from storage or
+    /// other trusted sources. Input from callers goes through [`Self::parse`].
     pub fn new(id: impl Into<String>) -> Self {
         Self(id.into())
     }
+
+    pub fn parse(id: impl Into<String>) -> Result<Self, ValidationError> {
+        let id = id.into();
+        TENANT_ID_RULES.check(&id)?;
+        Ok(Self(id))
+    }
 }
 
 impl fmt::Display for TenantId {
@@ -38,9 +104,17 @@ impl AsRef<str> for TenantId {
 pub struct PlanId(pub String);
 
 impl PlanId {
+    /// Wraps an id without checking it, for values that come from storage or
+    /// other trusted sources. Input from callers goes through [`Self::parse`].
     pub fn new(id: impl Into<String>) -> Self {
         Self(id.into())
     }
+
+    pub fn parse(id: impl Into<String>) -> Result<Self, ValidationError> {
+        let id = id.into();
+        PLAN_ID_RULES.check(&id)?;
+        Ok(Self(id))
+    }
 }
 
 impl fmt::Display for PlanId {
@@ -71,9 +145,17 @@ impl AsRef<str> for PlanId {
 pub struct SubscriptionId(pub String);
 
 impl SubscriptionId {
+    /// Wraps an id without checking it, for values that come from storage or
+    /// other trusted sources. Input from callers goes through [`Self::parse`].
     pub fn new(id: impl Into<String>) -> Self {
         Self(id.into())
     }
+
+    pub fn parse(id: impl Into<String>) -> Result<Self, ValidationError> {
+        let id = id.into();
+        SUBSCRIPTION_ID_RULES.check(&id)?;
+        Ok(Self(id))
+    }
 }
 
 impl fmt::Display for SubscriptionId {
@@ -99,3 +181,110 @@ impl AsRef<str> for SubscriptionId {
         &self.0
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WebhookEndpointId(pub String);
+
+impl WebhookEndpointId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+}
+
+impl fmt::Display for WebhookEndpointId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<String> for WebhookEndpointId {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&str> for WebhookEndpointId {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl AsRef<str> for WebhookEndpointId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WebhookDeliveryId(pub String);
+
+impl WebhookDeliveryId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+}
+
+impl fmt::Display for WebhookDeliveryId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<String> for WebhookDeliveryId {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<&str> for WebhookDeliveryId {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+impl AsRef<str> for WebhookDeliveryId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant_id_rules() {
+        assert!(TenantId::parse("tenant_with_payment").is_ok());
+        assert_eq!(TenantId::parse(""), Err(ValidationError::Empty));
+        assert_eq!(
+            TenantId::parse("acme"),
+            Err(ValidationError::MissingPrefix("tenant_"))
+        );
+        assert_eq!(
+            TenantId::parse("tenant_"),
+            Err(ValidationError::MissingPrefix("tenant_"))
+        );
+        assert!(matches!(
+            TenantId::parse("tenant_Acme Corp"),
+            Err(ValidationError::InvalidCharacters(_))
+        ));
+        assert_eq!(
+            TenantId::parse(format!("tenant_{}", "a".repeat(64))),
+            Err(ValidationError::TooLong(64))
+        );
+    }
+
+    #[test]
+    fn test_plan_and_subscription_id_rules() {
+        assert!(PlanId::parse("enterprise").is_ok());
+        assert!(matches!(
+            PlanId::parse("Pro"),
+            Err(ValidationError::InvalidCharacters(_))
+        ));
+        assert!(SubscriptionId::parse("7f1c5a8e-2d4b-4e0f-9a61-3b2c1d0e9f8a").is_ok());
+        assert!(matches!(
+            SubscriptionId::parse("../etc/passwd"),
+            Err(ValidationError::InvalidCharacters(_))
+        ));
+    }
+}
@@ -0,0 +1,440 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use flate2::{write::GzEncoder, Compression};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::{
+    format::Writer, FmtContext, FormatEvent, FormatFields, FormattedFields,
+};
+use tracing_subscriber::registry::LookupSpan;
+
+/// When the active log file is moved aside.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RotationPolicy {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    /// Once the file would grow past this many bytes.
+    Size(u64),
+}
+
+impl RotationPolicy {
+    /// Suffix of rotated files, as `tracing-appender` names them. Time-based
+    /// files are named after the period they cover, so a new period starts a
+    /// new file; size-based ones after the moment they were rotated.
+    fn suffix(&self, at: DateTime<Utc>) -> String {
+        let format = match self {
+            Self::Minutely => "%Y-%m-%d-%H-%M",
+            Self::Hourly => "%Y-%m-%d-%H",
+            Self::Daily => "%Y-%m-%d",
+            Self::Size(_) => "%Y-%m-%d-%H-%M-%S",
+        };
+        at.format(format).to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RotationSettings {
+    pub policy: RotationPolicy,
+    /// Rotated files kept next to the active one; 0 keeps all of them.
+    pub max_files: usize,
+    /// Gzip rotated files (`<name>.<suffix>.gz`).
+    pub compress: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FileLogFormat {
+    #[default]
+    Json,
+    Logfmt,
+}
+
+impl FileLogFormat {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "logfmt" => Some(Self::Logfmt),
+            _ => None,
+        }
+    }
+}
+
+/// Appends to `<dir>/<name>` and moves it to `<name>.<suffix>` on rotation.
+/// Compression and pruning of old files run on a helper thread so a large
+/// file does not hold up logging; the next rotation waits for it.
+pub struct RotatingFile {
+    dir: PathBuf,
+    name: String,
+    settings: RotationSettings,
+    file: File,
+    size: u64,
+    /// Suffix for the current file under a time-based policy.
+    period: String,
+    housekeeping: Option<JoinHandle<()>>,
+}
+
+impl RotatingFile {
+    pub fn new(dir: impl AsRef<Path>, name: &str, settings: RotationSettings) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(name);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        // A file left by an earlier run belongs to the period it was last
+        // written in, so it is rotated on the first write of a later one.
+        let last_written = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        Ok(Self {
+            period: settings.policy.suffix(last_written),
+            dir,
+            name: name.to_string(),
+            settings,
+            file,
+            size: metadata.len(),
+            housekeeping: None,
+        })
+    }
+
+    fn should_rotate(&self, incoming: usize) -> bool {
+        match self.settings.policy {
+            RotationPolicy::Size(max) => self.size > 0 && self.size + incoming as u64 > max,
+            policy => policy.suffix(Utc::now()) != self.period,
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let now = Utc::now();
+        let suffix = match self.settings.policy {
+            RotationPolicy::Size(_) => self.settings.policy.suffix(now),
+            _ => self.period.clone(),
+        };
+
+        let active = self.dir.join(&self.name);
+        // A suffix already taken (several rotations within one second, or a
+        // period reopened after a restart) gets the next index after the
+        // highest one, never a gap left by pruning, so order stays age order.
+        let taken = rotated_files(&self.dir, &self.name)?
+            .into_iter()
+            .filter(|(key, _)| key.0 == suffix)
+            .map(|(key, _)| key.1 + 1)
+            .max();
+        let rotated = match taken {
+            None => self.dir.join(format!("{}.{}", self.name, suffix)),
+            Some(n) => self.dir.join(format!("{}.{}.{}", self.name, suffix, n)),
+        };
+        fs::rename(&active, &rotated)?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(&active)?;
+        self.size = 0;
+        self.period = self.settings.policy.suffix(now);
+
+        if let Some(previous) = self.housekeeping.take() {
+            let _ = previous.join();
+        }
+        let (dir, name, settings) = (self.dir.clone(), self.name.clone(), self.settings.clone());
+        self.housekeeping = Some(std::thread::spawn(move || {
+            if settings.compress {
+                if let Err(e) = compress(&rotated) {
+                    eprintln!("Failed to compress {}: {}", rotated.display(), e);
+                }
+            }
+            if let Err(e) = prune(&dir, &name, settings.max_files) {
+                eprintln!("Failed to prune old log files in {}: {}", dir.display(), e);
+            }
+        }));
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.should_rotate(buf.len()) {
+            // Keep logging to the current file rather than losing lines.
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate log file {}: {}", self.name, e);
+            }
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for RotatingFile {
+    fn drop(&mut self) {
+        if let Some(housekeeping) = self.housekeeping.take() {
+            let _ = housekeeping.join();
+        }
+    }
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    PathBuf::from(gz)
+}
+
+fn compress(path: &Path) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(File::create(gz_path(path))?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+/// Where a rotated file `<name>.<suffix>[.<index>][.gz]` falls in rotation
+/// order: by its date suffix, then by its index, with no index coming first.
+fn rotation_key(name: &str, file_name: &str) -> Option<(String, u64)> {
+    let rest = file_name.strip_prefix(name)?.strip_prefix('.')?;
+    let rest = rest.strip_suffix(".gz").unwrap_or(rest);
+    match rest.split_once('.') {
+        None => Some((rest.to_string(), 0)),
+        Some((suffix, index)) => Some((suffix.to_string(), index.parse().ok()?)),
+    }
+}
+
+/// Rotated files of `name` in `dir`, oldest first.
+fn rotated_files(dir: &Path, name: &str) -> io::Result<Vec<((String, u64), PathBuf)>> {
+    let mut rotated: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let key = rotation_key(name, &entry.file_name().to_string_lossy())?;
+            Some((key, entry.path()))
+        })
+        .collect();
+    rotated.sort();
+    Ok(rotated)
+}
+
+/// Deletes the oldest rotated files beyond `max_files`.
+fn prune(dir: &Path, name: &str, max_files: usize) -> io::Result<()> {
+    if max_files == 0 {
+        return Ok(());
+    }
+    let rotated = rotated_files(dir, name)?;
+
+    let excess = rotated.len().saturating_sub(max_files);
+    for (_, path) in &rotated[..excess] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// `key=value` pairs, quoted when the value has spaces, quotes or `=`.
+fn logfmt_value(value: &str) -> String {
+    let plain = !value.is_empty()
+        && !value
+            .chars()
+            .any(|c| c == ' ' || c == '"' || c == '=' || c == '\\' || c.is_control());
+    if plain {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+struct LogfmtVisitor<'a> {
+    writer: Writer<'a>,
+    first: bool,
+    result: fmt::Result,
+}
+
+impl LogfmtVisitor<'_> {
+    fn pair(&mut self, field: &Field, value: &str) {
+        if self.result.is_err() || field.name().starts_with("log.") {
+            return;
+        }
+        let key = match field.name() {
+            "message" => "msg",
+            name => name,
+        };
+        let separator = if self.first { "" } else { " " };
+        self.first = false;
+        self.result = write!(self.writer, "{}{}={}", separator, key, logfmt_value(value));
+    }
+}
+
+impl Visit for LogfmtVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.pair(field, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.pair(field, &format!("{:?}", value));
+    }
+}
+
+/// Field formatter for [`Logfmt`], also used for the span fields it repeats
+/// on every line.
+#[derive(Default)]
+pub struct LogfmtFields;
+
+impl<'writer> FormatFields<'writer> for LogfmtFields {
+    fn format_fields<R: tracing_subscriber::field::RecordFields>(
+        &self,
+        writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut visitor = LogfmtVisitor {
+            writer,
+            first: true,
+            result: Ok(()),
+        };
+        fields.record(&mut visitor);
+        visitor.result
+    }
+}
+
+/// One `ts=… level=… target=… span=…` line per event, followed by the
+/// fields of the enclosing spans (outermost first) and of the event.
+pub struct Logfmt;
+
+impl<S, N> FormatEvent<S, N> for Logfmt
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        write!(
+            writer,
+            "ts={} level={} target={}",
+            Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true),
+            metadata.level().as_str().to_lowercase(),
+            logfmt_value(metadata.target())
+        )?;
+
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<_> = scope.from_root().collect();
+            let names: Vec<&str> = spans.iter().map(|span| span.name()).collect();
+            write!(writer, " span={}", logfmt_value(&names.join(">")))?;
+            for span in &spans {
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<FormattedFields<N>>() {
+                    if !fields.is_empty() {
+                        write!(writer, " {}", fields)?;
+                    }
+                }
+            }
+        }
+
+        write!(writer, " ")?;
+        ctx.format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_size_rotation_compresses_and_keeps_max_files() {
+        let dir = std::env::temp_dir().join(format!("log-file-test-{}", uuid::Uuid::new_v4()));
+        let settings = RotationSettings {
+            policy: RotationPolicy::Size(64),
+            max_files: 2,
+            compress: true,
+        };
+
+        // Rotations within the same second share a suffix and are told
+        // apart by their index.
+        let mut file = RotatingFile::new(&dir, "app.log", settings).unwrap();
+        for i in 0..5 {
+            file.write_all(format!("line {} {}\n", i, "x".repeat(50)).as_bytes())
+                .unwrap();
+        }
+        drop(file);
+
+        let rotated = rotated_files(&dir, "app.log").unwrap();
+        assert_eq!(rotated.len(), 2, "{:?}", rotated);
+        assert!(rotated
+            .iter()
+            .all(|(_, path)| path.extension().is_some_and(|ext| ext == "gz")));
+
+        let mut newest = String::new();
+        GzDecoder::new(File::open(&rotated[1].1).unwrap())
+            .read_to_string(&mut newest)
+            .unwrap();
+        assert!(newest.starts_with("line 3 "), "{}", newest);
+        assert!(fs::read_to_string(dir.join("app.log"))
+            .unwrap()
+            .starts_with("line 4 "));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_orders_by_suffix_then_index() {
+        let dir = std::env::temp_dir().join(format!("log-file-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut names = vec!["app.log.2026-10-17".to_string(), "app.log".to_string()];
+        names.extend((0..12).map(|n| match n {
+            0 => "app.log.2026-10-18.gz".to_string(),
+            n => format!("app.log.2026-10-18.{}.gz", n),
+        }));
+        for name in &names {
+            File::create(dir.join(name)).unwrap();
+        }
+
+        prune(&dir, "app.log", 3).unwrap();
+
+        let mut left: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "app.log",
+                "app.log.2026-10-18.10.gz",
+                "app.log.2026-10-18.11.gz",
+                "app.log.2026-10-18.9.gz",
+            ]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_logfmt_values_are_quoted_when_needed() {
+        assert_eq!(logfmt_value("pro"), "pro");
+        assert_eq!(logfmt_value(""), "\"\"");
+        assert_eq!(
+            logfmt_value("plan \"gold\"\nmissing"),
+            "\"plan \\\"gold\\\"\\nmissing\""
+        );
+    }
+}
@@ -1,42 +1,100 @@
 mod adapters;
+mod config;
 mod domain;
+mod log_file;
+mod metrics;
 mod observability;
+mod otlp;
 mod ports;
+mod sampling;
 mod services;
 
 use anyhow::Context;
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::{get, post, put},
     Router,
 };
 use sqlx::sqlite::SqlitePoolOptions;
-use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
+use utoipa_swagger_ui::{Config as SwaggerConfig, SwaggerUi};
 
-use adapters::inbound::http::{create_subscription_handler, health_check_handler, AppState};
+use adapters::inbound::http::{
+    authenticate, change_subscription_plan_handler, create_subscription_handler,
+    deprecation_middleware, get_log_filter_handler, http_metrics_middleware,
+    idempotency_middleware, issue_api_key_handler, list_subscriptions_handler,
+    list_webhook_deliveries_handler, liveness_handler, make_request_span, metrics_handler,
+    openapi_handler, problem_instance_middleware, readiness_handler, register_webhook_handler,
+    replay_webhook_delivery_handler, resolve_bind_addr, server, set_log_filter_handler,
+    subscription_history_handler, v2, AppState, AuthState, DeprecationPolicy, HealthState,
+    IdempotencyState, ListenerConfig, LogFilterState, RateLimitLayer, RequestIdLayer, WebhookState,
+    METRICS_PATH, OPENAPI_PATH, SWAGGER_UI_PATH,
+};
+use adapters::outbound::file::{verify_business_events, FileBusinessEventLog};
+use adapters::outbound::jwt::JwksTokenVerifier;
+use adapters::outbound::payment::client::PaymentClient;
 use adapters::outbound::sqlite::{
-    SqliteBillingProfileRepository, SqlitePlanRepository, SqliteSubscriptionRepository,
+    register_pool_metrics, EventSourcedSubscriptionRepository, SqliteApiKeyRepository,
+    SqliteAuditLog, SqliteBillingProfileRepository, SqliteBusinessEventOutbox,
+    SqliteIdempotencyStore, SqliteMigrationProbe, SqlitePingProbe, SqlitePlanRepository,
+    SqliteSubscriptionRepository, SqliteSubscriptionStore, SqliteWebhookRepository,
+};
+use adapters::outbound::webhook::HttpWebhookSender;
+use clap::Parser;
+use config::{AppConfig, Cli, SubscriptionStoreKind};
+use observability::{init_observability, shutdown_tracer, LogFilterHandle, OtlpExporterProbe};
+use ports::IdempotencyStore;
+use services::{
+    AuthService, BusinessEventRelay, BusinessMetricsCollector, HealthService, LogFilterService,
+    SubscriptionService, WebhookDeliveryWorker, WebhookRetryPolicy, WebhookService,
 };
-use observability::{init_observability, shutdown_tracer, ObservabilityConfig};
-use services::SubscriptionService;
+
+type SubscriptionState = AppState<
+    SqlitePlanRepository,
+    SqliteBillingProfileRepository,
+    SqliteSubscriptionStore,
+    SqliteWebhookRepository,
+    SqliteAuditLog,
+    SqliteBusinessEventOutbox,
+>;
+
+type ReadinessState =
+    HealthState<SqlitePingProbe, SqliteMigrationProbe, PaymentClient, OtlpExporterProbe>;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
-    let otel_config = ObservabilityConfig::from_env();
-    let _guard = init_observability(otel_config)?;
-
-    let database_url =
-        std::env::var("DATABASE_URL").context("DATABASE_URL environment variable not set")?;
+    let cli = Cli::parse();
+    let config = AppConfig::load(&cli)?;
+    if cli.print_config {
+        print!("{}", config.render_effective());
+        return Ok(());
+    }
+    if cli.verify_business_events {
+        let path = &config.business_events.path;
+        let head = verify_business_events(path, config.business_events.key.as_deref())
+            .with_context(|| {
+                format!("business event log {} failed verification", path.display())
+            })?;
+        println!(
+            "{}: {} events, chain intact, head {}",
+            path.display(),
+            head.seq,
+            head.hash
+        );
+        return Ok(());
+    }
 
-    let _host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let port = std::env::var("PORT")
-        .unwrap_or_else(|_| "3000".to_string())
-        .parse::<u16>()
-        .context("PORT must be a valid u16")?;
+    let observability_guard = init_observability(config.observability.clone())?;
+    let metrics = metrics::init_metrics(&config.observability)?;
 
+    let database_url = config.database_url.clone();
     info!(database_url = %database_url, "connecting to database");
 
     let pool = SqlitePoolOptions::new()
@@ -52,30 +110,790 @@ async fn main() -> anyhow::Result<()> {
         .await
         .context("failed to run database migrations")?;
 
+    let _pool_gauge =
+        register_pool_metrics(pool.clone()).context("failed to register pool metrics")?;
+
+    let workers_shutdown = CancellationToken::new();
+    let mut workers: Vec<JoinHandle<()>> = Vec::new();
+
+    let token_verifier = match config.jwt.clone() {
+        Some(jwt_config) => {
+            info!(jwks_path = %jwt_config.jwks_path.display(), "jwt authentication enabled");
+            let verifier = JwksTokenVerifier::new(jwt_config)?;
+            let watcher = verifier.clone();
+            let reload_interval = config.jwks_reload_interval;
+            let shutdown = workers_shutdown.clone();
+            workers.push(tokio::spawn(async move {
+                watcher.watch(reload_interval, shutdown).await
+            }));
+            Some(verifier)
+        }
+        None => None,
+    };
+
+    let auth_service = AuthService::new(SqliteApiKeyRepository::new(pool.clone()), token_verifier);
+
+    if let Some(key) = &config.admin_api_key {
+        auth_service
+            .register_bootstrap_admin_key(key)
+            .await
+            .context("failed to register ADMIN_API_KEY")?;
+        info!("bootstrap admin api key registered");
+    }
+
     let plan_repo = SqlitePlanRepository::new(pool.clone());
     let billing_repo = SqliteBillingProfileRepository::new(pool.clone());
-    let subscription_repo = SqliteSubscriptionRepository::new(pool.clone());
+    let webhook_repo = SqliteWebhookRepository::new(pool.clone());
+
+    let subscription_repo = match config.subscription_store {
+        SubscriptionStoreKind::State => {
+            info!(store = "state", "configuring subscription store");
+            SqliteSubscriptionStore::State(SqliteSubscriptionRepository::new(pool.clone()))
+        }
+        SubscriptionStoreKind::EventSourced { snapshot_interval } => {
+            info!(store = "event_sourced", "configuring subscription store");
+            SqliteSubscriptionStore::EventSourced(
+                EventSourcedSubscriptionRepository::new(pool.clone())
+                    .with_snapshot_interval(snapshot_interval),
+            )
+        }
+    };
+
+    let metrics_collector = BusinessMetricsCollector::new(subscription_repo.clone());
+    let business_metrics_interval = config.observability.business_metrics_interval;
+    let shutdown = workers_shutdown.clone();
+    workers.push(tokio::spawn(async move {
+        metrics_collector
+            .run(business_metrics_interval, shutdown)
+            .await
+    }));
+
+    let settings = &config.business_events;
+    let business_event_log = FileBusinessEventLog::open(&settings.path, settings.key.as_deref())
+        .with_context(|| {
+            format!(
+                "failed to open business event log {}",
+                settings.path.display()
+            )
+        })?;
+    let business_events = SqliteBusinessEventOutbox::new(pool.clone());
+    let relay = BusinessEventRelay::new(business_events.clone(), business_event_log);
+    let relay_interval = settings.relay_interval;
+    let shutdown = workers_shutdown.clone();
+    workers.push(tokio::spawn(async move {
+        relay.run(relay_interval, shutdown).await
+    }));
 
-    let subscription_service = SubscriptionService::new(plan_repo, billing_repo, subscription_repo);
+    let subscription_service = SubscriptionService::new(
+        plan_repo,
+        billing_repo,
+        subscription_repo.clone(),
+        webhook_repo.clone(),
+        SqliteAuditLog::new(pool.clone()),
+        business_events,
+    );
+    let webhook_service = WebhookService::new(webhook_repo.clone());
 
+    let webhook_worker = WebhookDeliveryWorker::new(
+        webhook_repo,
+        HttpWebhookSender::new(config.webhooks.timeout),
+        WebhookRetryPolicy {
+            max_attempts: config.webhooks.max_attempts,
+            ..WebhookRetryPolicy::default()
+        },
+        config.webhooks.timeout,
+    );
+    let shutdown = workers_shutdown.clone();
+    workers.push(tokio::spawn(async move {
+        webhook_worker
+            .run(config.webhooks.poll_interval, shutdown)
+            .await
+    }));
+
+    let idempotency_store = SqliteIdempotencyStore::new(pool.clone());
+    let purge_store = idempotency_store.clone();
+    let shutdown = workers_shutdown.clone();
+    workers.push(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            if let Ok(purged) = purge_store.purge_expired(chrono::Utc::now()).await {
+                info!(purged, "purged expired idempotency keys");
+            }
+        }
+    }));
+    let idempotency_state = IdempotencyState::new(
+        idempotency_store,
+        config.idempotency_ttl,
+        config.idempotency_lock_timeout,
+    );
+
+    let health_state = HealthState::new(HealthService::new(
+        SqlitePingProbe::new(pool.clone()),
+        SqliteMigrationProbe::new(pool.clone()),
+        config
+            .payment_provider
+            .as_ref()
+            .map(|provider| PaymentClient::new(provider.url.clone(), provider.api_key.clone())),
+        OtlpExporterProbe,
+    ));
+    let health_service = health_state.health_service.clone();
     let state = AppState::new(subscription_service);
+    let webhook_state = WebhookState::new(webhook_service);
+    let auth_state = AuthState::new(auth_service);
+    let log_filter_state =
+        LogFilterState::new(LogFilterService::new(observability_guard.log_filter()));
 
-    let app = Router::new()
-        .route("/health", get(health_check_handler))
-        .route("/api/subscriptions", post(create_subscription_handler))
-        .layer(TraceLayer::new_for_http())
-        .with_state(state);
+    let rate_limit = RateLimitLayer::new(subscription_repo, config.rate_limit.clone());
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    info!(address = %addr, "starting HTTP server");
+    let mut app = build_router(
+        health_state,
+        state,
+        webhook_state,
+        auth_state,
+        log_filter_state,
+        idempotency_state,
+        rate_limit,
+        config.v1_deprecation.clone(),
+    );
+    if config.server.swagger_ui_enabled {
+        info!(path = SWAGGER_UI_PATH, "serving swagger ui");
+        app = app.merge(SwaggerUi::new(SWAGGER_UI_PATH).config(SwaggerConfig::from(OPENAPI_PATH)));
+    }
 
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .context("failed to bind to address")?;
+    let listener_config = ListenerConfig {
+        addr: resolve_bind_addr(&config.server.host, config.server.port).await?,
+        tls: config.server.tls.clone(),
+        unix_socket: config.server.unix_socket.clone(),
+    };
+
+    let http_shutdown = CancellationToken::new();
+    let api_server = server::serve(app, listener_config, http_shutdown.clone());
+    let metrics_server = if config.observability.metrics_enabled {
+        let addr = resolve_bind_addr(
+            &config.observability.metrics_host,
+            config.observability.metrics_port,
+        )
+        .await?;
+        info!(%addr, path = METRICS_PATH, "serving prometheus metrics");
+        let metrics_app = Router::new().route(
+            METRICS_PATH,
+            get(metrics_handler).with_state(metrics.clone()),
+        );
+        let metrics_listener = ListenerConfig {
+            addr,
+            tls: None,
+            unix_socket: None,
+        };
+        Some(server::serve(
+            metrics_app,
+            metrics_listener,
+            http_shutdown.clone(),
+        ))
+    } else {
+        None
+    };
+    // Both listeners drain on the same token; either failing stops the other.
+    let mut server = tokio::spawn(async move {
+        match metrics_server {
+            Some(metrics_server) => tokio::try_join!(api_server, metrics_server).map(|_| ()),
+            None => api_server.await,
+        }
+    });
 
-    let result = axum::serve(listener, app).await.context("server error");
+    let result = tokio::select! {
+        result = &mut server => flatten_server_result(result),
+        signal = shutdown_signal() => {
+            info!(signal, "shutdown requested");
 
+            // Fail readiness first and give load balancers a chance to notice
+            // before the listener stops accepting connections.
+            health_service.begin_draining();
+            tokio::time::sleep(config.server.shutdown_readiness_delay).await;
+
+            info!(
+                drain_timeout_secs = config.server.shutdown_drain_timeout.as_secs(),
+                "draining in-flight requests"
+            );
+            http_shutdown.cancel();
+            match tokio::time::timeout(config.server.shutdown_drain_timeout, &mut server)
+                .await
+            {
+                Ok(result) => flatten_server_result(result),
+                Err(_) => {
+                    warn!("drain timeout elapsed, dropping remaining connections");
+                    server.abort();
+                    Ok(())
+                }
+            }
+        }
+    };
+
+    workers_shutdown.cancel();
+    for worker in workers {
+        match tokio::time::timeout(config.server.shutdown_drain_timeout, worker).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!(error = %e, "background worker panicked"),
+            Err(_) => warn!("background worker did not stop within the drain timeout"),
+        }
+    }
+    info!("background workers stopped");
+
+    pool.close().await;
+    info!("database pool closed");
+
+    metrics.shutdown();
     shutdown_tracer();
 
     result
 }
+
+fn flatten_server_result(
+    result: Result<anyhow::Result<()>, tokio::task::JoinError>,
+) -> anyhow::Result<()> {
+    result.context("server task failed")?
+}
+
+/// Resolves on SIGINT (ctrl-c) or, on Unix, SIGTERM, returning the signal name.
+async fn shutdown_signal() -> &'static str {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!(error = %e, "failed to listen for ctrl-c");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!(error = %e, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => "SIGINT",
+        _ = terminate => "SIGTERM",
+    }
+}
+
+/// Every route the service exposes. Kept apart from `main` so the OpenAPI
+/// drift test can exercise the same router.
+///
+/// `/api/v1` and `/api/v2` differ only in the subscription resources; webhook
+/// and admin routes are shared. The unversioned `/api` paths predate
+/// versioning and keep serving v1.
+#[allow(clippy::too_many_arguments)]
+fn build_router(
+    health_state: ReadinessState,
+    state: SubscriptionState,
+    webhook_state: WebhookState<SqliteWebhookRepository>,
+    auth_state: AuthState<SqliteApiKeyRepository, JwksTokenVerifier>,
+    log_filter_state: LogFilterState<LogFilterHandle>,
+    idempotency_state: Arc<IdempotencyState<SqliteIdempotencyStore>>,
+    rate_limit: RateLimitLayer<SqliteSubscriptionStore>,
+    v1_deprecation: DeprecationPolicy,
+) -> Router {
+    let idempotency = middleware::from_fn_with_state(idempotency_state, idempotency_middleware);
+
+    let webhook_routes = Router::new()
+        .route(
+            "/tenants/:tenant_id/webhooks",
+            post(register_webhook_handler),
+        )
+        .route(
+            "/tenants/:tenant_id/webhooks/:endpoint_id/deliveries",
+            get(list_webhook_deliveries_handler),
+        )
+        .route(
+            "/tenants/:tenant_id/webhook-deliveries/:delivery_id/replay",
+            post(replay_webhook_delivery_handler),
+        )
+        .with_state(webhook_state);
+
+    let log_filter_routes = Router::new()
+        .route(
+            "/admin/log-filter",
+            get(get_log_filter_handler).put(set_log_filter_handler),
+        )
+        .with_state(log_filter_state);
+
+    let admin_routes = Router::new()
+        .route("/admin/api-keys", post(issue_api_key_handler))
+        .with_state(auth_state.clone())
+        .merge(log_filter_routes);
+
+    let shared_routes = webhook_routes.merge(admin_routes);
+
+    let v1_routes = Router::new()
+        .route(
+            "/subscriptions",
+            post(create_subscription_handler).layer(idempotency.clone()),
+        )
+        .route(
+            "/subscriptions/:subscription_id/plan",
+            put(change_subscription_plan_handler),
+        )
+        .route(
+            "/subscriptions/:subscription_id/history",
+            get(subscription_history_handler),
+        )
+        .route(
+            "/tenants/:tenant_id/subscriptions",
+            get(list_subscriptions_handler),
+        )
+        .with_state(state.clone())
+        .merge(shared_routes.clone())
+        .layer(middleware::from_fn_with_state(
+            Arc::new(v1_deprecation),
+            deprecation_middleware,
+        ));
+
+    let v2_routes = Router::new()
+        .route(
+            "/subscriptions",
+            post(v2::create_subscription_handler).layer(idempotency),
+        )
+        .route(
+            "/subscriptions/:subscription_id/plan",
+            put(v2::change_subscription_plan_handler),
+        )
+        .route(
+            "/subscriptions/:subscription_id/history",
+            get(v2::subscription_history_handler),
+        )
+        .route(
+            "/tenants/:tenant_id/subscriptions",
+            get(v2::list_subscriptions_handler),
+        )
+        .with_state(state)
+        .merge(shared_routes);
+
+    let api_routes = Router::new()
+        .nest("/api/v1", v1_routes.clone())
+        .nest("/api/v2", v2_routes)
+        .nest("/api", v1_routes)
+        .route_layer(rate_limit)
+        .route_layer(middleware::from_fn_with_state(auth_state, authenticate));
+
+    Router::new()
+        .route("/health/live", get(liveness_handler))
+        .route(
+            "/health/ready",
+            get(readiness_handler).with_state(health_state),
+        )
+        .route(OPENAPI_PATH, get(openapi_handler))
+        .merge(api_routes)
+        .layer(middleware::from_fn(http_metrics_middleware))
+        .layer(middleware::from_fn(problem_instance_middleware))
+        .layer(TraceLayer::new_for_http().make_span_with(make_request_span))
+        .layer(RequestIdLayer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Method, Request, StatusCode},
+    };
+    use chrono::Utc;
+    use sqlx::SqlitePool;
+    use std::collections::{BTreeSet, HashMap};
+    use tower::ServiceExt;
+    use utoipa::OpenApi;
+
+    use adapters::inbound::http::openapi::ApiDoc;
+    use adapters::inbound::http::{RateLimitConfig, RateLimitPolicy};
+
+    const ADMIN_KEY: &str = "test-admin-key";
+
+    async fn test_pool() -> SqlitePool {
+        // A single connection, since every sqlite::memory: connection is its own database.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        pool
+    }
+
+    async fn test_router() -> Router {
+        test_router_on(test_pool().await).await
+    }
+
+    async fn test_router_on(pool: SqlitePool) -> Router {
+        let auth_service = AuthService::new(SqliteApiKeyRepository::new(pool.clone()), None);
+        auth_service
+            .register_bootstrap_admin_key(ADMIN_KEY)
+            .await
+            .unwrap();
+        let subscription_repo =
+            SqliteSubscriptionStore::State(SqliteSubscriptionRepository::new(pool.clone()));
+        let webhook_repo = SqliteWebhookRepository::new(pool.clone());
+
+        build_router(
+            HealthState::new(HealthService::new(
+                SqlitePingProbe::new(pool.clone()),
+                SqliteMigrationProbe::new(pool.clone()),
+                None,
+                OtlpExporterProbe,
+            )),
+            AppState::new(SubscriptionService::new(
+                SqlitePlanRepository::new(pool.clone()),
+                SqliteBillingProfileRepository::new(pool.clone()),
+                subscription_repo.clone(),
+                webhook_repo.clone(),
+                SqliteAuditLog::new(pool.clone()),
+                SqliteBusinessEventOutbox::new(pool.clone()),
+            )),
+            WebhookState::new(WebhookService::new(webhook_repo)),
+            AuthState::new(auth_service),
+            LogFilterState::new(LogFilterService::new(
+                LogFilterHandle::new("info").unwrap().0,
+            )),
+            IdempotencyState::new(
+                SqliteIdempotencyStore::new(pool.clone()),
+                Duration::from_secs(60),
+                Duration::from_secs(60),
+            ),
+            RateLimitLayer::new(
+                subscription_repo,
+                RateLimitConfig {
+                    default_policy: RateLimitPolicy {
+                        requests_per_minute: 10_000,
+                    },
+                    plan_policies: HashMap::new(),
+                    tier_cache_ttl: Duration::from_secs(60),
+                },
+            ),
+            DeprecationPolicy {
+                deprecated_at: Some(Utc::now()),
+                sunset_at: None,
+                successor: "/api/v2".to_string(),
+            },
+        )
+    }
+
+    /// Served, but not part of the API the document describes.
+    const UNDOCUMENTED_PATHS: &[&str] = &[OPENAPI_PATH, SWAGGER_UI_PATH];
+
+    /// Every path `router` serves, in OpenAPI syntax. axum has no way to list
+    /// routes, so they are read from the router's `Debug` output, which maps
+    /// each route id to its path; the fallback's own paths are left out.
+    fn router_paths(router: &Router) -> BTreeSet<String> {
+        let debug = format!("{router:?}");
+        let paths: BTreeSet<String> = debug
+            .split("): \"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .filter(|path| path.starts_with('/') && *path != "/" && !path.contains("__private__"))
+            .map(|path| {
+                path.split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(name) => format!("{{{name}}}"),
+                        None => segment.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect();
+        assert!(
+            paths.contains("/health/live"),
+            "router Debug output no longer lists paths: {debug}"
+        );
+        paths
+    }
+
+    /// The methods `router` serves on `path`, from the `Allow` header of the
+    /// 405 a TRACE request gets. Sent as an admin because the auth layer also
+    /// wraps the 405 fallback.
+    async fn served_methods(router: &Router, path: &str) -> BTreeSet<String> {
+        let uri = path
+            .split('/')
+            .map(|segment| {
+                if segment.starts_with('{') {
+                    "tenant_sample"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::TRACE)
+                    .uri(&uri)
+                    .header("x-api-key", ADMIN_KEY)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED, "{path}");
+
+        response.headers()[header::ALLOW]
+            .to_str()
+            .unwrap()
+            .split(',')
+            .map(|method| method.trim().to_string())
+            .filter(|method| method != "HEAD")
+            .collect()
+    }
+
+    /// The documented operations must be exactly the routes `build_router`
+    /// serves, less the undocumented ones, in both directions.
+    #[tokio::test]
+    async fn test_openapi_spec_matches_routes() {
+        let router = test_router().await;
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3.1"));
+
+        let mut documented = BTreeSet::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in ["get", "post", "put", "patch", "delete"] {
+                if item.get(method).is_some() {
+                    documented.insert((method.to_uppercase(), path.clone()));
+                }
+            }
+        }
+
+        let mut served = BTreeSet::new();
+        for path in router_paths(&router) {
+            if UNDOCUMENTED_PATHS.contains(&path.as_str()) {
+                continue;
+            }
+            for method in served_methods(&router, &path).await {
+                served.insert((method, path.clone()));
+            }
+        }
+        // Guards against the probe quietly finding nothing.
+        assert!(served.contains(&("PUT".to_string(), "/api/v2/admin/log-filter".to_string())));
+
+        assert_eq!(
+            documented.difference(&served).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "documented but not served"
+        );
+        assert_eq!(
+            served.difference(&documented).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "served but not documented"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_documented_error_responses_are_problem_details() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let mut operation_ids = std::collections::HashSet::new();
+
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            // Readiness reports its 503 as a health document, not a problem.
+            let is_probe = path.starts_with("/health/");
+
+            for (method, operation) in item.as_object().unwrap() {
+                let operation_id = operation["operationId"].as_str().unwrap();
+                assert!(
+                    operation_ids.insert(operation_id.to_string()),
+                    "operationId {} is used twice",
+                    operation_id
+                );
+                for (status, response) in operation["responses"].as_object().unwrap() {
+                    if !is_probe && (status.starts_with('4') || status.starts_with('5')) {
+                        assert!(
+                            response["content"]
+                                .get("application/problem+json")
+                                .is_some(),
+                            "{} {} {} is not documented as problem+json",
+                            method,
+                            path,
+                            status
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscription_history_lists_each_change_with_its_request() {
+        let router = test_router().await;
+        let send = |method: Method, uri: String, request_id: &str, body: serde_json::Value| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("x-api-key", ADMIN_KEY)
+                .header("x-request-id", request_id)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let router = router.clone();
+            async move {
+                let response = router.oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+
+        let (status, created) = send(
+            Method::POST,
+            "/api/v1/subscriptions".to_string(),
+            "req_create",
+            serde_json::json!({"tenant_id": "tenant_with_payment", "plan_id": "pro"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = created["id"].as_str().unwrap();
+
+        let (status, _) = send(
+            Method::PUT,
+            format!("/api/v1/subscriptions/{id}/plan"),
+            "req_upgrade",
+            serde_json::json!({"plan_id": "enterprise"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, history) = send(
+            Method::GET,
+            format!("/api/v1/subscriptions/{id}/history"),
+            "req_history",
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let changes: Vec<_> = history
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| {
+                (
+                    entry["action"].as_str().unwrap(),
+                    entry["request_id"].as_str().unwrap(),
+                    entry["before"]["plan_id"].as_str(),
+                    entry["after"]["plan_id"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("subscription.created", "req_create", None, "pro"),
+                (
+                    "subscription.plan_changed",
+                    "req_upgrade",
+                    Some("pro"),
+                    "enterprise"
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_a_server_error_creates_the_subscription_once() {
+        let pool = test_pool().await;
+        let router = test_router_on(pool.clone()).await;
+        let create = || {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/api/v2/subscriptions")
+                .header("x-api-key", ADMIN_KEY)
+                .header("idempotency-key", "signup-42")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({"tenant_id": "tenant_no_payment", "plan_id": "free"})
+                        .to_string(),
+                ))
+                .unwrap();
+            router.clone().oneshot(request)
+        };
+
+        // The outbox row is the last write of the change's transaction.
+        sqlx::query(
+            "CREATE TRIGGER reject_outbox BEFORE INSERT ON business_event_outbox BEGIN SELECT RAISE(ABORT, 'outbox unavailable'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let failed = create().await.unwrap();
+        assert_eq!(failed.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        sqlx::query("DROP TRIGGER reject_outbox")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let retried = create().await.unwrap();
+        assert_eq!(retried.status(), StatusCode::CREATED);
+        let replayed = create().await.unwrap();
+        assert_eq!(replayed.status(), StatusCode::CREATED);
+        assert_eq!(replayed.headers()["idempotent-replayed"], "true");
+
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v2/tenants/tenant_no_payment/subscriptions")
+                    .header("x-api-key", ADMIN_KEY)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listed.as_array().unwrap().len(), 1, "{listed}");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_echoed_or_generated_and_set_as_problem_instance() {
+        let router = test_router().await;
+
+        for sent in [Some("req_7f3a"), None, Some("not a valid id")] {
+            let mut request = Request::builder().uri("/api/v2/tenants/tenant_sample/subscriptions");
+            if let Some(id) = sent {
+                request = request.header("x-request-id", id);
+            }
+            let response = router
+                .clone()
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            let id = response.headers()["x-request-id"]
+                .to_str()
+                .unwrap()
+                .to_string();
+            match sent {
+                Some("req_7f3a") => assert_eq!(id, "req_7f3a"),
+                _ => assert!(uuid::Uuid::parse_str(&id).is_ok(), "{}", id),
+            }
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(problem["instance"], id.as_str());
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use opentelemetry::metrics::{Counter, Histogram, MeterProvider as _, Unit};
+use opentelemetry::{global, KeyValue};
+use opentelemetry_sdk::metrics::{
+    new_view,
+    reader::{DefaultAggregationSelector, DefaultTemporalitySelector},
+    Aggregation, Instrument, MeterProvider, PeriodicReader, Stream,
+};
+use opentelemetry_sdk::{runtime, Resource};
+use prometheus::{Encoder as _, Registry, TextEncoder};
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+use crate::observability::ObservabilityConfig;
+use crate::otlp::otlp_exporter;
+
+const METER_NAME: &str = "hexagonal-rust";
+
+/// Bucket bounds, in seconds, for every `*.duration` histogram.
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Instruments recorded from request handling code. They are created on
+/// first use, which happens after `init_metrics` has installed the global
+/// meter provider; before that (and in tests) they are no-ops.
+pub struct Instruments {
+    pub http_request_duration: Histogram<f64>,
+    pub subscription_create_errors: Counter<u64>,
+    pub payment_request_duration: Histogram<f64>,
+}
+
+static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+
+pub fn instruments() -> &'static Instruments {
+    INSTRUMENTS.get_or_init(|| {
+        let meter = global::meter(METER_NAME);
+        Instruments {
+            http_request_duration: meter
+                .f64_histogram("http.server.request.duration")
+                .with_description("Duration of HTTP requests by route and status")
+                .with_unit(Unit::new("s"))
+                .init(),
+            subscription_create_errors: meter
+                .u64_counter("subscription.create.errors")
+                .with_description("Failed subscription creations by error variant")
+                .init(),
+            payment_request_duration: meter
+                .f64_histogram("payment.client.request.duration")
+                .with_description("Duration of payment provider calls by operation and outcome")
+                .with_unit(Unit::new("s"))
+                .init(),
+        }
+    })
+}
+
+/// Active subscriptions per plan, refreshed by the business metrics collector
+/// and read by an observable gauge at collection time.
+static ACTIVE_SUBSCRIPTIONS: Mutex<Vec<(String, u64)>> = Mutex::new(Vec::new());
+
+pub fn set_active_subscriptions(counts: Vec<(String, u64)>) {
+    if let Ok(mut current) = ACTIVE_SUBSCRIPTIONS.lock() {
+        *current = counts;
+    }
+}
+
+/// Owns the meter provider. Scraped through [`render`](Self::render) and
+/// optionally pushed to the OTLP endpoint on an interval.
+#[derive(Clone)]
+pub struct MetricsHandle {
+    provider: MeterProvider,
+    registry: Registry,
+}
+
+impl fmt::Debug for MetricsHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsHandle").finish_non_exhaustive()
+    }
+}
+
+pub fn init_metrics(config: &ObservabilityConfig) -> anyhow::Result<MetricsHandle> {
+    let registry = Registry::new();
+    let prometheus = prometheus_exporter(registry.clone())?;
+    let duration_view = new_view(
+        Instrument::new().name("*.duration"),
+        Stream::new().aggregation(Aggregation::ExplicitBucketHistogram {
+            boundaries: DURATION_BUCKETS.to_vec(),
+            record_min_max: true,
+        }),
+    )?;
+
+    let mut builder = MeterProvider::builder()
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", config.service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ]))
+        .with_reader(prometheus)
+        .with_view(duration_view);
+
+    if config.otel_metrics_enabled {
+        let exporter = opentelemetry_otlp::MetricsExporterBuilder::from(otlp_exporter(config)?)
+            .build_metrics_exporter(
+                Box::new(DefaultTemporalitySelector::new()),
+                Box::new(DefaultAggregationSelector::new()),
+            )?;
+        let periodic = PeriodicReader::builder(exporter, runtime::Tokio)
+            .with_interval(config.otel_metrics_interval)
+            .build();
+        builder = builder.with_reader(periodic);
+    }
+
+    let provider = builder.build();
+    global::set_meter_provider(provider.clone());
+
+    let meter = provider.meter(METER_NAME);
+    meter
+        .u64_observable_gauge("subscriptions.active")
+        .with_description("Active subscriptions per plan")
+        .with_callback(|observer| {
+            if let Ok(counts) = ACTIVE_SUBSCRIPTIONS.lock() {
+                for (plan, count) in counts.iter() {
+                    observer.observe(*count, &[KeyValue::new("plan_id", plan.clone())]);
+                }
+            }
+        })
+        .try_init()?;
+
+    tracing::info!(
+        otlp_push = config.otel_metrics_enabled,
+        "metrics initialized"
+    );
+
+    Ok(MetricsHandle { provider, registry })
+}
+
+/// Uses the OpenTelemetry-to-Prometheus naming (dots become underscores, the
+/// unit becomes a suffix, counters get `_total`) without the per-scope labels,
+/// since every instrument comes from the one meter.
+fn prometheus_exporter(
+    registry: Registry,
+) -> anyhow::Result<opentelemetry_prometheus::PrometheusExporter> {
+    Ok(opentelemetry_prometheus::exporter()
+        .with_registry(registry)
+        .without_scope_info()
+        .build()?)
+}
+
+impl MetricsHandle {
+    /// Collects every instrument and encodes it in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut text = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut text) {
+            tracing::warn!(error = %e, "failed to encode metrics");
+        }
+        String::from_utf8(text).unwrap_or_default()
+    }
+
+    /// Pushes a final OTLP export, if enabled, and stops the readers.
+    pub fn shutdown(&self) {
+        // The 0.21 periodic reader marks itself shut down before its final
+        // collect, so that export always fails. Flush first instead and
+        // ignore the error shutdown reports for it.
+        if let Err(e) = self.provider.force_flush() {
+            eprintln!("Failed to flush metrics: {}", e);
+        }
+        let _ = self.provider.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_encoding_of_counters_and_histograms() {
+        let registry = Registry::new();
+        let provider = MeterProvider::builder()
+            .with_reader(prometheus_exporter(registry.clone()).unwrap())
+            .with_view(
+                new_view(
+                    Instrument::new().name("*.duration"),
+                    Stream::new().aggregation(Aggregation::ExplicitBucketHistogram {
+                        boundaries: vec![0.1, 1.0],
+                        record_min_max: false,
+                    }),
+                )
+                .unwrap(),
+            )
+            .build();
+        let meter = provider.meter("test");
+
+        let errors = meter.u64_counter("subscription.create.errors").init();
+        errors.add(2, &[KeyValue::new("error", "PlanNotFound")]);
+        let latency = meter
+            .f64_histogram("http.server.request.duration")
+            .with_unit(Unit::new("s"))
+            .init();
+        latency.record(
+            0.05,
+            &[KeyValue::new("http.route", "/api/v2/subscriptions")],
+        );
+        latency.record(0.5, &[KeyValue::new("http.route", "/api/v2/subscriptions")]);
+
+        let handle = MetricsHandle { provider, registry };
+        let text = handle.render();
+
+        assert!(
+            text.contains("# TYPE subscription_create_errors_total counter"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("subscription_create_errors_total{error=\"PlanNotFound\"} 2"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains(
+                "http_server_request_duration_seconds_bucket{http_route=\"/api/v2/subscriptions\",le=\"0.1\"} 1"
+            ),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains(
+                "http_server_request_duration_seconds_bucket{http_route=\"/api/v2/subscriptions\",le=\"+Inf\"} 2"
+            ),
+            "{}",
+            text
+        );
+    }
+}
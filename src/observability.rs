@@ -1,29 +1,66 @@
-use opentelemetry::KeyValue;
-use opentelemetry_otlp::WithExportConfig;
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::SpanExporterBuilder;
 use opentelemetry_sdk::{
+    propagation::{BaggagePropagator, TextMapCompositePropagator, TraceContextPropagator},
     runtime,
-    trace::{RandomIdGenerator, Sampler, Tracer},
+    trace::{BatchSpanProcessor, RandomIdGenerator, Tracer, TracerProvider},
     Resource,
 };
+use sha2::Sha256;
+use std::any::TypeId;
 use std::collections::HashMap;
-use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{DisplayValue, Field, FieldSet, Value, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
 use tracing_subscriber::{
     fmt::{self, format::FmtSpan},
-    layer::SubscriberExt,
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    reload,
     util::SubscriberInitExt,
-    EnvFilter, Layer,
+    EnvFilter, Layer, Registry,
 };
 
+use crate::domain::{LogFilterError, LogFilterStatus};
+use crate::log_file::{FileLogFormat, Logfmt, LogfmtFields, RotatingFile, RotationSettings};
+use crate::otlp::{otlp_exporter, BatchSettings, OtlpCompression, OtlpProtocol};
+use crate::ports::{HealthProbe, LogFilterControl, ProbeReport, ProbeStatus};
+use crate::sampling::{with_sampling, SamplingConfig};
+
 #[derive(Debug, Clone)]
 pub struct ObservabilityConfig {
     pub service_name: String,
+    pub log_filter: String,
     pub log_format: LogFormat,
     pub file_logging_enabled: bool,
     pub log_file_dir: String,
     pub log_file_name: String,
+    pub log_file_format: FileLogFormat,
+    pub log_rotation: RotationSettings,
     pub otel_enabled: bool,
     pub otel_endpoint: String,
     pub otel_headers: HashMap<String, String>,
+    pub otel_protocol: OtlpProtocol,
+    pub otel_timeout: Duration,
+    pub otel_compression: OtlpCompression,
+    pub otel_batch: BatchSettings,
+    pub sampling: SamplingConfig,
+    pub redaction: RedactionConfig,
+    pub metrics_enabled: bool,
+    /// `/metrics` has no authentication, so it gets a listener of its own,
+    /// local by default, rather than a route next to the API.
+    pub metrics_host: String,
+    pub metrics_port: u16,
+    pub business_metrics_interval: Duration,
+    pub otel_metrics_enabled: bool,
+    pub otel_metrics_interval: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -32,117 +69,583 @@ pub enum LogFormat {
     Pretty,
 }
 
-impl Default for ObservabilityConfig {
-    fn default() -> Self {
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Runs `future` with `id` as the current request id, so outbound calls made
+/// while handling the request can forward it. Tasks spawned from it don't
+/// inherit the id.
+pub async fn with_request_id<F: Future>(id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(id, future).await
+}
+
+/// The id of the request being handled, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(String::clone).ok()
+}
+
+/// What happens to the value of a field listed in [`RedactionConfig`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RedactAction {
+    /// A keyed hash, so the same value can still be correlated across lines.
+    Hash,
+    /// The first character and, for emails, the domain.
+    Mask,
+    /// The field is left out entirely.
+    Drop,
+}
+
+impl RedactAction {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "hash" => Some(Self::Hash),
+            "mask" => Some(Self::Mask),
+            "drop" => Some(Self::Drop),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RedactionConfig {
+    /// Field names (as recorded, e.g. `customer.email`) and their action.
+    pub fields: HashMap<String, RedactAction>,
+    /// Key for [`RedactAction::Hash`]; a random one per process when unset,
+    /// so hashes then only match within one run.
+    pub hash_key: Option<String>,
+}
+
+/// Applies a [`RedactionConfig`] to field values.
+pub struct Redactor {
+    fields: HashMap<String, RedactAction>,
+    hash_key: Vec<u8>,
+}
+
+impl Redactor {
+    pub fn new(config: &RedactionConfig) -> Self {
+        let hash_key = match &config.hash_key {
+            Some(key) => key.as_bytes().to_vec(),
+            None => uuid::Uuid::new_v4().as_bytes().to_vec(),
+        };
         Self {
-            service_name: "hexagonal-rust".to_string(),
-            log_format: LogFormat::Pretty,
-            file_logging_enabled: false,
-            log_file_dir: "./logs".to_string(),
-            log_file_name: "hexagonal-rust.log".to_string(),
-            otel_enabled: false,
-            otel_endpoint: "http://localhost:4317".to_string(),
-            otel_headers: HashMap::new(),
+            fields: config.fields.clone(),
+            hash_key,
+        }
+    }
+
+    fn action(&self, field: &str) -> Option<RedactAction> {
+        self.fields.get(field).copied()
+    }
+
+    /// The value to write for `field`, or `None` when it is dropped.
+    /// Fields that are not configured pass through unchanged.
+    pub fn redact(&self, field: &str, value: &str) -> Option<String> {
+        match self.action(field) {
+            None => Some(value.to_string()),
+            Some(RedactAction::Hash) => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(&self.hash_key)
+                    .expect("HMAC accepts keys of any size");
+                mac.update(value.as_bytes());
+                let digest = hex::encode(mac.finalize().into_bytes());
+                Some(format!("hash:{}", &digest[..16]))
+            }
+            Some(RedactAction::Mask) => Some(mask(value)),
+            Some(RedactAction::Drop) => None,
         }
     }
+
+    fn covers(&self, fields: &FieldSet) -> bool {
+        !self.fields.is_empty() && fields.iter().any(|f| self.fields.contains_key(f.name()))
+    }
+
+    /// Records `values` into `capture`, redacting as it goes, and hands the
+    /// rebuilt value set to `f`. Only called when [`Self::covers`] the fields.
+    fn rebuild<R>(
+        &self,
+        field_set: &'static FieldSet,
+        record: impl FnOnce(&mut dyn Visit),
+        f: impl FnOnce(&tracing::field::ValueSet<'_>) -> R,
+    ) -> R {
+        let mut capture = Capture {
+            redactor: self,
+            values: (0..field_set.len()).map(|_| None).collect(),
+        };
+        record(&mut capture);
+        let values: Vec<Option<&dyn Value>> = capture
+            .values
+            .iter()
+            .map(|v| v.as_ref().map(Captured::as_value))
+            .collect();
+        f(&field_set.value_set_all(&values))
+    }
 }
 
-impl ObservabilityConfig {
-    pub fn from_env() -> Self {
-        let mut config = Self::default();
+fn mask(value: &str) -> String {
+    let first = value.chars().next().map(String::from).unwrap_or_default();
+    match value.split_once('@') {
+        Some((_, domain)) => format!("{}***@{}", first, domain),
+        None => format!("{}***", first),
+    }
+}
+
+/// A field value copied out of an event or span so it can be re-emitted.
+enum Captured {
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    Debug(DisplayValue<String>),
+}
 
-        if let Ok(name) = std::env::var("OTEL_SERVICE_NAME") {
-            config.service_name = name;
+impl Captured {
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            Self::I64(v) => v,
+            Self::U64(v) => v,
+            Self::I128(v) => v,
+            Self::U128(v) => v,
+            Self::F64(v) => v,
+            Self::Bool(v) => v,
+            Self::Str(v) => v,
+            Self::Debug(v) => v,
         }
+    }
+}
 
-        if let Ok(format) = std::env::var("LOG_FORMAT") {
-            config.log_format = match format.to_lowercase().as_str() {
-                "json" => LogFormat::Json,
-                _ => LogFormat::Pretty,
-            };
+struct Capture<'a> {
+    redactor: &'a Redactor,
+    values: Vec<Option<Captured>>,
+}
+
+impl Capture<'_> {
+    fn put(&mut self, field: &Field, value: Captured, text: impl FnOnce() -> String) {
+        let value = if self.redactor.action(field.name()).is_some() {
+            self.redactor
+                .redact(field.name(), &text())
+                .map(Captured::Str)
+        } else {
+            Some(value)
+        };
+        self.values[field.index()] = value;
+    }
+}
+
+impl Visit for Capture<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.put(field, Captured::I64(value), || value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.put(field, Captured::U64(value), || value.to_string());
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        self.put(field, Captured::I128(value), || value.to_string());
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        self.put(field, Captured::U128(value), || value.to_string());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.put(field, Captured::F64(value), || value.to_string());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.put(field, Captured::Bool(value), || value.to_string());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.put(field, Captured::Str(value.to_string()), || {
+            value.to_string()
+        });
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let text = format!("{:?}", value);
+        self.put(
+            field,
+            Captured::Debug(tracing::field::display(text.clone())),
+            || text,
+        );
+    }
+}
+
+/// Wraps an output layer (console, file, OpenTelemetry) so it only ever sees
+/// redacted field values. Events and spans without a configured field are
+/// passed through untouched.
+pub struct Redacted<L> {
+    inner: L,
+    redactor: Arc<Redactor>,
+}
+
+impl<L> Redacted<L> {
+    pub fn new(inner: L, redactor: Arc<Redactor>) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+impl<S, L> Layer<S> for Redacted<L>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    L: Layer<S>,
+{
+    fn on_register_dispatch(&self, subscriber: &tracing::Dispatch) {
+        self.inner.on_register_dispatch(subscriber);
+    }
+
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn register_callsite(
+        &self,
+        metadata: &'static Metadata<'static>,
+    ) -> tracing::subscriber::Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        self.inner.max_level_hint()
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let metadata = attrs.metadata();
+        if !self.redactor.covers(metadata.fields()) {
+            return self.inner.on_new_span(attrs, id, ctx);
         }
+        self.redactor.rebuild(
+            metadata.fields(),
+            |visitor| attrs.record(visitor),
+            |values| {
+                let attrs = if attrs.is_root() {
+                    Attributes::new_root(metadata, values)
+                } else if let Some(parent) = attrs.parent() {
+                    Attributes::child_of(parent.clone(), metadata, values)
+                } else {
+                    Attributes::new(metadata, values)
+                };
+                self.inner.on_new_span(&attrs, id, ctx)
+            },
+        )
+    }
 
-        if let Ok(enabled) = std::env::var("LOG_FILE_ENABLED") {
-            config.file_logging_enabled = enabled.to_lowercase() == "true";
+    fn on_record(&self, span: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(metadata) = ctx.metadata(span) else {
+            return self.inner.on_record(span, values, ctx);
+        };
+        if !self.redactor.covers(metadata.fields()) {
+            return self.inner.on_record(span, values, ctx);
         }
+        self.redactor.rebuild(
+            metadata.fields(),
+            |visitor| values.record(visitor),
+            |rebuilt| self.inner.on_record(span, &Record::new(rebuilt), ctx),
+        )
+    }
 
-        if let Ok(path) = std::env::var("LOG_FILE_PATH") {
-            if let Some(parent) = std::path::Path::new(&path).parent() {
-                config.log_file_dir = parent.to_string_lossy().to_string();
-            }
-            if let Some(name) = std::path::Path::new(&path).file_name() {
-                config.log_file_name = name.to_string_lossy().to_string();
-            }
+    fn on_follows_from(&self, span: &Id, follows: &Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(span, follows, ctx);
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if !self.redactor.covers(metadata.fields()) {
+            return self.inner.on_event(event, ctx);
         }
+        self.redactor.rebuild(
+            metadata.fields(),
+            |visitor| event.record(visitor),
+            |values| {
+                let event = if event.is_contextual() {
+                    Event::new(metadata, values)
+                } else {
+                    Event::new_child_of(event.parent().cloned(), metadata, values)
+                };
+                self.inner.on_event(&event, ctx)
+            },
+        )
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
 
-        if let Ok(enabled) = std::env::var("OTEL_TRACING_ENABLED") {
-            config.otel_enabled = enabled.to_lowercase() == "true";
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(&self, old: &Id, new: &Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+
+    // `Span::context()` and `set_parent` find the OpenTelemetry layer by
+    // downcasting, so that has to reach through the wrapper.
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            Some(self as *const Self as *const ())
+        } else {
+            self.inner.downcast_raw(id)
         }
+    }
+}
+
+/// Export failures newer than this mark the exporter as down.
+const EXPORT_FAILURE_WINDOW_SECS: i64 = 60;
+
+struct ExporterHealth {
+    enabled: bool,
+    protocol: &'static str,
+    init_error: Option<String>,
+    last_error: Option<(String, DateTime<Utc>)>,
+}
+
+/// Written by `init_observability` and by the OpenTelemetry global error
+/// handler, which is where the batch exporter reports failed exports.
+static EXPORTER_HEALTH: Mutex<ExporterHealth> = Mutex::new(ExporterHealth {
+    enabled: false,
+    protocol: "grpc",
+    init_error: None,
+    last_error: None,
+});
+
+fn record_export_error(error: opentelemetry::global::Error) {
+    eprintln!("OpenTelemetry error: {}", error);
+    if let Ok(mut health) = EXPORTER_HEALTH.lock() {
+        health.last_error = Some((error.to_string(), Utc::now()));
+    }
+}
+
+/// Reports the OTLP exporters (traces and metrics push) for the readiness
+/// probe: disabled, failed to start, or failing to export within the last
+/// minute. With `http/protobuf`, a collector rejecting an export counts as a
+/// failure, not just an unreachable one.
+#[derive(Clone)]
+pub struct OtlpExporterProbe;
 
-        if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
-            config.otel_endpoint = endpoint;
+impl HealthProbe for OtlpExporterProbe {
+    async fn probe(&self) -> ProbeReport {
+        let Ok(health) = EXPORTER_HEALTH.lock() else {
+            return ProbeReport::new(ProbeStatus::Down).with_detail("error", "state unavailable");
+        };
+
+        if !health.enabled {
+            return ProbeReport::new(ProbeStatus::Disabled);
         }
 
-        if let Ok(headers_str) = std::env::var("OTEL_EXPORTER_OTLP_HEADERS") {
-            config.otel_headers = parse_otlp_headers(&headers_str);
+        if let Some(error) = &health.init_error {
+            return ProbeReport::new(ProbeStatus::Down)
+                .with_detail("protocol", health.protocol)
+                .with_detail("error", error.clone());
         }
 
-        config
+        let report = match &health.last_error {
+            Some((error, at)) => {
+                let recent = Utc::now().signed_duration_since(*at).num_seconds()
+                    < EXPORT_FAILURE_WINDOW_SECS;
+                let status = if recent {
+                    ProbeStatus::Down
+                } else {
+                    ProbeStatus::Up
+                };
+                ProbeReport::new(status)
+                    .with_detail("last_error", error.clone())
+                    .with_detail("last_error_at", at.to_rfc3339())
+            }
+            None => ProbeReport::new(ProbeStatus::Up),
+        };
+        report.with_detail("protocol", health.protocol)
     }
 }
 
-fn parse_otlp_headers(headers_str: &str) -> HashMap<String, String> {
-    headers_str
-        .split(',')
-        .filter_map(|pair| {
-            let mut parts = pair.splitn(2, '=');
-            match (parts.next(), parts.next()) {
-                (Some(key), Some(value)) => Some((key.trim().to_string(), value.trim().to_string())),
-                _ => None,
+struct LogFilterState {
+    directives: String,
+    revert_at: Option<DateTime<Utc>>,
+    /// Bumped on every change, so a pending revert knows it was superseded.
+    generation: u64,
+}
+
+/// Added to every filter set at runtime, so a change never silences the
+/// `audit` events that record it.
+const AUDIT_DIRECTIVE: &str = "audit=info";
+
+/// Swaps the `EnvFilter` installed by `init_observability` at runtime.
+#[derive(Clone)]
+pub struct LogFilterHandle {
+    reload: reload::Handle<EnvFilter, Registry>,
+    default_directives: String,
+    state: Arc<Mutex<LogFilterState>>,
+}
+
+impl LogFilterHandle {
+    /// Returns the handle and the layer it controls, which goes first in the
+    /// subscriber.
+    pub fn new(directives: &str) -> anyhow::Result<(Self, reload::Layer<EnvFilter, Registry>)> {
+        let (layer, reload) = reload::Layer::new(EnvFilter::try_new(directives)?);
+        let handle = Self {
+            reload,
+            default_directives: directives.to_string(),
+            state: Arc::new(Mutex::new(LogFilterState {
+                directives: directives.to_string(),
+                revert_at: None,
+                generation: 0,
+            })),
+        };
+        Ok((handle, layer))
+    }
+
+    fn default_max_level(&self) -> Option<tracing::level_filters::LevelFilter> {
+        EnvFilter::try_new(&self.default_directives)
+            .ok()
+            .and_then(|filter| filter.max_level_hint())
+    }
+
+    fn revert(&self, generation: u64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.generation != generation {
+            return;
+        }
+        let filter = EnvFilter::try_new(&self.default_directives)
+            .expect("the configured filter was valid at startup");
+        match self.reload.reload(filter) {
+            Ok(()) => {
+                tracing::info!(
+                    target: "audit",
+                    from = %state.directives,
+                    to = %self.default_directives,
+                    "log filter reverted"
+                );
+                state.directives = self.default_directives.clone();
+                state.revert_at = None;
+                state.generation += 1;
             }
-        })
-        .collect()
+            Err(e) => tracing::error!(error = %e, "failed to revert log filter"),
+        }
+    }
+}
+
+impl LogFilterControl for LogFilterHandle {
+    fn status(&self) -> LogFilterStatus {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        LogFilterStatus {
+            directives: state.directives.clone(),
+            default_directives: self.default_directives.clone(),
+            revert_at: state.revert_at,
+        }
+    }
+
+    /// Installs `directives` plus `audit=info`. A filter whose own directives
+    /// enable a more verbose level than the configured one must revert;
+    /// `audit=info` is left out of that comparison.
+    fn set(
+        &self,
+        directives: &str,
+        revert_after: Option<Duration>,
+    ) -> Result<LogFilterStatus, LogFilterError> {
+        let filter =
+            EnvFilter::try_new(directives).map_err(|e| LogFilterError::Invalid(e.to_string()))?;
+        if revert_after.is_none() && filter.max_level_hint() > self.default_max_level() {
+            return Err(LogFilterError::RevertRequired);
+        }
+        let filter = filter.add_directive(AUDIT_DIRECTIVE.parse().expect("valid directive"));
+        let generation = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            self.reload
+                .reload(filter)
+                .map_err(|e| LogFilterError::Reload(e.to_string()))?;
+            state.directives = directives.to_string();
+            state.revert_at = revert_after
+                .and_then(|after| chrono::Duration::from_std(after).ok())
+                .map(|after| Utc::now() + after);
+            state.generation += 1;
+            state.generation
+        };
+
+        if let Some(after) = revert_after {
+            let handle = self.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(after).await;
+                handle.revert(generation);
+            });
+        }
+
+        Ok(self.status())
+    }
 }
 
 pub struct ObservabilityGuard {
     _file_guard: Option<tracing_appender::non_blocking::WorkerGuard>,
+    log_filter: LogFilterHandle,
 }
 
-fn init_tracer(config: &ObservabilityConfig) -> Result<Tracer, opentelemetry::trace::TraceError> {
-    let mut metadata = tonic::metadata::MetadataMap::new();
-
-    for (key, value) in &config.otel_headers {
-        if let (Ok(header_name), Ok(header_value)) = (
-            tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
-            tonic::metadata::MetadataValue::try_from(value.as_str()),
-        ) {
-            metadata.insert(header_name, header_value);
-        }
+impl ObservabilityGuard {
+    /// The handle for the filter installed by `init_observability`.
+    pub fn log_filter(&self) -> LogFilterHandle {
+        self.log_filter.clone()
     }
+}
 
-    let exporter = opentelemetry_otlp::new_exporter()
-        .tonic()
-        .with_endpoint(&config.otel_endpoint)
-        .with_metadata(metadata);
+fn init_tracer(config: &ObservabilityConfig, redactor: &Redactor) -> anyhow::Result<Tracer> {
+    let exporter = SpanExporterBuilder::from(otlp_exporter(config)?).build_span_exporter()?;
 
-    opentelemetry_otlp::new_pipeline()
-        .tracing()
-        .with_exporter(exporter)
-        .with_trace_config(
-            opentelemetry_sdk::trace::Config::default()
-                .with_sampler(Sampler::AlwaysOn)
-                .with_id_generator(RandomIdGenerator::default())
-                .with_resource(Resource::new(vec![
-                    KeyValue::new("service.name", config.service_name.clone()),
-                    KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
-                ])),
-        )
-        .install_batch(runtime::Tokio)
+    let batch = BatchSpanProcessor::builder(exporter, runtime::Tokio)
+        .with_batch_config(config.otel_batch.to_batch_config())
+        .build();
+    let trace_config = opentelemetry_sdk::trace::Config::default()
+        .with_id_generator(RandomIdGenerator::default())
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", config.service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ]));
+    // Span attributes are redacted before the sampler sees them, so the kept
+    // tenants have to be compared in the same form.
+    let sampling = SamplingConfig {
+        keep_tenants: config
+            .sampling
+            .keep_tenants
+            .iter()
+            .filter_map(|tenant| redactor.redact("tenant.id", tenant))
+            .collect(),
+        ..config.sampling.clone()
+    };
+    let provider = with_sampling(&sampling, TracerProvider::builder(), trace_config, batch).build();
+    let tracer = provider.versioned_tracer(
+        "opentelemetry-otlp",
+        Some(env!("CARGO_PKG_VERSION")),
+        None::<&str>,
+        None,
+    );
+    opentelemetry::global::set_tracer_provider(provider);
+
+    Ok(tracer)
 }
 
 pub fn init_observability(config: ObservabilityConfig) -> anyhow::Result<ObservabilityGuard> {
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("hexagonal_rust=debug,tower_http=debug"));
+    let (log_filter, filter_layer) = LogFilterHandle::new(&config.log_filter)?;
+    let redactor = Arc::new(Redactor::new(&config.redaction));
+
+    // W3C `traceparent`/`tracestate` plus `baggage`, for both the inbound
+    // adapter and outbound HTTP calls.
+    opentelemetry::global::set_text_map_propagator(TextMapCompositePropagator::new(vec![
+        Box::new(TraceContextPropagator::new()),
+        Box::new(BaggagePropagator::new()),
+    ]));
 
     let console_layer: Box<dyn Layer<_> + Send + Sync> = if config.log_format == LogFormat::Json {
         Box::new(
@@ -164,48 +667,86 @@ pub fn init_observability(config: ObservabilityConfig) -> anyhow::Result<Observa
         )
     };
 
-    let (file_layer, file_guard): (Option<Box<dyn Layer<_> + Send + Sync>>, _) = if config
-        .file_logging_enabled
-    {
-        let file_appender =
-            RollingFileAppender::new(Rotation::DAILY, &config.log_file_dir, &config.log_file_name);
-        let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+    let (file_layer, file_guard): (Option<Box<dyn Layer<_> + Send + Sync>>, _) =
+        if config.file_logging_enabled {
+            let file = RotatingFile::new(
+                &config.log_file_dir,
+                &config.log_file_name,
+                config.log_rotation.clone(),
+            )
+            .with_context(|| format!("Failed to open log file in {}", config.log_file_dir))?;
+            let (non_blocking, guard) = tracing_appender::non_blocking(file);
 
-        let layer = fmt::layer()
-            .json()
-            .with_writer(non_blocking)
-            .with_span_events(FmtSpan::CLOSE)
-            .with_target(true)
-            .with_level(true)
-            .with_ansi(false);
+            let layer: Box<dyn Layer<_> + Send + Sync> = match config.log_file_format {
+                FileLogFormat::Json => Box::new(
+                    fmt::layer()
+                        .json()
+                        .with_writer(non_blocking)
+                        .with_span_events(FmtSpan::CLOSE)
+                        .with_target(true)
+                        .with_level(true)
+                        .with_ansi(false),
+                ),
+                FileLogFormat::Logfmt => Box::new(
+                    fmt::layer()
+                        .with_span_events(FmtSpan::CLOSE)
+                        .event_format(Logfmt)
+                        .fmt_fields(LogfmtFields)
+                        .with_writer(non_blocking)
+                        .with_ansi(false),
+                ),
+            };
 
-        (Some(Box::new(layer)), Some(guard))
-    } else {
-        (None, None)
-    };
+            (Some(layer), Some(guard))
+        } else {
+            (None, None)
+        };
 
-    let otel_layer: Option<tracing_opentelemetry::OpenTelemetryLayer<_, _>> = if config.otel_enabled
-    {
-        match init_tracer(&config) {
-            Ok(tracer) => {
-                eprintln!("OpenTelemetry tracer initialized successfully for endpoint: {}", config.otel_endpoint);
-                Some(tracing_opentelemetry::layer().with_tracer(tracer))
-            }
-            Err(e) => {
-                eprintln!("Failed to initialize OpenTelemetry tracer: {}", e);
-                eprintln!("  Endpoint: {}", config.otel_endpoint);
-                eprintln!("  Headers: {:?}", config.otel_headers.keys().collect::<Vec<_>>());
-                None
-            }
+    if config.otel_enabled || config.otel_metrics_enabled {
+        if let Ok(mut health) = EXPORTER_HEALTH.lock() {
+            health.enabled = true;
+            health.protocol = config.otel_protocol.as_str();
         }
-    } else {
-        None
-    };
+        if let Err(e) = opentelemetry::global::set_error_handler(record_export_error) {
+            eprintln!("Failed to install OpenTelemetry error handler: {}", e);
+        }
+    }
+
+    let otel_layer: Option<Redacted<tracing_opentelemetry::OpenTelemetryLayer<_, _>>> =
+        if config.otel_enabled {
+            match init_tracer(&config, &redactor) {
+                Ok(tracer) => {
+                    eprintln!(
+                        "OpenTelemetry tracer initialized successfully for endpoint: {} ({})",
+                        config.otel_endpoint,
+                        config.otel_protocol.as_str()
+                    );
+                    Some(Redacted::new(
+                        tracing_opentelemetry::layer().with_tracer(tracer),
+                        redactor.clone(),
+                    ))
+                }
+                Err(e) => {
+                    if let Ok(mut health) = EXPORTER_HEALTH.lock() {
+                        health.init_error = Some(format!("{:#}", e));
+                    }
+                    eprintln!("Failed to initialize OpenTelemetry tracer: {:#}", e);
+                    eprintln!("  Endpoint: {}", config.otel_endpoint);
+                    eprintln!(
+                        "  Headers: {:?}",
+                        config.otel_headers.keys().collect::<Vec<_>>()
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
     let subscriber = tracing_subscriber::registry()
-        .with(env_filter)
-        .with(console_layer)
-        .with(file_layer)
+        .with(filter_layer)
+        .with(Redacted::new(console_layer, redactor.clone()))
+        .with(file_layer.map(|layer| Redacted::new(layer, redactor.clone())))
         .with(otel_layer);
 
     subscriber.init();
@@ -220,9 +761,159 @@ pub fn init_observability(config: ObservabilityConfig) -> anyhow::Result<Observa
 
     Ok(ObservabilityGuard {
         _file_guard: file_guard,
+        log_filter,
     })
 }
 
 pub fn shutdown_tracer() {
     opentelemetry::global::shutdown_tracer_provider();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_redacted_layer_hashes_masks_and_drops_span_and_event_fields() {
+        let config = RedactionConfig {
+            fields: HashMap::from([
+                ("customer.email".to_string(), RedactAction::Mask),
+                ("tenant_id".to_string(), RedactAction::Hash),
+                ("customer_id".to_string(), RedactAction::Drop),
+            ]),
+            hash_key: Some("test-key".to_string()),
+        };
+        let redactor = Arc::new(Redactor::new(&config));
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let layer = fmt::layer()
+            .json()
+            .with_writer(move || writer.clone())
+            .with_current_span(true);
+        let subscriber =
+            tracing_subscriber::registry().with(Redacted::new(layer, redactor.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                "payment_create_customer",
+                customer.email = "jane@example.com",
+                tenant_id = tracing::field::Empty
+            );
+            span.record("tenant_id", "tenant_acme");
+            let _entered = span.enter();
+            tracing::info!(customer_id = "cus_123", attempts = 2, "customer created");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        let hashed = redactor.redact("tenant_id", "tenant_acme").unwrap();
+        assert_eq!(line["span"]["customer.email"], "j***@example.com");
+        assert_eq!(line["span"]["tenant_id"], hashed.as_str());
+        assert!(line["fields"].get("customer_id").is_none(), "{}", output);
+        assert_eq!(line["fields"]["attempts"], 2);
+        assert_eq!(line["fields"]["message"], "customer created");
+        assert!(
+            !output.contains("jane")
+                && !output.contains("tenant_acme")
+                && !output.contains("cus_123")
+        );
+    }
+
+    #[test]
+    fn test_tenant_errors_keep_the_tenant_id_out_of_their_message() {
+        use crate::domain::{
+            ChangeSubscriptionPlanError, CreateSubscriptionError, PlanId, TenantId,
+        };
+
+        let config = RedactionConfig {
+            fields: HashMap::from([("tenant_id".to_string(), RedactAction::Hash)]),
+            hash_key: Some("test-key".to_string()),
+        };
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let layer = fmt::layer().json().with_writer(move || writer.clone());
+        let subscriber = tracing_subscriber::registry()
+            .with(Redacted::new(layer, Arc::new(Redactor::new(&config))));
+
+        let tenant_id = TenantId::new("tenant_acme");
+        let plan_id = PlanId::new("pro");
+        let errors = [
+            CreateSubscriptionError::PlanNotAllowed(tenant_id.clone(), plan_id.clone()).to_string(),
+            CreateSubscriptionError::MissingPaymentMethod(tenant_id.clone()).to_string(),
+            ChangeSubscriptionPlanError::PlanNotAllowed(tenant_id.clone(), plan_id).to_string(),
+            ChangeSubscriptionPlanError::MissingPaymentMethod(tenant_id.clone()).to_string(),
+        ];
+        tracing::subscriber::with_default(subscriber, || {
+            for error in &errors {
+                tracing::warn!(error = %error, tenant_id = %tenant_id, "subscription creation failed");
+            }
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().count(), errors.len());
+        assert!(!output.contains("tenant_acme"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_log_filter_changes_and_reverts_to_the_configured_directives() {
+        let (handle, layer) = LogFilterHandle::new("info").unwrap();
+        let _default = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+        assert!(!tracing::enabled!(tracing::Level::DEBUG));
+
+        assert!(matches!(
+            handle.set("hexagonal_rust=loud", None),
+            Err(LogFilterError::Invalid(_))
+        ));
+        assert!(matches!(
+            handle.set("debug", None),
+            Err(LogFilterError::RevertRequired)
+        ));
+
+        handle.set("audit=off,warn", None).unwrap();
+        assert!(tracing::enabled!(target: "audit", tracing::Level::INFO));
+        assert!(!tracing::enabled!(tracing::Level::INFO));
+
+        let status = handle
+            .set("debug", Some(Duration::from_millis(50)))
+            .unwrap();
+        assert!(status.revert_at.is_some());
+        assert!(tracing::enabled!(tracing::Level::DEBUG));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let status = handle.status();
+        assert_eq!(status.directives, "info");
+        assert!(status.revert_at.is_none());
+        assert!(!tracing::enabled!(tracing::Level::DEBUG));
+
+        // A later change cancels the pending revert.
+        handle
+            .set("debug", Some(Duration::from_millis(50)))
+            .unwrap();
+        handle.set("warn", None).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(handle.status().directives, "warn");
+        // The `audit=info` added to every filter is not held against a
+        // quieter configured level.
+        let (quiet, _layer) = LogFilterHandle::new("warn").unwrap();
+        quiet.set("error", None).unwrap();
+        quiet.set("warn", None).unwrap();
+        assert!(matches!(
+            quiet.set("info", None),
+            Err(LogFilterError::RevertRequired)
+        ));
+    }
+}
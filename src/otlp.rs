@@ -0,0 +1,228 @@
+use async_trait::async_trait;
+use flate2::{write::GzEncoder, Compression as GzipLevel};
+use opentelemetry_http::{Bytes, HttpClient, HttpError, Request, Response};
+use opentelemetry_otlp::{
+    Compression, HttpExporterBuilder, MetricsExporterBuilder, SpanExporterBuilder,
+    TonicExporterBuilder, WithExportConfig,
+};
+use opentelemetry_sdk::trace::BatchConfig;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::observability::ObservabilityConfig;
+
+/// Wire protocol for OTLP exports, as in `OTEL_EXPORTER_OTLP_PROTOCOL`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OtlpProtocol {
+    #[default]
+    Grpc,
+    HttpProtobuf,
+}
+
+impl OtlpProtocol {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "grpc" => Some(Self::Grpc),
+            "http/protobuf" => Some(Self::HttpProtobuf),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Grpc => "grpc",
+            Self::HttpProtobuf => "http/protobuf",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OtlpCompression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl OtlpCompression {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "none" | "" => Some(Self::None),
+            "gzip" => Some(Self::Gzip),
+            _ => None,
+        }
+    }
+}
+
+/// Span batching, as in the `OTEL_BSP_*` variables.
+#[derive(Debug, Clone, Default)]
+pub struct BatchSettings {
+    pub max_queue_size: usize,
+    pub max_export_batch_size: usize,
+    pub scheduled_delay: Duration,
+    pub max_export_timeout: Duration,
+}
+
+impl BatchSettings {
+    pub fn to_batch_config(&self) -> BatchConfig {
+        BatchConfig::default()
+            .with_max_queue_size(self.max_queue_size)
+            .with_max_export_batch_size(self.max_export_batch_size)
+            .with_scheduled_delay(self.scheduled_delay)
+            .with_max_export_timeout(self.max_export_timeout)
+    }
+}
+
+/// An OTLP exporter builder for either protocol, turned into a span or
+/// metrics exporter builder by the caller.
+// Built once at startup, so the variant size difference doesn't matter.
+#[allow(clippy::large_enum_variant)]
+pub enum OtlpExporter {
+    Grpc(TonicExporterBuilder),
+    Http(HttpExporterBuilder),
+}
+
+impl From<OtlpExporter> for SpanExporterBuilder {
+    fn from(exporter: OtlpExporter) -> Self {
+        match exporter {
+            OtlpExporter::Grpc(builder) => builder.into(),
+            OtlpExporter::Http(builder) => builder.into(),
+        }
+    }
+}
+
+impl From<OtlpExporter> for MetricsExporterBuilder {
+    fn from(exporter: OtlpExporter) -> Self {
+        match exporter {
+            OtlpExporter::Grpc(builder) => builder.into(),
+            OtlpExporter::Http(builder) => builder.into(),
+        }
+    }
+}
+
+/// Builds the exporter for `config.otel_protocol` with the endpoint, headers,
+/// timeout and compression shared by traces and metrics.
+pub fn otlp_exporter(config: &ObservabilityConfig) -> anyhow::Result<OtlpExporter> {
+    match config.otel_protocol {
+        OtlpProtocol::Grpc => {
+            let mut metadata = tonic::metadata::MetadataMap::new();
+            for (key, value) in &config.otel_headers {
+                if let (Ok(header_name), Ok(header_value)) = (
+                    tonic::metadata::MetadataKey::from_bytes(key.as_bytes()),
+                    tonic::metadata::MetadataValue::try_from(value.as_str()),
+                ) {
+                    metadata.insert(header_name, header_value);
+                }
+            }
+
+            let mut builder = opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&config.otel_endpoint)
+                .with_timeout(config.otel_timeout)
+                .with_metadata(metadata);
+            if config.otel_compression == OtlpCompression::Gzip {
+                builder = builder.with_compression(Compression::Gzip);
+            }
+            Ok(OtlpExporter::Grpc(builder))
+        }
+        OtlpProtocol::HttpProtobuf => {
+            let transport = HttpTransport {
+                client: reqwest::Client::builder()
+                    .timeout(config.otel_timeout)
+                    .build()?,
+                gzip: config.otel_compression == OtlpCompression::Gzip,
+            };
+
+            Ok(OtlpExporter::Http(
+                opentelemetry_otlp::new_exporter()
+                    .http()
+                    .with_endpoint(&config.otel_endpoint)
+                    .with_timeout(config.otel_timeout)
+                    .with_headers(config.otel_headers.clone())
+                    .with_http_client(transport),
+            ))
+        }
+    }
+}
+
+/// The HTTP exporter neither applies its timeout nor checks the response
+/// status, so both happen here, and a rejected export reaches the global
+/// error handler and readiness like a failed connection does. Bodies are
+/// gzipped here too, as the exporter has no compression option.
+#[derive(Debug)]
+struct HttpTransport {
+    client: reqwest::Client,
+    gzip: bool,
+}
+
+#[async_trait]
+impl HttpClient for HttpTransport {
+    async fn send(&self, request: Request<Vec<u8>>) -> Result<Response<Bytes>, HttpError> {
+        let (parts, body) = request.into_parts();
+        let mut builder = self
+            .client
+            .request(parts.method, parts.uri.to_string())
+            .headers(parts.headers);
+        builder = if self.gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), GzipLevel::default());
+            encoder.write_all(&body)?;
+            builder
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(encoder.finish()?)
+        } else {
+            builder.body(body)
+        };
+
+        let response = builder.send().await?.error_for_status()?;
+        let status = response.status();
+        let body = response.bytes().await?;
+
+        Ok(Response::builder().status(status).body(body)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, http::StatusCode, routing::post, Router};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn test_http_transport_gzips_bodies_and_rejects_error_statuses() {
+        async fn collector(headers: HeaderMap, body: axum::body::Bytes) -> StatusCode {
+            let mut decoded = String::new();
+            let gzipped = headers.get("content-encoding").is_some_and(|v| v == "gzip");
+            if gzipped
+                && GzDecoder::new(&body[..])
+                    .read_to_string(&mut decoded)
+                    .is_ok()
+                && decoded == "spans"
+            {
+                return StatusCode::OK;
+            }
+            StatusCode::BAD_REQUEST
+        }
+        let app = Router::new().route("/v1/traces", post(collector)).route(
+            "/v1/metrics",
+            post(|| async { StatusCode::SERVICE_UNAVAILABLE }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let transport = HttpTransport {
+            client: reqwest::Client::new(),
+            gzip: true,
+        };
+        let request = |path: &str| {
+            Request::post(format!("http://{addr}{path}"))
+                .body(b"spans".to_vec())
+                .unwrap()
+        };
+
+        let accepted = transport.send(request("/v1/traces")).await.unwrap();
+        assert_eq!(accepted.status(), 200);
+        let rejected = transport.send(request("/v1/metrics")).await.unwrap_err();
+        assert!(rejected.to_string().contains("503"));
+    }
+}
@@ -0,0 +1,12 @@
+use crate::domain::ApiKey;
+
+pub trait ApiKeyRepository: Send + Sync {
+    async fn insert_api_key(&self, api_key: &ApiKey) -> Result<(), anyhow::Error>;
+
+    /// Inserts the key, or replaces the key stored under the same id and
+    /// reinstates it if it was revoked.
+    async fn upsert_api_key(&self, api_key: &ApiKey) -> Result<(), anyhow::Error>;
+
+    /// Looks up a key that has not been revoked.
+    async fn find_active_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, anyhow::Error>;
+}
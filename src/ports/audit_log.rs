@@ -0,0 +1,12 @@
+use crate::domain::{AuditEntry, SubscriptionId};
+
+/// Read side of the audit log. Entries are written by the
+/// [`SubscriptionRepository`](super::SubscriptionRepository) together with
+/// the change they describe.
+pub trait AuditLog: Send + Sync {
+    /// All entries for the subscription, oldest first.
+    async fn list_for_subscription(
+        &self,
+        subscription_id: &SubscriptionId,
+    ) -> Result<Vec<AuditEntry>, anyhow::Error>;
+}
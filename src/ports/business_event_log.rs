@@ -0,0 +1,30 @@
+use crate::domain::{BusinessEvent, RecordedBusinessEvent, RequestContext};
+
+pub trait BusinessEventLog: Send + Sync {
+    /// Records `event`, attributed to the request in `context`, and returns
+    /// once it is durably stored.
+    async fn append(
+        &self,
+        event: &BusinessEvent,
+        context: &RequestContext,
+    ) -> Result<(), anyhow::Error>;
+}
+
+/// Business events that are recorded but not yet copied to the chained log.
+pub trait BusinessEventOutbox: Send + Sync {
+    /// Returns up to `limit` events, oldest first.
+    async fn pending(&self, limit: u32) -> Result<Vec<RecordedBusinessEvent>, anyhow::Error>;
+
+    /// Removes every event up to and including `outbox_id`.
+    async fn acknowledge(&self, outbox_id: i64) -> Result<(), anyhow::Error>;
+}
+
+/// The hash-chained log the outbox is copied to.
+pub trait BusinessEventChain: Send + Sync {
+    /// Outbox id of the last event appended, if any carried one. Events up to
+    /// it are already in the chain even when the outbox still holds them.
+    async fn last_outbox_id(&self) -> Result<Option<i64>, anyhow::Error>;
+
+    /// Appends `event` and returns once it is durably stored.
+    async fn append(&self, event: &RecordedBusinessEvent) -> Result<(), anyhow::Error>;
+}
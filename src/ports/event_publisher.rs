@@ -0,0 +1,5 @@
+use crate::domain::SubscriptionEvent;
+
+pub trait SubscriptionEventPublisher: Send + Sync {
+    async fn publish(&self, event: &SubscriptionEvent) -> Result<(), anyhow::Error>;
+}
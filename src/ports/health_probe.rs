@@ -0,0 +1,46 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeStatus {
+    Up,
+    Down,
+    /// The dependency is not configured, so there is nothing to check.
+    Disabled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeReport {
+    pub status: ProbeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", flatten)]
+    pub details: BTreeMap<String, serde_json::Value>,
+}
+
+impl ProbeReport {
+    pub fn new(status: ProbeStatus) -> Self {
+        Self {
+            status,
+            latency_ms: None,
+            details: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_latency(mut self, latency: std::time::Duration) -> Self {
+        self.latency_ms = Some(latency.as_millis() as u64);
+        self
+    }
+
+    pub fn with_detail(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.details.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+/// Checks one dependency for the readiness probe. Probes never fail; a
+/// broken dependency is reported as `Down` with the reason in the details.
+pub trait HealthProbe: Send + Sync {
+    async fn probe(&self) -> ProbeReport;
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status_code: u16,
+    /// Response headers as sent, e.g. `Content-Type` and `Location`.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IdempotencyRecord {
+    pub fingerprint: String,
+    /// `None` while the original request is still being processed.
+    pub response: Option<StoredResponse>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub enum IdempotencyClaim {
+    /// The key was free, expired, or held by a request whose lease has passed,
+    /// and is now reserved for this request until `locked_until`.
+    Acquired,
+    /// The key is already taken by an earlier request.
+    Existing(IdempotencyRecord),
+}
+
+pub trait IdempotencyStore: Send + Sync {
+    /// Atomically reserves `key` within `scope` unless a live record exists.
+    /// A reservation that was neither completed nor released by `locked_until`
+    /// (the request was dropped or the process died) is taken over.
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<IdempotencyClaim, anyhow::Error>;
+
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        response: &StoredResponse,
+    ) -> Result<(), anyhow::Error>;
+
+    /// Drops a reservation so that the request can be retried with the same key.
+    async fn release(&self, scope: &str, key: &str) -> Result<(), anyhow::Error>;
+
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<u64, anyhow::Error>;
+}
@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use crate::domain::{LogFilterError, LogFilterStatus};
+
+/// The filter deciding which diagnostic logs are written, changeable at runtime.
+pub trait LogFilterControl: Send + Sync {
+    fn status(&self) -> LogFilterStatus;
+
+    /// Installs `directives`. With `revert_after`, the configured filter comes
+    /// back once it elapses, unless the filter was changed again meanwhile.
+    fn set(
+        &self,
+        directives: &str,
+        revert_after: Option<Duration>,
+    ) -> Result<LogFilterStatus, LogFilterError>;
+}
@@ -1,7 +1,33 @@
+pub mod api_key_repository;
+pub mod audit_log;
 pub mod billing_profile_repository;
+pub mod business_event_log;
+pub mod event_publisher;
+pub mod health_probe;
+pub mod idempotency_store;
+pub mod log_filter_control;
 pub mod plan_repository;
 pub mod subscription_repository;
+pub mod subscription_stats;
+pub mod tenant_plan_lookup;
+pub mod token_verifier;
+pub mod webhook_repository;
+pub mod webhook_sender;
 
+pub use api_key_repository::ApiKeyRepository;
+pub use audit_log::AuditLog;
 pub use billing_profile_repository::BillingProfileRepository;
+pub use business_event_log::{BusinessEventChain, BusinessEventLog, BusinessEventOutbox};
+pub use event_publisher::SubscriptionEventPublisher;
+pub use health_probe::{HealthProbe, ProbeReport, ProbeStatus};
+pub use idempotency_store::{
+    IdempotencyClaim, IdempotencyRecord, IdempotencyStore, StoredResponse,
+};
+pub use log_filter_control::LogFilterControl;
 pub use plan_repository::PlanRepository;
 pub use subscription_repository::SubscriptionRepository;
+pub use subscription_stats::SubscriptionStats;
+pub use tenant_plan_lookup::TenantPlanLookup;
+pub use token_verifier::{TokenClaims, TokenVerifier};
+pub use webhook_repository::WebhookRepository;
+pub use webhook_sender::WebhookSender;
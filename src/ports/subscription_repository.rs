@@ -1,9 +1,42 @@
-use crate::domain::{PlanId, Subscription, TenantId};
+use chrono::{DateTime, Utc};
 
+use crate::domain::{PlanId, RequestContext, Subscription, SubscriptionId, TenantId};
+
+/// Changes are stored together with their audit entry, attributed to the
+/// actor and request in `context`: either both are written or neither is.
 pub trait SubscriptionRepository: Send + Sync {
     async fn insert_subscription(
         &self,
         tenant_id: &TenantId,
         plan_id: &PlanId,
+        context: &RequestContext,
     ) -> Result<Subscription, anyhow::Error>;
+
+    async fn find_subscription(
+        &self,
+        subscription_id: &SubscriptionId,
+    ) -> Result<Option<Subscription>, anyhow::Error>;
+
+    /// Moves `subscription` to `plan_id`, provided the stored subscription is
+    /// still on `subscription.plan_id`. Returns `None`, changing nothing, when
+    /// another request changed the plan in the meantime.
+    async fn change_plan(
+        &self,
+        subscription: &Subscription,
+        plan_id: &PlanId,
+        context: &RequestContext,
+    ) -> Result<Option<Subscription>, anyhow::Error>;
+
+    /// Lists a tenant's subscriptions, either as they are now or, with `as_of`,
+    /// as they were at that instant. Only called with `as_of` when
+    /// [`supports_point_in_time`](Self::supports_point_in_time) is true.
+    async fn list_subscriptions(
+        &self,
+        tenant_id: &TenantId,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Subscription>, anyhow::Error>;
+
+    fn supports_point_in_time(&self) -> bool {
+        false
+    }
 }
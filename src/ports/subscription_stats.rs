@@ -0,0 +1,12 @@
+use std::future::Future;
+
+use crate::domain::PlanId;
+
+/// Aggregates over all tenants for business metrics. Polled from a spawned
+/// task, so like [`TenantPlanLookup`](super::TenantPlanLookup) the future is
+/// required to be `Send`.
+pub trait SubscriptionStats: Send + Sync {
+    fn active_subscriptions_by_plan(
+        &self,
+    ) -> impl Future<Output = Result<Vec<(PlanId, u64)>, anyhow::Error>> + Send;
+}
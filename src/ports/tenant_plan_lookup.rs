@@ -0,0 +1,13 @@
+use std::future::Future;
+
+use crate::domain::{PlanId, TenantId};
+
+/// Used by the HTTP rate limiter to find a tenant's plan tier. It runs inside
+/// a tower `Service`, whose futures must be `Send`, so unlike the other ports
+/// the bound is spelled out.
+pub trait TenantPlanLookup: Send + Sync {
+    fn plans_for_tenant(
+        &self,
+        tenant_id: &TenantId,
+    ) -> impl Future<Output = Result<Vec<PlanId>, anyhow::Error>> + Send;
+}
@@ -0,0 +1,14 @@
+/// Claims of a verified bearer token, already mapped from the issuer's
+/// claim names.
+#[derive(Debug, Clone)]
+pub struct TokenClaims {
+    pub subject: String,
+    pub tenant_id: Option<String>,
+    pub roles: Vec<String>,
+}
+
+pub trait TokenVerifier: Send + Sync {
+    /// Returns `None` for tokens that fail verification (bad signature,
+    /// unknown key, expired, wrong issuer or audience).
+    async fn verify(&self, token: &str) -> Result<Option<TokenClaims>, anyhow::Error>;
+}
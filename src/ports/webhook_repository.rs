@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    TenantId, WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryId, WebhookDeliveryStatus,
+    WebhookEndpoint, WebhookEndpointId,
+};
+
+pub trait WebhookRepository: Send + Sync {
+    async fn insert_endpoint(&self, endpoint: &WebhookEndpoint) -> Result<(), anyhow::Error>;
+
+    async fn find_endpoint(
+        &self,
+        tenant_id: &TenantId,
+        endpoint_id: &WebhookEndpointId,
+    ) -> Result<Option<WebhookEndpoint>, anyhow::Error>;
+
+    async fn list_endpoints(
+        &self,
+        tenant_id: &TenantId,
+    ) -> Result<Vec<WebhookEndpoint>, anyhow::Error>;
+
+    async fn insert_delivery(&self, delivery: &WebhookDelivery) -> Result<(), anyhow::Error>;
+
+    async fn find_delivery(
+        &self,
+        tenant_id: &TenantId,
+        delivery_id: &WebhookDeliveryId,
+    ) -> Result<Option<WebhookDelivery>, anyhow::Error>;
+
+    async fn list_deliveries(
+        &self,
+        tenant_id: &TenantId,
+        endpoint_id: &WebhookEndpointId,
+    ) -> Result<Vec<WebhookDelivery>, anyhow::Error>;
+
+    async fn list_attempts(
+        &self,
+        delivery_id: &WebhookDeliveryId,
+    ) -> Result<Vec<WebhookDeliveryAttempt>, anyhow::Error>;
+
+    /// Claims pending deliveries whose `next_attempt_at` is at or before `now`
+    /// and that no other worker holds, until `locked_until`, and returns them
+    /// oldest first. A claim ends when the attempt is recorded.
+    async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<(WebhookDelivery, WebhookEndpoint)>, anyhow::Error>;
+
+    /// Appends `attempt` to the delivery log, moves the delivery to `status`
+    /// and releases its claim.
+    async fn record_attempt(
+        &self,
+        attempt: &WebhookDeliveryAttempt,
+        status: WebhookDeliveryStatus,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<(), anyhow::Error>;
+
+    /// Puts a delivery back into the pending queue so the worker picks it up
+    /// again at `at`. Returns false, changing nothing, while a worker's claim
+    /// on the delivery has not run out.
+    async fn schedule_delivery(
+        &self,
+        delivery_id: &WebhookDeliveryId,
+        at: DateTime<Utc>,
+    ) -> Result<bool, anyhow::Error>;
+}
@@ -0,0 +1,10 @@
+use crate::domain::{WebhookDelivery, WebhookEndpoint};
+
+pub trait WebhookSender: Send + Sync {
+    /// Sends the delivery payload to the endpoint and returns the HTTP status code.
+    async fn send(
+        &self,
+        endpoint: &WebhookEndpoint,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, anyhow::Error>;
+}
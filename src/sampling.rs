@@ -0,0 +1,441 @@
+use opentelemetry::trace::{
+    Link, SamplingDecision, SamplingResult, Span as _, SpanContext, SpanId, SpanKind, Status,
+    TraceContextExt, TraceId, TraceResult,
+};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::trace::{
+    Builder as TracerProviderBuilder, Config, Sampler, ShouldSample, Span, SpanProcessor,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Spans whose `url.path` starts with this are health checks.
+const HEALTH_CHECK_PATH: &str = "/health";
+/// Span attribute the keep-list of tenants is matched against.
+const TENANT_ATTRIBUTE: &str = "tenant.id";
+/// Traces with more spans than this keep only the first ones if retained.
+const MAX_PENDING_SPANS_PER_TRACE: usize = 512;
+
+/// How traces are sampled, named after the `OTEL_TRACES_SAMPLER` values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SamplingStrategy {
+    #[default]
+    AlwaysOn,
+    AlwaysOff,
+    TraceIdRatio(f64),
+    ParentBasedAlwaysOn,
+    ParentBasedAlwaysOff,
+    ParentBasedTraceIdRatio(f64),
+    /// Parent-based ratio sampling, plus the rules in [`SamplingConfig`].
+    RuleBased(f64),
+}
+
+impl SamplingStrategy {
+    pub const NAMES: &'static str = "always_on, always_off, traceidratio, parentbased_always_on, \
+         parentbased_always_off, parentbased_traceidratio or rule_based";
+
+    pub fn parse(name: &str, ratio: f64) -> Option<Self> {
+        Some(match name.trim().to_lowercase().as_str() {
+            "always_on" => Self::AlwaysOn,
+            "always_off" => Self::AlwaysOff,
+            "traceidratio" => Self::TraceIdRatio(ratio),
+            "parentbased_always_on" => Self::ParentBasedAlwaysOn,
+            "parentbased_always_off" => Self::ParentBasedAlwaysOff,
+            "parentbased_traceidratio" => Self::ParentBasedTraceIdRatio(ratio),
+            "rule_based" => Self::RuleBased(ratio),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SamplingConfig {
+    pub strategy: SamplingStrategy,
+    /// Traces for these tenants are always kept (rule-based only).
+    pub keep_tenants: HashSet<String>,
+    /// Health-check traces kept per minute (rule-based only).
+    pub health_checks_per_minute: u32,
+}
+
+/// Registers `exporter`, the processor that exports sampled spans, and the
+/// configured sampler. Rule-based sampling wraps the exporter in a
+/// processor that makes the deferred decisions.
+pub fn with_sampling<P: SpanProcessor + 'static>(
+    config: &SamplingConfig,
+    provider: TracerProviderBuilder,
+    trace_config: Config,
+    exporter: P,
+) -> TracerProviderBuilder {
+    let ratio_sampler = |ratio: f64| Sampler::TraceIdRatioBased(ratio.clamp(0.0, 1.0));
+    let sampler = match config.strategy {
+        SamplingStrategy::AlwaysOn => Sampler::AlwaysOn,
+        SamplingStrategy::AlwaysOff => Sampler::AlwaysOff,
+        SamplingStrategy::TraceIdRatio(ratio) => ratio_sampler(ratio),
+        SamplingStrategy::ParentBasedAlwaysOn => Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+        SamplingStrategy::ParentBasedAlwaysOff => {
+            Sampler::ParentBased(Box::new(Sampler::AlwaysOff))
+        }
+        SamplingStrategy::ParentBasedTraceIdRatio(ratio) => {
+            Sampler::ParentBased(Box::new(ratio_sampler(ratio)))
+        }
+        SamplingStrategy::RuleBased(ratio) => {
+            let pending = Arc::new(Mutex::new(HashMap::new()));
+            let sampler = RuleBasedSampler {
+                fallback: Sampler::ParentBased(Box::new(ratio_sampler(ratio))),
+                pending: pending.clone(),
+                health_checks: Arc::new(Mutex::new(RateWindow::new(
+                    config.health_checks_per_minute,
+                ))),
+            };
+            let processor = DeferredDecisionProcessor {
+                inner: exporter,
+                pending,
+                keep_tenants: config.keep_tenants.clone(),
+            };
+            return provider
+                .with_span_processor(processor)
+                .with_config(trace_config.with_sampler(sampler));
+        }
+    };
+
+    provider
+        .with_span_processor(exporter)
+        .with_config(trace_config.with_sampler(sampler))
+}
+
+#[derive(Debug, Default)]
+struct PendingTrace {
+    /// Local roots sampled as record-only that have not ended yet.
+    open_roots: usize,
+    root_ids: HashSet<SpanId>,
+    spans: Vec<SpanData>,
+}
+
+type PendingTraces = Arc<Mutex<HashMap<TraceId, PendingTrace>>>;
+
+#[derive(Debug)]
+struct RateWindow {
+    limit: u32,
+    started: Instant,
+    used: u32,
+}
+
+impl RateWindow {
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            started: Instant::now(),
+            used: 0,
+        }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        if self.started.elapsed() >= Duration::from_secs(60) {
+            self.started = Instant::now();
+            self.used = 0;
+        }
+        if self.used < self.limit {
+            self.used += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Head sampling for the rule-based strategy. Health checks are capped per
+/// minute. Anything the parent-based ratio sampler would drop is recorded
+/// rather than dropped, because whether it errors or which tenant it
+/// belongs to is only known when it ends; [`DeferredDecisionProcessor`]
+/// decides then.
+#[derive(Debug, Clone)]
+struct RuleBasedSampler {
+    fallback: Sampler,
+    pending: PendingTraces,
+    health_checks: Arc<Mutex<RateWindow>>,
+}
+
+impl ShouldSample for RuleBasedSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let decision = |decision| SamplingResult {
+            decision,
+            attributes: Vec::new(),
+            trace_state: parent_context
+                .map(|cx| cx.span().span_context().trace_state().clone())
+                .unwrap_or_default(),
+        };
+
+        // Children follow their local root.
+        let local_parent = parent_context
+            .filter(|cx| cx.has_active_span())
+            .map(|cx| cx.span().span_context().clone())
+            .filter(|parent| parent.is_valid() && !parent.is_remote());
+        if let Some(parent) = local_parent {
+            return if parent.is_sampled() {
+                decision(SamplingDecision::RecordAndSample)
+            } else if self
+                .pending
+                .lock()
+                .is_ok_and(|pending| pending.contains_key(&trace_id))
+            {
+                decision(SamplingDecision::RecordOnly)
+            } else {
+                decision(SamplingDecision::Drop)
+            };
+        }
+
+        let is_health_check = attributes.iter().any(|kv| {
+            kv.key.as_str() == "url.path" && kv.value.as_str().starts_with(HEALTH_CHECK_PATH)
+        });
+        if is_health_check {
+            let allowed = self
+                .health_checks
+                .lock()
+                .is_ok_and(|mut window| window.try_acquire());
+            return decision(if allowed {
+                SamplingDecision::RecordAndSample
+            } else {
+                SamplingDecision::Drop
+            });
+        }
+
+        let result = self.fallback.should_sample(
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        );
+        if result.decision == SamplingDecision::RecordAndSample {
+            return result;
+        }
+
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.entry(trace_id).or_default().open_roots += 1;
+        }
+        SamplingResult {
+            decision: SamplingDecision::RecordOnly,
+            ..result
+        }
+    }
+}
+
+/// Holds the record-only spans of a trace until its local root ends, then
+/// exports them if any span has an error status (set by
+/// `ApiError::into_response` for 5xx responses only) or belongs to a tenant
+/// on the keep list.
+#[derive(Debug)]
+struct DeferredDecisionProcessor<P> {
+    inner: P,
+    pending: PendingTraces,
+    keep_tenants: HashSet<String>,
+}
+
+impl<P> DeferredDecisionProcessor<P> {
+    fn should_keep(&self, spans: &[SpanData]) -> bool {
+        spans.iter().any(|span| {
+            matches!(span.status, Status::Error { .. })
+                || span.attributes.iter().any(|kv| {
+                    kv.key.as_str() == TENANT_ATTRIBUTE
+                        && self.keep_tenants.contains(kv.value.as_str().as_ref())
+                })
+        })
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for DeferredDecisionProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+
+        let span_context = span.span_context();
+        let is_local_root = !cx.has_active_span() || cx.span().span_context().is_remote();
+        if span_context.is_sampled() || !is_local_root {
+            return;
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            if let Some(trace) = pending.get_mut(&span_context.trace_id()) {
+                trace.root_ids.insert(span_context.span_id());
+            }
+        }
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if span.span_context.is_sampled() {
+            return self.inner.on_end(span);
+        }
+
+        let trace_id = span.span_context.trace_id();
+        let finished = {
+            let Ok(mut pending) = self.pending.lock() else {
+                return;
+            };
+            let Some(trace) = pending.get_mut(&trace_id) else {
+                return;
+            };
+            let is_root = trace.root_ids.remove(&span.span_context.span_id());
+            if trace.spans.len() < MAX_PENDING_SPANS_PER_TRACE {
+                trace.spans.push(span);
+            }
+            if is_root {
+                trace.open_roots = trace.open_roots.saturating_sub(1);
+            }
+            if is_root && trace.open_roots == 0 {
+                pending.remove(&trace_id)
+            } else {
+                None
+            }
+        };
+
+        let Some(trace) = finished else {
+            return;
+        };
+        if !self.should_keep(&trace.spans) {
+            return;
+        }
+        for mut span in trace.spans {
+            let context = &span.span_context;
+            span.span_context = SpanContext::new(
+                context.trace_id(),
+                context.span_id(),
+                context.trace_flags().with_sampled(true),
+                context.is_remote(),
+                context.trace_state().clone(),
+            );
+            self.inner.on_end(span);
+        }
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        self.inner.shutdown()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{Tracer as _, TracerProvider as _};
+    use opentelemetry_sdk::trace::TracerProvider;
+
+    #[derive(Debug, Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<String>>>);
+
+    impl SpanProcessor for Captured {
+        fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+        fn on_end(&self, span: SpanData) {
+            if span.span_context.is_sampled() {
+                self.0.lock().unwrap().push(span.name.to_string());
+            }
+        }
+
+        fn force_flush(&self) -> TraceResult<()> {
+            Ok(())
+        }
+
+        fn shutdown(&mut self) -> TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Rule-based sampling with a ratio of 0, so only the rules keep traces.
+    fn provider(captured: &Captured) -> TracerProvider {
+        let config = SamplingConfig {
+            strategy: SamplingStrategy::RuleBased(0.0),
+            keep_tenants: HashSet::from(["tenant_vip".to_string()]),
+            health_checks_per_minute: 1,
+        };
+        with_sampling(
+            &config,
+            TracerProvider::builder(),
+            Config::default(),
+            captured.clone(),
+        )
+        .build()
+    }
+
+    /// A root span with one child. `status_code` is recorded on the child,
+    /// with an error status for 5xx as `ApiError` does, and `tenant` on the
+    /// root, as the request span carries the tenant.
+    fn request(
+        tracer: &opentelemetry_sdk::trace::Tracer,
+        name: &'static str,
+        path: &'static str,
+        status_code: i64,
+        tenant: Option<&'static str>,
+    ) {
+        let root = tracer
+            .span_builder(name)
+            .with_attributes(vec![KeyValue::new("url.path", path)])
+            .start(tracer);
+        let cx = Context::new().with_span(root);
+
+        let mut child = tracer.start_with_context(format!("{}_child", name), &cx);
+        child.set_attribute(KeyValue::new("http.response.status_code", status_code));
+        if status_code >= 500 {
+            child.set_status(Status::error("boom"));
+        }
+        child.end();
+
+        if let Some(tenant) = tenant {
+            cx.span()
+                .set_attribute(KeyValue::new(TENANT_ATTRIBUTE, tenant));
+        }
+        cx.span().end();
+    }
+
+    #[test]
+    fn test_rule_based_sampling_keeps_errors_tenants_and_limited_health_checks() {
+        let captured = Captured::default();
+        let provider = provider(&captured);
+        let tracer = provider.tracer("test");
+
+        request(&tracer, "ok", "/api/v2/subscriptions", 201, None);
+        request(&tracer, "rate_limited", "/api/v2/subscriptions", 429, None);
+        request(&tracer, "not_found", "/api/v2/subscriptions/x", 404, None);
+        request(&tracer, "failed", "/api/v2/subscriptions", 500, None);
+        request(
+            &tracer,
+            "vip",
+            "/api/v2/subscriptions",
+            201,
+            Some("tenant_vip"),
+        );
+        request(
+            &tracer,
+            "other",
+            "/api/v2/subscriptions",
+            201,
+            Some("tenant_acme"),
+        );
+        request(&tracer, "health_1", "/health/ready", 200, None);
+        request(&tracer, "health_2", "/health/ready", 200, None);
+
+        let kept = captured.0.lock().unwrap().clone();
+        assert_eq!(
+            kept,
+            vec![
+                "failed_child",
+                "failed",
+                "vip_child",
+                "vip",
+                "health_1_child",
+                "health_1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use tracing::{instrument, warn};
+use uuid::Uuid;
+
+use crate::domain::{
+    ApiKey, AuthenticationError, IssueApiKeyError, IssueApiKeyRequest, Permission, Principal,
+    RequestContext, Role, TenantId,
+};
+use crate::ports::{ApiKeyRepository, TokenVerifier};
+
+use super::authorization::authorize;
+
+const API_KEY_PREFIX: &str = "lc_";
+
+/// API keys carry 244 bits of randomness, so a plain SHA-256 is enough to keep
+/// them safe at rest; a slow password hash would only add request latency.
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+pub struct AuthService<K, T>
+where
+    K: ApiKeyRepository,
+    T: TokenVerifier,
+{
+    api_keys: K,
+    tokens: Option<T>,
+}
+
+impl<K, T> AuthService<K, T>
+where
+    K: ApiKeyRepository,
+    T: TokenVerifier,
+{
+    /// `tokens` is `None` when bearer token authentication is not configured;
+    /// bearer tokens are then rejected like any other invalid credential.
+    pub fn new(api_keys: K, tokens: Option<T>) -> Self {
+        Self { api_keys, tokens }
+    }
+
+    #[instrument(name = "authenticate_api_key", skip(self, key))]
+    pub async fn authenticate_api_key(&self, key: &str) -> Result<Principal, AuthenticationError> {
+        let api_key = self
+            .api_keys
+            .find_active_by_hash(&hash_api_key(key))
+            .await
+            .map_err(AuthenticationError::Unexpected)?;
+
+        let Some(api_key) = api_key else {
+            let error = AuthenticationError::InvalidCredentials;
+            warn!(error = %error, "api key authentication failed");
+            return Err(error);
+        };
+
+        Ok(Principal::new(
+            format!("api_key:{}", api_key.id),
+            api_key.tenant_id,
+            vec![api_key.role],
+        ))
+    }
+
+    /// Maps a verified token to a principal. Unknown role names are ignored;
+    /// a token without any known role, or a tenant-scoped role without a
+    /// tenant claim, is rejected.
+    #[instrument(name = "authenticate_bearer_token", skip(self, token))]
+    pub async fn authenticate_bearer_token(
+        &self,
+        token: &str,
+    ) -> Result<Principal, AuthenticationError> {
+        let Some(tokens) = &self.tokens else {
+            let error = AuthenticationError::InvalidCredentials;
+            warn!(error = %error, "bearer token presented but jwt authentication is disabled");
+            return Err(error);
+        };
+
+        let claims = tokens
+            .verify(token)
+            .await
+            .map_err(AuthenticationError::Unexpected)?;
+
+        let Some(claims) = claims else {
+            return Err(AuthenticationError::InvalidCredentials);
+        };
+
+        let roles: Vec<Role> = claims.roles.iter().filter_map(|r| Role::parse(r)).collect();
+        let tenant_id = match claims.tenant_id.map(TenantId::parse).transpose() {
+            Ok(tenant_id) => tenant_id,
+            Err(reason) => {
+                let error = AuthenticationError::InvalidCredentials;
+                warn!(
+                    error = %error,
+                    subject = %claims.subject,
+                    reason = %reason,
+                    "bearer token carries a malformed tenant claim"
+                );
+                return Err(error);
+            }
+        };
+
+        let unbound_tenant_role = tenant_id.is_none() && roles.iter().any(Role::is_tenant_scoped);
+
+        if roles.is_empty() || unbound_tenant_role {
+            let error = AuthenticationError::InvalidCredentials;
+            warn!(
+                error = %error,
+                subject = %claims.subject,
+                roles = ?claims.roles,
+                "bearer token carries no usable role or tenant"
+            );
+            return Err(error);
+        }
+
+        Ok(Principal::new(
+            format!("jwt:{}", claims.subject),
+            tenant_id,
+            roles,
+        ))
+    }
+
+    /// Returns the stored key together with its plaintext, which is not kept
+    /// anywhere and cannot be recovered later.
+    #[instrument(
+        name = "issue_api_key",
+        skip(self, request, context),
+        fields(
+            tenant_id = ?request.tenant_id,
+            role = request.role.as_str(),
+            actor = %context.actor
+        )
+    )]
+    pub async fn issue_api_key(
+        &self,
+        request: &IssueApiKeyRequest,
+        context: &RequestContext,
+    ) -> Result<(ApiKey, String), IssueApiKeyError> {
+        authorize(&context.principal, Permission::ManageApiKeys, None)?;
+
+        let bound_correctly = request.role.is_tenant_scoped() == request.tenant_id.is_some();
+
+        if !bound_correctly {
+            let error = IssueApiKeyError::InvalidTenantBinding;
+            warn!(error = %error, "api key issuance failed");
+            return Err(error);
+        }
+
+        let plaintext = format!(
+            "{}{}{}",
+            API_KEY_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+
+        let api_key = ApiKey {
+            id: Uuid::new_v4().to_string(),
+            key_hash: hash_api_key(&plaintext),
+            tenant_id: request.tenant_id.clone(),
+            role: request.role,
+            created_at: Utc::now(),
+            revoked_at: None,
+        };
+
+        self.api_keys
+            .insert_api_key(&api_key)
+            .await
+            .map_err(IssueApiKeyError::Unexpected)?;
+
+        Ok((api_key, plaintext))
+    }
+
+    /// Registers an operator-provided admin key (e.g. from the environment) so
+    /// that the first tenant keys can be issued. There is one bootstrap key: a
+    /// different key replaces the previous one, which stops working.
+    #[instrument(name = "register_bootstrap_admin_key", skip(self, key))]
+    pub async fn register_bootstrap_admin_key(&self, key: &str) -> Result<(), anyhow::Error> {
+        let api_key = ApiKey {
+            id: "bootstrap-admin".to_string(),
+            key_hash: hash_api_key(key),
+            tenant_id: None,
+            role: Role::PlatformAdmin,
+            created_at: Utc::now(),
+            revoked_at: None,
+        };
+
+        self.api_keys.upsert_api_key(&api_key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::TokenClaims;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct MockApiKeyRepository {
+        keys: Arc<Mutex<Vec<ApiKey>>>,
+    }
+
+    impl ApiKeyRepository for MockApiKeyRepository {
+        async fn insert_api_key(&self, api_key: &ApiKey) -> Result<(), anyhow::Error> {
+            self.keys.lock().unwrap().push(api_key.clone());
+            Ok(())
+        }
+
+        async fn upsert_api_key(&self, api_key: &ApiKey) -> Result<(), anyhow::Error> {
+            let mut keys = self.keys.lock().unwrap();
+            keys.retain(|k| k.id != api_key.id);
+            keys.push(api_key.clone());
+            Ok(())
+        }
+
+        async fn find_active_by_hash(
+            &self,
+            key_hash: &str,
+        ) -> Result<Option<ApiKey>, anyhow::Error> {
+            Ok(self
+                .keys
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|k| k.key_hash == key_hash && k.revoked_at.is_none())
+                .cloned())
+        }
+    }
+
+    struct MockTokenVerifier {
+        claims: Option<TokenClaims>,
+    }
+
+    impl TokenVerifier for MockTokenVerifier {
+        async fn verify(&self, _token: &str) -> Result<Option<TokenClaims>, anyhow::Error> {
+            Ok(self.claims.clone())
+        }
+    }
+
+    fn admin() -> RequestContext {
+        RequestContext::new(
+            Principal::new("support@ledgercloud.test", None, vec![Role::PlatformAdmin]),
+            None,
+        )
+    }
+
+    fn service() -> AuthService<MockApiKeyRepository, MockTokenVerifier> {
+        AuthService::new(MockApiKeyRepository::default(), None)
+    }
+
+    fn token_service(
+        tenant_id: Option<&str>,
+        roles: &[&str],
+    ) -> AuthService<MockApiKeyRepository, MockTokenVerifier> {
+        AuthService::new(
+            MockApiKeyRepository::default(),
+            Some(MockTokenVerifier {
+                claims: Some(TokenClaims {
+                    subject: "user_42".to_string(),
+                    tenant_id: tenant_id.map(str::to_string),
+                    roles: roles.iter().map(|r| r.to_string()).collect(),
+                }),
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_issued_key_authenticates_as_tenant() {
+        let service = service();
+
+        let (api_key, plaintext) = service
+            .issue_api_key(
+                &IssueApiKeyRequest {
+                    tenant_id: Some(TenantId::new("tenant_with_payment")),
+                    role: Role::TenantAdmin,
+                },
+                &admin(),
+            )
+            .await
+            .unwrap();
+
+        assert_ne!(api_key.key_hash, plaintext);
+
+        let principal = service.authenticate_api_key(&plaintext).await.unwrap();
+        assert_eq!(principal.subject, format!("api_key:{}", api_key.id));
+        assert!(principal.can_access_tenant(&TenantId::new("tenant_with_payment")));
+        assert!(!principal.can_access_tenant(&TenantId::new("tenant_no_payment")));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_is_rejected() {
+        let service = service();
+
+        let result = service.authenticate_api_key("lc_unknown").await;
+
+        assert!(matches!(
+            result,
+            Err(AuthenticationError::InvalidCredentials)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tenant_key_requires_tenant() {
+        let service = service();
+
+        let result = service
+            .issue_api_key(
+                &IssueApiKeyRequest {
+                    tenant_id: None,
+                    role: Role::TenantAdmin,
+                },
+                &admin(),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(IssueApiKeyError::InvalidTenantBinding)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tenant_admin_cannot_issue_keys() {
+        let context = RequestContext::new(
+            Principal::new(
+                "api_key:tenant",
+                Some(TenantId::new("tenant_with_payment")),
+                vec![Role::TenantAdmin],
+            ),
+            None,
+        );
+
+        let result = service()
+            .issue_api_key(
+                &IssueApiKeyRequest {
+                    tenant_id: Some(TenantId::new("tenant_with_payment")),
+                    role: Role::TenantAdmin,
+                },
+                &context,
+            )
+            .await;
+
+        assert!(matches!(result, Err(IssueApiKeyError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token_claims_become_principal() {
+        let principal = token_service(Some("tenant_with_payment"), &["tenant_admin", "unknown"])
+            .authenticate_bearer_token("token")
+            .await
+            .unwrap();
+
+        assert_eq!(principal.subject, "jwt:user_42");
+        assert_eq!(principal.roles, vec![Role::TenantAdmin]);
+        assert!(principal.can_access_tenant(&TenantId::new("tenant_with_payment")));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token_without_tenant_is_rejected() {
+        let result = token_service(None, &["tenant_viewer"])
+            .authenticate_bearer_token("token")
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AuthenticationError::InvalidCredentials)
+        ));
+    }
+}
@@ -0,0 +1,24 @@
+use tracing::warn;
+
+use crate::domain::{AccessDenied, Permission, Principal, TenantId};
+
+/// Checks a permission and records denials under the `audit` target, so they
+/// can be routed to the same place as other security-relevant events.
+pub fn authorize(
+    principal: &Principal,
+    permission: Permission,
+    tenant_id: Option<&TenantId>,
+) -> Result<(), AccessDenied> {
+    principal
+        .authorize(permission, tenant_id)
+        .inspect_err(|denied| {
+            warn!(
+                target: "audit",
+                subject = %denied.subject,
+                permission = permission.as_str(),
+                tenant_id = tenant_id.map(|t| t.as_ref()),
+                roles = ?principal.roles,
+                "access denied"
+            );
+        })
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, instrument};
+
+use crate::ports::{BusinessEventChain, BusinessEventOutbox};
+
+/// Copies business events from the outbox, where they commit with the change
+/// they describe, to the chained log. An event is only removed from the
+/// outbox after it is appended, and one the log already holds is skipped, so
+/// each event is appended exactly once even across a crash in between.
+pub struct BusinessEventRelay<O, C>
+where
+    O: BusinessEventOutbox,
+    C: BusinessEventChain,
+{
+    outbox: O,
+    chain: C,
+    batch_size: u32,
+}
+
+impl<O, C> BusinessEventRelay<O, C>
+where
+    O: BusinessEventOutbox,
+    C: BusinessEventChain,
+{
+    pub fn new(outbox: O, chain: C) -> Self {
+        Self {
+            outbox,
+            chain,
+            batch_size: 100,
+        }
+    }
+
+    /// Polls until `shutdown` is cancelled, then drains the outbox once more
+    /// so events of the last requests are not left for the next start.
+    pub async fn run(&self, poll_interval: Duration, shutdown: CancellationToken) {
+        info!(
+            poll_interval_ms = poll_interval.as_millis() as u64,
+            "business event relay started"
+        );
+
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            self.drain().await;
+        }
+        self.drain().await;
+
+        info!("business event relay stopped");
+    }
+
+    /// Relays batches until the outbox is empty or a batch fails.
+    async fn drain(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(count) if count == self.batch_size as usize => {}
+                Ok(_) => break,
+                Err(e) => {
+                    error!(error = %e, "business event relay failed");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Copies one batch of pending events and returns how many were pending.
+    #[instrument(name = "relay_business_events", skip(self))]
+    pub async fn run_once(&self) -> Result<usize, anyhow::Error> {
+        let pending = self.outbox.pending(self.batch_size).await?;
+        let Some(last) = pending.last().map(|event| event.outbox_id) else {
+            return Ok(0);
+        };
+
+        let appended = self.chain.last_outbox_id().await?;
+        for event in &pending {
+            if appended.is_some_and(|appended| event.outbox_id <= appended) {
+                continue;
+            }
+            self.chain.append(event).await?;
+        }
+        self.outbox.acknowledge(last).await?;
+
+        Ok(pending.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::sync::{Arc, Mutex};
+
+    use crate::domain::{PaymentEvent, PlanId, RecordedBusinessEvent, TenantId};
+
+    fn recorded(outbox_id: i64) -> RecordedBusinessEvent {
+        RecordedBusinessEvent {
+            outbox_id,
+            event: PaymentEvent::checked(&TenantId::new("tenant_1"), &PlanId::new("pro"), true)
+                .into(),
+            request_id: None,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    #[derive(Default)]
+    struct MockOutbox {
+        events: Mutex<Vec<RecordedBusinessEvent>>,
+    }
+
+    impl BusinessEventOutbox for MockOutbox {
+        async fn pending(&self, limit: u32) -> Result<Vec<RecordedBusinessEvent>, anyhow::Error> {
+            let events = self.events.lock().unwrap();
+            Ok(events.iter().take(limit as usize).cloned().collect())
+        }
+
+        async fn acknowledge(&self, outbox_id: i64) -> Result<(), anyhow::Error> {
+            self.events
+                .lock()
+                .unwrap()
+                .retain(|event| event.outbox_id > outbox_id);
+            Ok(())
+        }
+    }
+
+    #[derive(Default, Clone)]
+    struct MockChain {
+        appended: Arc<Mutex<Vec<i64>>>,
+        fail_on: Option<i64>,
+    }
+
+    impl BusinessEventChain for MockChain {
+        async fn last_outbox_id(&self) -> Result<Option<i64>, anyhow::Error> {
+            Ok(self.appended.lock().unwrap().last().copied())
+        }
+
+        async fn append(&self, event: &RecordedBusinessEvent) -> Result<(), anyhow::Error> {
+            if self.fail_on == Some(event.outbox_id) {
+                anyhow::bail!("disk full");
+            }
+            self.appended.lock().unwrap().push(event.outbox_id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_are_appended_once_across_a_failed_batch() {
+        let outbox = MockOutbox::default();
+        outbox
+            .events
+            .lock()
+            .unwrap()
+            .extend([recorded(1), recorded(2), recorded(3)]);
+        let chain = MockChain {
+            fail_on: Some(3),
+            ..Default::default()
+        };
+        let appended = chain.appended.clone();
+
+        let mut relay = BusinessEventRelay::new(outbox, chain);
+        assert!(relay.run_once().await.is_err());
+        assert_eq!(*appended.lock().unwrap(), vec![1, 2]);
+        assert_eq!(relay.outbox.events.lock().unwrap().len(), 3);
+
+        relay.chain.fail_on = None;
+        assert_eq!(relay.run_once().await.unwrap(), 3);
+        assert_eq!(*appended.lock().unwrap(), vec![1, 2, 3]);
+        assert!(relay.outbox.events.lock().unwrap().is_empty());
+        assert_eq!(relay.run_once().await.unwrap(), 0);
+    }
+}
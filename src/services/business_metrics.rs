@@ -0,0 +1,53 @@
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, instrument, warn};
+
+use crate::metrics;
+use crate::ports::SubscriptionStats;
+
+/// Refreshes the business gauges on an interval. The queries run here rather
+/// than in the gauge callback so a slow database never stalls a scrape.
+pub struct BusinessMetricsCollector<S>
+where
+    S: SubscriptionStats,
+{
+    stats: S,
+}
+
+impl<S> BusinessMetricsCollector<S>
+where
+    S: SubscriptionStats,
+{
+    pub fn new(stats: S) -> Self {
+        Self { stats }
+    }
+
+    #[instrument(name = "refresh_business_metrics", skip(self))]
+    pub async fn refresh(&self) -> Result<(), anyhow::Error> {
+        let counts = self.stats.active_subscriptions_by_plan().await?;
+
+        metrics::set_active_subscriptions(
+            counts
+                .into_iter()
+                .map(|(plan_id, count)| (plan_id.to_string(), count))
+                .collect(),
+        );
+
+        Ok(())
+    }
+
+    pub async fn run(&self, interval: Duration, shutdown: CancellationToken) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            if let Err(e) = self.refresh().await {
+                warn!(error = %e, "failed to refresh business metrics");
+            }
+        }
+
+        info!("business metrics collector stopped");
+    }
+}
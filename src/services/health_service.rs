@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, instrument, warn};
+
+use crate::ports::{HealthProbe, ProbeReport, ProbeStatus};
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub critical: bool,
+    pub report: ProbeReport,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub draining: bool,
+    pub checks: BTreeMap<&'static str, CheckResult>,
+}
+
+/// Runs the readiness checks. The database and its migrations are critical:
+/// without them no request can succeed. The payment provider and the OTLP
+/// exporter are reported but do not take the instance out of rotation.
+///
+/// Once shutdown begins the instance reports not ready regardless of the
+/// checks, so load balancers stop routing to it before connections drain.
+pub struct HealthService<D, M, P, T>
+where
+    D: HealthProbe,
+    M: HealthProbe,
+    P: HealthProbe,
+    T: HealthProbe,
+{
+    database: D,
+    migrations: M,
+    payment_provider: Option<P>,
+    telemetry: T,
+    draining: AtomicBool,
+}
+
+impl<D, M, P, T> HealthService<D, M, P, T>
+where
+    D: HealthProbe,
+    M: HealthProbe,
+    P: HealthProbe,
+    T: HealthProbe,
+{
+    pub fn new(database: D, migrations: M, payment_provider: Option<P>, telemetry: T) -> Self {
+        Self {
+            database,
+            migrations,
+            payment_provider,
+            telemetry,
+            draining: AtomicBool::new(false),
+        }
+    }
+
+    pub fn begin_draining(&self) {
+        if !self.draining.swap(true, Ordering::SeqCst) {
+            info!("readiness now failing, instance is draining");
+        }
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    #[instrument(name = "readiness", skip(self), fields(ready))]
+    pub async fn readiness(&self) -> ReadinessReport {
+        let payment_provider = async {
+            match &self.payment_provider {
+                Some(probe) => probe.probe().await,
+                None => ProbeReport::new(ProbeStatus::Disabled),
+            }
+        };
+
+        let (database, migrations, payment_provider, telemetry) = tokio::join!(
+            self.database.probe(),
+            self.migrations.probe(),
+            payment_provider,
+            self.telemetry.probe(),
+        );
+
+        let checks: BTreeMap<_, _> = [
+            ("database", true, database),
+            ("migrations", true, migrations),
+            ("payment_provider", false, payment_provider),
+            ("otlp_exporter", false, telemetry),
+        ]
+        .into_iter()
+        .map(|(name, critical, report)| (name, CheckResult { critical, report }))
+        .collect();
+
+        let failed: Vec<&str> = checks
+            .iter()
+            .filter(|(_, check)| check.critical && check.report.status == ProbeStatus::Down)
+            .map(|(name, _)| *name)
+            .collect();
+        let draining = self.is_draining();
+        let ready = failed.is_empty() && !draining;
+
+        tracing::Span::current().record("ready", ready);
+        if !failed.is_empty() {
+            warn!(failed = ?failed, "readiness check failed");
+        }
+
+        ReadinessReport {
+            ready,
+            draining,
+            checks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedProbe(ProbeStatus);
+
+    impl HealthProbe for FixedProbe {
+        async fn probe(&self) -> ProbeReport {
+            ProbeReport::new(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_critical_checks_affect_readiness() {
+        let degraded = HealthService::new(
+            FixedProbe(ProbeStatus::Up),
+            FixedProbe(ProbeStatus::Up),
+            Some(FixedProbe(ProbeStatus::Down)),
+            FixedProbe(ProbeStatus::Down),
+        );
+        assert!(degraded.readiness().await.ready);
+
+        let broken = HealthService::new(
+            FixedProbe(ProbeStatus::Down),
+            FixedProbe(ProbeStatus::Up),
+            None::<FixedProbe>,
+            FixedProbe(ProbeStatus::Disabled),
+        );
+        let report = broken.readiness().await;
+        assert!(!report.ready);
+        assert_eq!(
+            report.checks["payment_provider"].report.status,
+            ProbeStatus::Disabled
+        );
+    }
+
+    #[tokio::test]
+    async fn test_draining_fails_readiness_with_healthy_checks() {
+        let service = HealthService::new(
+            FixedProbe(ProbeStatus::Up),
+            FixedProbe(ProbeStatus::Up),
+            None::<FixedProbe>,
+            FixedProbe(ProbeStatus::Up),
+        );
+        assert!(service.readiness().await.ready);
+
+        service.begin_draining();
+
+        let report = service.readiness().await;
+        assert!(!report.ready);
+        assert!(report.draining);
+        assert_eq!(report.checks["database"].report.status, ProbeStatus::Up);
+    }
+}
@@ -0,0 +1,127 @@
+use std::time::Duration;
+use tracing::{info, instrument};
+
+use crate::domain::{LogFilterError, LogFilterStatus, Permission, RequestContext};
+use crate::ports::LogFilterControl;
+
+use super::authorization::authorize;
+
+pub struct LogFilterService<F>
+where
+    F: LogFilterControl,
+{
+    log_filter: F,
+}
+
+impl<F> LogFilterService<F>
+where
+    F: LogFilterControl,
+{
+    pub fn new(log_filter: F) -> Self {
+        Self { log_filter }
+    }
+
+    #[instrument(name = "get_log_filter", skip(self, context), fields(actor = %context.actor))]
+    pub fn log_filter(&self, context: &RequestContext) -> Result<LogFilterStatus, LogFilterError> {
+        authorize(&context.principal, Permission::ManageLogging, None)?;
+
+        Ok(self.log_filter.status())
+    }
+
+    /// Replaces the log filter and records the change under the `audit`
+    /// target; see [`LogFilterControl::set`].
+    #[instrument(
+        name = "set_log_filter",
+        skip(self, context),
+        fields(actor = %context.actor)
+    )]
+    pub fn set_log_filter(
+        &self,
+        directives: &str,
+        revert_after: Option<Duration>,
+        context: &RequestContext,
+    ) -> Result<LogFilterStatus, LogFilterError> {
+        authorize(&context.principal, Permission::ManageLogging, None)?;
+
+        let previous = self.log_filter.status();
+        let status = self.log_filter.set(directives, revert_after)?;
+
+        info!(
+            target: "audit",
+            subject = %context.principal.subject,
+            from = %previous.directives,
+            to = %status.directives,
+            revert_at = ?status.revert_at,
+            "log filter changed"
+        );
+
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::domain::{Principal, Role, TenantId};
+
+    struct MockLogFilter {
+        directives: Mutex<String>,
+    }
+
+    impl LogFilterControl for MockLogFilter {
+        fn status(&self) -> LogFilterStatus {
+            LogFilterStatus {
+                directives: self.directives.lock().unwrap().clone(),
+                default_directives: "info".to_string(),
+                revert_at: None,
+            }
+        }
+
+        fn set(
+            &self,
+            directives: &str,
+            _revert_after: Option<Duration>,
+        ) -> Result<LogFilterStatus, LogFilterError> {
+            *self.directives.lock().unwrap() = directives.to_string();
+            Ok(self.status())
+        }
+    }
+
+    fn service() -> LogFilterService<MockLogFilter> {
+        LogFilterService::new(MockLogFilter {
+            directives: Mutex::new("info".to_string()),
+        })
+    }
+
+    #[test]
+    fn test_only_platform_staff_manage_the_log_filter() {
+        let service = service();
+        let tenant_admin = RequestContext::new(
+            Principal::new(
+                "api_key:tenant",
+                Some(TenantId::new("tenant_with_payment")),
+                vec![Role::TenantAdmin],
+            ),
+            None,
+        );
+        assert!(matches!(
+            service.log_filter(&tenant_admin),
+            Err(LogFilterError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            service.set_log_filter("debug", None, &tenant_admin),
+            Err(LogFilterError::PermissionDenied(_))
+        ));
+        assert_eq!(service.log_filter.status().directives, "info");
+
+        let admin = RequestContext::new(
+            Principal::new("api_key:admin", None, vec![Role::PlatformAdmin]),
+            None,
+        );
+        let status = service.set_log_filter("debug", None, &admin).unwrap();
+        assert_eq!(status.directives, "debug");
+        assert_eq!(service.log_filter(&admin).unwrap().directives, "debug");
+    }
+}
@@ -1,3 +1,18 @@
+pub mod auth_service;
+pub mod authorization;
+pub mod business_event_relay;
+pub mod business_metrics;
+pub mod health_service;
+pub mod log_filter_service;
 pub mod subscription_service;
+pub mod webhook_delivery_worker;
+pub mod webhook_service;
 
+pub use auth_service::AuthService;
+pub use business_event_relay::BusinessEventRelay;
+pub use business_metrics::BusinessMetricsCollector;
+pub use health_service::{HealthService, ReadinessReport};
+pub use log_filter_service::LogFilterService;
 pub use subscription_service::SubscriptionService;
+pub use webhook_delivery_worker::{WebhookDeliveryWorker, WebhookRetryPolicy};
+pub use webhook_service::WebhookService;
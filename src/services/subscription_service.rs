@@ -1,47 +1,83 @@
-use tracing::{instrument, warn};
+use chrono::{DateTime, Utc};
+use tracing::{error, info, instrument, warn};
 
 use crate::domain::{
-    CreateSubscriptionError, CreateSubscriptionRequest, Plan, Subscription, TenantId,
+    AuditEntry, ChangeSubscriptionPlanError, ChangeSubscriptionPlanRequest,
+    CreateSubscriptionError, CreateSubscriptionRequest, ListSubscriptionsError, PaymentEvent,
+    Permission, Plan, RequestContext, Subscription, SubscriptionEvent, SubscriptionHistoryError,
+    SubscriptionId, TenantId,
 };
-use crate::ports::{BillingProfileRepository, PlanRepository, SubscriptionRepository};
+use crate::ports::{
+    AuditLog, BillingProfileRepository, BusinessEventLog, PlanRepository,
+    SubscriptionEventPublisher, SubscriptionRepository,
+};
+
+use super::authorization::authorize;
 
-pub struct SubscriptionService<P, B, S>
+pub struct SubscriptionService<P, B, S, E, A, L>
 where
     P: PlanRepository,
     B: BillingProfileRepository,
     S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
 {
     plans: P,
     billing_profiles: B,
     subscriptions: S,
+    events: E,
+    audit_log: A,
+    business_events: L,
 }
 
-impl<P, B, S> SubscriptionService<P, B, S>
+impl<P, B, S, E, A, L> SubscriptionService<P, B, S, E, A, L>
 where
     P: PlanRepository,
     B: BillingProfileRepository,
     S: SubscriptionRepository,
+    E: SubscriptionEventPublisher,
+    A: AuditLog,
+    L: BusinessEventLog,
 {
-    pub fn new(plans: P, billing_profiles: B, subscriptions: S) -> Self {
+    pub fn new(
+        plans: P,
+        billing_profiles: B,
+        subscriptions: S,
+        events: E,
+        audit_log: A,
+        business_events: L,
+    ) -> Self {
         Self {
             plans,
             billing_profiles,
             subscriptions,
+            events,
+            audit_log,
+            business_events,
         }
     }
 
     #[instrument(
         name = "create_subscription",
-        skip(self),
+        skip(self, request, context),
         fields(
             tenant_id = %request.tenant_id,
-            plan_id = %request.plan_id
+            plan_id = %request.plan_id,
+            actor = %context.actor
         )
     )]
     pub async fn create_subscription(
         &self,
         request: &CreateSubscriptionRequest,
+        context: &RequestContext,
     ) -> Result<Subscription, CreateSubscriptionError> {
+        authorize(
+            &context.principal,
+            Permission::CreateSubscription,
+            Some(&request.tenant_id),
+        )?;
+
         let plan = self
             .plans
             .find_plan(&request.plan_id)
@@ -60,7 +96,7 @@ where
         if !self.tenant_allowed_on_plan(&request.tenant_id, &plan).await {
             let error =
                 CreateSubscriptionError::PlanNotAllowed(request.tenant_id.clone(), plan.id.clone());
-            warn!(error = %error, "subscription creation failed");
+            warn!(error = %error, tenant_id = %request.tenant_id, "subscription creation failed");
             return Err(error);
         }
 
@@ -70,25 +106,226 @@ where
                 .has_active_payment_method(&request.tenant_id)
                 .await
                 .map_err(CreateSubscriptionError::Unexpected)?;
+            self.record_card_check(&request.tenant_id, &plan, has_payment, context)
+                .await
+                .map_err(CreateSubscriptionError::Unexpected)?;
 
             if !has_payment {
                 let error =
                     CreateSubscriptionError::MissingPaymentMethod(request.tenant_id.clone());
-                warn!(error = %error, "subscription creation failed");
+                warn!(error = %error, tenant_id = %request.tenant_id, "subscription creation failed");
                 return Err(error);
             }
         }
 
         let subscription = self
             .subscriptions
-            .insert_subscription(&request.tenant_id, &request.plan_id)
+            .insert_subscription(&request.tenant_id, &request.plan_id, context)
             .await
             .map_err(CreateSubscriptionError::Unexpected)?;
 
+        self.publish(SubscriptionEvent::Created(subscription.clone()))
+            .await;
+
         Ok(subscription)
     }
 
-    #[instrument(skip(self), fields(tenant_id = %_tenant_id, plan_id = %_plan.id))]
+    #[instrument(
+        name = "change_subscription_plan",
+        skip(self, request, context),
+        fields(
+            subscription_id = %request.subscription_id,
+            plan_id = %request.plan_id,
+            actor = %context.actor
+        )
+    )]
+    pub async fn change_subscription_plan(
+        &self,
+        request: &ChangeSubscriptionPlanRequest,
+        context: &RequestContext,
+    ) -> Result<Subscription, ChangeSubscriptionPlanError> {
+        let subscription = self
+            .subscriptions
+            .find_subscription(&request.subscription_id)
+            .await
+            .map_err(ChangeSubscriptionPlanError::Unexpected)?;
+
+        let subscription = match subscription {
+            // Subscriptions of other tenants are reported as missing rather
+            // than forbidden so that their ids cannot be probed.
+            Some(s) if context.principal.can_access_tenant(&s.tenant_id) => s,
+            _ => {
+                let error = ChangeSubscriptionPlanError::SubscriptionNotFound(
+                    request.subscription_id.clone(),
+                );
+                warn!(error = %error, "subscription plan change failed");
+                return Err(error);
+            }
+        };
+
+        authorize(
+            &context.principal,
+            Permission::ChangeSubscriptionPlan,
+            Some(&subscription.tenant_id),
+        )?;
+
+        if subscription.plan_id == request.plan_id {
+            info!("subscription already on requested plan");
+            return Ok(subscription);
+        }
+
+        let plan = self
+            .plans
+            .find_plan(&request.plan_id)
+            .await
+            .map_err(ChangeSubscriptionPlanError::Unexpected)?;
+
+        let plan = match plan {
+            Some(p) => p,
+            None => {
+                let error = ChangeSubscriptionPlanError::PlanNotFound(request.plan_id.clone());
+                warn!(error = %error, "subscription plan change failed");
+                return Err(error);
+            }
+        };
+
+        let tenant_id = &subscription.tenant_id;
+
+        if !self.tenant_allowed_on_plan(tenant_id, &plan).await {
+            let error =
+                ChangeSubscriptionPlanError::PlanNotAllowed(tenant_id.clone(), plan.id.clone());
+            warn!(error = %error, tenant_id = %tenant_id, "subscription plan change failed");
+            return Err(error);
+        }
+
+        if plan.requires_card_on_file {
+            let has_payment = self
+                .billing_profiles
+                .has_active_payment_method(tenant_id)
+                .await
+                .map_err(ChangeSubscriptionPlanError::Unexpected)?;
+            self.record_card_check(tenant_id, &plan, has_payment, context)
+                .await
+                .map_err(ChangeSubscriptionPlanError::Unexpected)?;
+
+            if !has_payment {
+                let error = ChangeSubscriptionPlanError::MissingPaymentMethod(tenant_id.clone());
+                warn!(error = %error, tenant_id = %tenant_id, "subscription plan change failed");
+                return Err(error);
+            }
+        }
+
+        let Some(updated) = self
+            .subscriptions
+            .change_plan(&subscription, &plan.id, context)
+            .await
+            .map_err(ChangeSubscriptionPlanError::Unexpected)?
+        else {
+            let error = ChangeSubscriptionPlanError::ConcurrentChange(subscription.id.clone());
+            warn!(error = %error, "subscription plan change failed");
+            return Err(error);
+        };
+
+        self.publish(SubscriptionEvent::plan_changed(&subscription, &updated))
+            .await;
+
+        Ok(updated)
+    }
+
+    #[instrument(
+        name = "list_subscriptions",
+        skip(self, context),
+        fields(tenant_id = %tenant_id, actor = %context.actor)
+    )]
+    pub async fn list_subscriptions(
+        &self,
+        tenant_id: &TenantId,
+        as_of: Option<DateTime<Utc>>,
+        context: &RequestContext,
+    ) -> Result<Vec<Subscription>, ListSubscriptionsError> {
+        authorize(
+            &context.principal,
+            Permission::ListSubscriptions,
+            Some(tenant_id),
+        )?;
+
+        if as_of.is_some() && !self.subscriptions.supports_point_in_time() {
+            let error = ListSubscriptionsError::PointInTimeUnsupported;
+            warn!(error = %error, "subscription listing failed");
+            return Err(error);
+        }
+
+        self.subscriptions
+            .list_subscriptions(tenant_id, as_of)
+            .await
+            .map_err(ListSubscriptionsError::Unexpected)
+    }
+
+    #[instrument(
+        name = "subscription_history",
+        skip(self, context),
+        fields(subscription_id = %subscription_id, actor = %context.actor)
+    )]
+    pub async fn subscription_history(
+        &self,
+        subscription_id: &SubscriptionId,
+        context: &RequestContext,
+    ) -> Result<Vec<AuditEntry>, SubscriptionHistoryError> {
+        let subscription = self
+            .subscriptions
+            .find_subscription(subscription_id)
+            .await
+            .map_err(SubscriptionHistoryError::Unexpected)?;
+
+        let subscription = match subscription {
+            Some(s) if context.principal.can_access_tenant(&s.tenant_id) => s,
+            _ => {
+                let error = SubscriptionHistoryError::SubscriptionNotFound(subscription_id.clone());
+                warn!(error = %error, "subscription history lookup failed");
+                return Err(error);
+            }
+        };
+
+        authorize(
+            &context.principal,
+            Permission::ViewSubscriptionHistory,
+            Some(&subscription.tenant_id),
+        )?;
+
+        self.audit_log
+            .list_for_subscription(subscription_id)
+            .await
+            .map_err(SubscriptionHistoryError::Unexpected)
+    }
+
+    /// Notifies subscribers, best effort: the subscription is already
+    /// persisted, so a failed notification is only logged. The repository
+    /// records the business event in the change's own transaction.
+    async fn publish(&self, event: SubscriptionEvent) {
+        if let Err(e) = self.events.publish(&event).await {
+            error!(
+                error = %e,
+                event_type = event.event_type(),
+                subscription_id = %event.subscription_id(),
+                "failed to publish subscription event"
+            );
+        }
+    }
+
+    /// Records the card-on-file check before anything changes, so a failure
+    /// here fails the use case with nothing committed.
+    async fn record_card_check(
+        &self,
+        tenant_id: &TenantId,
+        plan: &Plan,
+        has_payment_method: bool,
+        context: &RequestContext,
+    ) -> Result<(), anyhow::Error> {
+        let event = PaymentEvent::checked(tenant_id, &plan.id, has_payment_method);
+        self.business_events.append(&event.into(), context).await
+    }
+
+    #[instrument(skip(self, _tenant_id, _plan), fields(tenant_id = %_tenant_id, plan_id = %_plan.id))]
     async fn tenant_allowed_on_plan(&self, _tenant_id: &TenantId, _plan: &Plan) -> bool {
         true
     }
@@ -97,7 +334,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{PlanId, SubscriptionId};
+    use crate::domain::{AuditAction, BusinessEvent, PlanId, Principal, Role, SubscriptionId};
     use std::sync::{Arc, Mutex};
 
     struct MockPlanRepository {
@@ -145,22 +382,170 @@ mod tests {
         }
     }
 
-    struct MockSubscriptionRepository;
+    #[derive(Default)]
+    struct MockSubscriptionRepository {
+        audit_entries: Arc<Mutex<Vec<AuditEntry>>>,
+    }
+
+    impl MockSubscriptionRepository {
+        fn audit(
+            &self,
+            context: &RequestContext,
+            action: AuditAction,
+            before: Option<&Subscription>,
+            after: &Subscription,
+        ) {
+            let mut entries = self.audit_entries.lock().unwrap();
+            let id = format!("audit_{}", entries.len());
+            entries.push(AuditEntry {
+                id,
+                subscription_id: after.id.clone(),
+                tenant_id: after.tenant_id.clone(),
+                actor: context.actor.clone(),
+                action,
+                before: before.cloned(),
+                after: Some(after.clone()),
+                request_id: context.request_id.clone(),
+                occurred_at: Utc::now(),
+            });
+        }
+    }
 
     impl SubscriptionRepository for MockSubscriptionRepository {
         async fn insert_subscription(
             &self,
             tenant_id: &TenantId,
             plan_id: &PlanId,
+            context: &RequestContext,
         ) -> Result<Subscription, anyhow::Error> {
-            Ok(Subscription::new(
+            let subscription = Subscription::new(
                 SubscriptionId("sub_123".to_string()),
                 tenant_id.clone(),
                 plan_id.clone(),
-            ))
+            );
+            self.audit(
+                context,
+                AuditAction::SubscriptionCreated,
+                None,
+                &subscription,
+            );
+            Ok(subscription)
+        }
+
+        async fn find_subscription(
+            &self,
+            subscription_id: &SubscriptionId,
+        ) -> Result<Option<Subscription>, anyhow::Error> {
+            if subscription_id.as_ref() != "sub_123" {
+                return Ok(None);
+            }
+
+            Ok(Some(Subscription::new(
+                subscription_id.clone(),
+                TenantId("tenant_1".to_string()),
+                PlanId("free".to_string()),
+            )))
+        }
+
+        async fn change_plan(
+            &self,
+            subscription: &Subscription,
+            plan_id: &PlanId,
+            context: &RequestContext,
+        ) -> Result<Option<Subscription>, anyhow::Error> {
+            let mut updated = subscription.clone();
+            updated.plan_id = plan_id.clone();
+            updated.updated_at = chrono::Utc::now();
+            self.audit(
+                context,
+                AuditAction::SubscriptionPlanChanged,
+                Some(subscription),
+                &updated,
+            );
+            Ok(Some(updated))
+        }
+
+        async fn list_subscriptions(
+            &self,
+            _tenant_id: &TenantId,
+            _as_of: Option<DateTime<Utc>>,
+        ) -> Result<Vec<Subscription>, anyhow::Error> {
+            Ok(Vec::new())
         }
     }
 
+    struct MockEventPublisher {
+        events: Arc<Mutex<Vec<SubscriptionEvent>>>,
+    }
+
+    impl MockEventPublisher {
+        fn new() -> Self {
+            Self {
+                events: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    impl SubscriptionEventPublisher for MockEventPublisher {
+        async fn publish(&self, event: &SubscriptionEvent) -> Result<(), anyhow::Error> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockBusinessEventLog {
+        events: Arc<Mutex<Vec<BusinessEvent>>>,
+        fail: bool,
+    }
+
+    impl BusinessEventLog for MockBusinessEventLog {
+        async fn append(
+            &self,
+            event: &BusinessEvent,
+            _context: &RequestContext,
+        ) -> Result<(), anyhow::Error> {
+            if self.fail {
+                anyhow::bail!("disk full");
+            }
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    struct MockAuditLog {
+        entries: Arc<Mutex<Vec<AuditEntry>>>,
+    }
+
+    impl MockAuditLog {
+        fn new() -> Self {
+            Self {
+                entries: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    impl AuditLog for MockAuditLog {
+        async fn list_for_subscription(
+            &self,
+            subscription_id: &SubscriptionId,
+        ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .iter()
+                .filter(|e| &e.subscription_id == subscription_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn context() -> RequestContext {
+        RequestContext::new(
+            Principal::new("support@ledgercloud.test", None, vec![Role::PlatformAdmin]),
+            Some("req_123".to_string()),
+        )
+    }
+
     #[tokio::test]
     async fn test_create_subscription_success() {
         let service = SubscriptionService::new(
@@ -168,7 +553,10 @@ mod tests {
             MockBillingProfileRepository {
                 has_payment_method: true,
             },
-            MockSubscriptionRepository,
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
         );
 
         let request = CreateSubscriptionRequest {
@@ -176,7 +564,7 @@ mod tests {
             plan_id: PlanId("pro".to_string()),
         };
 
-        let result = service.create_subscription(&request).await;
+        let result = service.create_subscription(&request, &context()).await;
         assert!(result.is_ok());
 
         let subscription = result.unwrap();
@@ -191,7 +579,10 @@ mod tests {
             MockBillingProfileRepository {
                 has_payment_method: true,
             },
-            MockSubscriptionRepository,
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
         );
 
         let request = CreateSubscriptionRequest {
@@ -199,7 +590,7 @@ mod tests {
             plan_id: PlanId("nonexistent".to_string()),
         };
 
-        let result = service.create_subscription(&request).await;
+        let result = service.create_subscription(&request, &context()).await;
         assert!(matches!(
             result,
             Err(CreateSubscriptionError::PlanNotFound(_))
@@ -213,7 +604,10 @@ mod tests {
             MockBillingProfileRepository {
                 has_payment_method: false,
             },
-            MockSubscriptionRepository,
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
         );
 
         let request = CreateSubscriptionRequest {
@@ -221,7 +615,7 @@ mod tests {
             plan_id: PlanId("pro".to_string()),
         };
 
-        let result = service.create_subscription(&request).await;
+        let result = service.create_subscription(&request, &context()).await;
         assert!(matches!(
             result,
             Err(CreateSubscriptionError::MissingPaymentMethod(_))
@@ -235,7 +629,10 @@ mod tests {
             MockBillingProfileRepository {
                 has_payment_method: false,
             },
-            MockSubscriptionRepository,
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
         );
 
         let request = CreateSubscriptionRequest {
@@ -243,7 +640,324 @@ mod tests {
             plan_id: PlanId("free".to_string()),
         };
 
-        let result = service.create_subscription(&request).await;
+        let result = service.create_subscription(&request, &context()).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_create_subscription_publishes_created_event() {
+        let publisher = MockEventPublisher::new();
+        let events = publisher.events.clone();
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            MockSubscriptionRepository::default(),
+            publisher,
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
+        );
+
+        let request = CreateSubscriptionRequest {
+            tenant_id: TenantId("tenant_1".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+
+        let subscription = service
+            .create_subscription(&request, &context())
+            .await
+            .unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type(), "subscription.created");
+        assert_eq!(events[0].subscription_id(), &subscription.id);
+    }
+
+    #[tokio::test]
+    async fn test_change_subscription_plan_success() {
+        let publisher = MockEventPublisher::new();
+        let events = publisher.events.clone();
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            MockSubscriptionRepository::default(),
+            publisher,
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
+        );
+
+        let request = ChangeSubscriptionPlanRequest {
+            subscription_id: SubscriptionId("sub_123".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+
+        let subscription = service
+            .change_subscription_plan(&request, &context())
+            .await
+            .unwrap();
+        assert_eq!(subscription.plan_id, request.plan_id);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            SubscriptionEvent::PlanChanged { from_plan_id, .. } if from_plan_id.as_ref() == "free"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_subscription_plan_not_found() {
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
+        );
+
+        let request = ChangeSubscriptionPlanRequest {
+            subscription_id: SubscriptionId("sub_missing".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+
+        let result = service.change_subscription_plan(&request, &context()).await;
+        assert!(matches!(
+            result,
+            Err(ChangeSubscriptionPlanError::SubscriptionNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_subscription_plan_hides_foreign_tenant() {
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
+        );
+
+        let request = ChangeSubscriptionPlanRequest {
+            subscription_id: SubscriptionId("sub_123".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+        let context = RequestContext::new(
+            Principal::new(
+                "api_key:other",
+                Some(TenantId("tenant_2".to_string())),
+                vec![Role::TenantAdmin],
+            ),
+            None,
+        );
+
+        let result = service.change_subscription_plan(&request, &context).await;
+        assert!(matches!(
+            result,
+            Err(ChangeSubscriptionPlanError::SubscriptionNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_subscription_plan_denied_for_viewer() {
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
+        );
+
+        let request = ChangeSubscriptionPlanRequest {
+            subscription_id: SubscriptionId("sub_123".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+        let context = RequestContext::new(
+            Principal::new(
+                "api_key:viewer",
+                Some(TenantId("tenant_1".to_string())),
+                vec![Role::TenantViewer],
+            ),
+            None,
+        );
+
+        let result = service.change_subscription_plan(&request, &context).await;
+        assert!(matches!(
+            result,
+            Err(ChangeSubscriptionPlanError::PermissionDenied(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_subscription_plan_missing_payment_method() {
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: false,
+            },
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
+        );
+
+        let request = ChangeSubscriptionPlanRequest {
+            subscription_id: SubscriptionId("sub_123".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+
+        let result = service.change_subscription_plan(&request, &context()).await;
+        assert!(matches!(
+            result,
+            Err(ChangeSubscriptionPlanError::MissingPaymentMethod(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_subscriptions_point_in_time_unsupported() {
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog::default(),
+        );
+
+        let result = service
+            .list_subscriptions(
+                &TenantId("tenant_1".to_string()),
+                Some(Utc::now()),
+                &context(),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(ListSubscriptionsError::PointInTimeUnsupported)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_subscription_plan_records_audit_entry() {
+        let subscriptions = MockSubscriptionRepository::default();
+        let audit_log = MockAuditLog {
+            entries: subscriptions.audit_entries.clone(),
+        };
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            subscriptions,
+            MockEventPublisher::new(),
+            audit_log,
+            MockBusinessEventLog::default(),
+        );
+
+        let request = ChangeSubscriptionPlanRequest {
+            subscription_id: SubscriptionId("sub_123".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+
+        service
+            .change_subscription_plan(&request, &context())
+            .await
+            .unwrap();
+
+        let history = service
+            .subscription_history(&request.subscription_id, &context())
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].action, AuditAction::SubscriptionPlanChanged);
+        assert_eq!(history[0].actor, "support@ledgercloud.test");
+        assert_eq!(history[0].request_id.as_deref(), Some("req_123"));
+        assert_eq!(
+            history[0].before.as_ref().map(|s| s.plan_id.as_ref()),
+            Some("free")
+        );
+        assert_eq!(
+            history[0].after.as_ref().map(|s| s.plan_id.as_ref()),
+            Some("pro")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_subscription_records_card_check() {
+        let business_events = MockBusinessEventLog::default();
+        let recorded = business_events.events.clone();
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            MockSubscriptionRepository::default(),
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            business_events,
+        );
+
+        let request = CreateSubscriptionRequest {
+            tenant_id: TenantId("tenant_1".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+        service
+            .create_subscription(&request, &context())
+            .await
+            .unwrap();
+
+        // subscription.created is recorded by the repository, with the change.
+        let types: Vec<&str> = recorded
+            .lock()
+            .unwrap()
+            .iter()
+            .map(BusinessEvent::event_type)
+            .collect();
+        assert_eq!(types, vec!["payment.method_verified"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_subscription_stores_nothing_when_card_check_is_not_recorded() {
+        let subscriptions = MockSubscriptionRepository::default();
+        let audit_entries = subscriptions.audit_entries.clone();
+        let service = SubscriptionService::new(
+            MockPlanRepository::new(),
+            MockBillingProfileRepository {
+                has_payment_method: true,
+            },
+            subscriptions,
+            MockEventPublisher::new(),
+            MockAuditLog::new(),
+            MockBusinessEventLog {
+                fail: true,
+                ..Default::default()
+            },
+        );
+
+        let request = CreateSubscriptionRequest {
+            tenant_id: TenantId("tenant_1".to_string()),
+            plan_id: PlanId("pro".to_string()),
+        };
+        let result = service.create_subscription(&request, &context()).await;
+
+        assert!(matches!(
+            result,
+            Err(CreateSubscriptionError::Unexpected(_))
+        ));
+        assert!(audit_entries.lock().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,364 @@
+use chrono::Utc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, instrument, warn};
+
+use crate::domain::{
+    WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryStatus, WebhookEndpoint,
+};
+use crate::ports::{WebhookRepository, WebhookSender};
+
+#[derive(Debug, Clone)]
+pub struct WebhookRetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for WebhookRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl WebhookRetryPolicy {
+    /// Exponential backoff after the given (1-based) attempt, capped at `max_delay`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        self.base_delay
+            .checked_mul(1 << exponent)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+pub struct WebhookDeliveryWorker<W, D>
+where
+    W: WebhookRepository,
+    D: WebhookSender,
+{
+    webhooks: W,
+    sender: D,
+    policy: WebhookRetryPolicy,
+    batch_size: u32,
+    /// How long a batch stays claimed: long enough to send every delivery in
+    /// it one after the other.
+    lease: Duration,
+}
+
+impl<W, D> WebhookDeliveryWorker<W, D>
+where
+    W: WebhookRepository,
+    D: WebhookSender,
+{
+    /// `send_timeout` is the most a single send may take.
+    pub fn new(webhooks: W, sender: D, policy: WebhookRetryPolicy, send_timeout: Duration) -> Self {
+        let batch_size = 50;
+        Self {
+            webhooks,
+            sender,
+            policy,
+            batch_size,
+            // A second on top of each send for recording the attempt.
+            lease: (send_timeout + Duration::from_secs(1)) * batch_size,
+        }
+    }
+
+    /// Polls until `shutdown` is cancelled. A batch that is already running
+    /// is finished first, so no attempt is left half-recorded.
+    pub async fn run(&self, poll_interval: Duration, shutdown: CancellationToken) {
+        info!(
+            poll_interval_ms = poll_interval.as_millis() as u64,
+            "webhook worker started"
+        );
+
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            if let Err(e) = self.run_once().await {
+                error!(error = %e, "webhook delivery batch failed");
+            }
+        }
+
+        info!("webhook worker stopped");
+    }
+
+    /// Claims and attempts every delivery that is currently due and returns
+    /// how many were attempted. One that fails to send or record is logged
+    /// and left to its claim lapsing; the rest of the batch still goes out.
+    pub async fn run_once(&self) -> Result<usize, anyhow::Error> {
+        let now = Utc::now();
+        let due = self
+            .webhooks
+            .claim_due_deliveries(
+                now,
+                now + chrono::Duration::from_std(self.lease)?,
+                self.batch_size,
+            )
+            .await?;
+
+        let count = due.len();
+        for (delivery, endpoint) in due {
+            if let Err(e) = self.deliver(&delivery, &endpoint).await {
+                error!(
+                    error = %e,
+                    delivery_id = %delivery.id,
+                    "webhook delivery attempt could not be recorded"
+                );
+            }
+        }
+
+        Ok(count)
+    }
+
+    #[instrument(
+        name = "deliver_webhook",
+        skip(self, delivery, endpoint),
+        fields(
+            delivery_id = %delivery.id,
+            endpoint_id = %endpoint.id,
+            tenant_id = %delivery.tenant_id,
+            event_type = %delivery.event_type,
+            attempt = delivery.attempts + 1
+        )
+    )]
+    async fn deliver(
+        &self,
+        delivery: &WebhookDelivery,
+        endpoint: &WebhookEndpoint,
+    ) -> Result<(), anyhow::Error> {
+        let attempt_number = delivery.attempts + 1;
+        let started = Instant::now();
+        let result = self.sender.send(endpoint, delivery).await;
+
+        let mut attempt = WebhookDeliveryAttempt {
+            delivery_id: delivery.id.clone(),
+            attempt: attempt_number,
+            response_code: None,
+            error: None,
+            duration_ms: started.elapsed().as_millis() as u64,
+            attempted_at: Utc::now(),
+        };
+
+        let succeeded = match result {
+            Ok(code) => {
+                attempt.response_code = Some(code);
+                (200..300).contains(&code)
+            }
+            Err(e) => {
+                attempt.error = Some(format!("{:#}", e));
+                false
+            }
+        };
+
+        let (status, next_attempt_at) = if succeeded {
+            info!(response_code = ?attempt.response_code, "webhook delivered");
+            (WebhookDeliveryStatus::Succeeded, None)
+        } else if attempt_number >= self.policy.max_attempts {
+            warn!(
+                response_code = ?attempt.response_code,
+                error = ?attempt.error,
+                "webhook delivery failed permanently"
+            );
+            (WebhookDeliveryStatus::Failed, None)
+        } else {
+            let delay = self.policy.backoff(attempt_number);
+            warn!(
+                response_code = ?attempt.response_code,
+                error = ?attempt.error,
+                retry_in_secs = delay.as_secs(),
+                "webhook delivery failed, will retry"
+            );
+            let next = Utc::now() + chrono::Duration::from_std(delay)?;
+            (WebhookDeliveryStatus::Pending, Some(next))
+        };
+
+        self.webhooks
+            .record_attempt(&attempt, status, next_attempt_at)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{TenantId, WebhookDeliveryId, WebhookEndpointId};
+    use chrono::DateTime;
+    use std::sync::Mutex;
+
+    struct MockWebhookRepository {
+        due: Mutex<Vec<(WebhookDelivery, WebhookEndpoint)>>,
+        /// Deliveries whose attempt cannot be recorded.
+        failing: Vec<WebhookDeliveryId>,
+        recorded: Mutex<Vec<WebhookDeliveryId>>,
+    }
+
+    impl WebhookRepository for MockWebhookRepository {
+        async fn insert_endpoint(&self, _: &WebhookEndpoint) -> Result<(), anyhow::Error> {
+            unimplemented!()
+        }
+
+        async fn find_endpoint(
+            &self,
+            _: &TenantId,
+            _: &WebhookEndpointId,
+        ) -> Result<Option<WebhookEndpoint>, anyhow::Error> {
+            unimplemented!()
+        }
+
+        async fn list_endpoints(
+            &self,
+            _: &TenantId,
+        ) -> Result<Vec<WebhookEndpoint>, anyhow::Error> {
+            unimplemented!()
+        }
+
+        async fn insert_delivery(&self, _: &WebhookDelivery) -> Result<(), anyhow::Error> {
+            unimplemented!()
+        }
+
+        async fn find_delivery(
+            &self,
+            _: &TenantId,
+            _: &WebhookDeliveryId,
+        ) -> Result<Option<WebhookDelivery>, anyhow::Error> {
+            unimplemented!()
+        }
+
+        async fn list_deliveries(
+            &self,
+            _: &TenantId,
+            _: &WebhookEndpointId,
+        ) -> Result<Vec<WebhookDelivery>, anyhow::Error> {
+            unimplemented!()
+        }
+
+        async fn list_attempts(
+            &self,
+            _: &WebhookDeliveryId,
+        ) -> Result<Vec<WebhookDeliveryAttempt>, anyhow::Error> {
+            unimplemented!()
+        }
+
+        async fn claim_due_deliveries(
+            &self,
+            _: DateTime<Utc>,
+            _: DateTime<Utc>,
+            _: u32,
+        ) -> Result<Vec<(WebhookDelivery, WebhookEndpoint)>, anyhow::Error> {
+            Ok(std::mem::take(&mut *self.due.lock().unwrap()))
+        }
+
+        async fn record_attempt(
+            &self,
+            attempt: &WebhookDeliveryAttempt,
+            _: WebhookDeliveryStatus,
+            _: Option<DateTime<Utc>>,
+        ) -> Result<(), anyhow::Error> {
+            if self.failing.contains(&attempt.delivery_id) {
+                anyhow::bail!("database is locked");
+            }
+            self.recorded
+                .lock()
+                .unwrap()
+                .push(attempt.delivery_id.clone());
+            Ok(())
+        }
+
+        async fn schedule_delivery(
+            &self,
+            _: &WebhookDeliveryId,
+            _: DateTime<Utc>,
+        ) -> Result<bool, anyhow::Error> {
+            unimplemented!()
+        }
+    }
+
+    struct MockWebhookSender;
+
+    impl WebhookSender for MockWebhookSender {
+        async fn send(
+            &self,
+            _: &WebhookEndpoint,
+            _: &WebhookDelivery,
+        ) -> Result<u16, anyhow::Error> {
+            Ok(200)
+        }
+    }
+
+    fn due(id: &str) -> (WebhookDelivery, WebhookEndpoint) {
+        let now = Utc::now();
+        let endpoint = WebhookEndpoint {
+            id: WebhookEndpointId::new("endpoint_1"),
+            tenant_id: TenantId::new("tenant_1"),
+            url: "https://hooks.example.test".to_string(),
+            secret: "whsec_test".to_string(),
+            created_at: now,
+        };
+        let delivery = WebhookDelivery {
+            id: WebhookDeliveryId::new(id),
+            endpoint_id: endpoint.id.clone(),
+            tenant_id: endpoint.tenant_id.clone(),
+            event_type: "subscription.created".to_string(),
+            payload: "{}".to_string(),
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Some(now),
+            last_response_code: None,
+            created_at: now,
+        };
+        (delivery, endpoint)
+    }
+
+    #[tokio::test]
+    async fn test_one_failed_delivery_does_not_stop_the_batch() {
+        let webhooks = MockWebhookRepository {
+            due: Mutex::new(vec![
+                due("delivery_1"),
+                due("delivery_2"),
+                due("delivery_3"),
+            ]),
+            failing: vec![WebhookDeliveryId::new("delivery_2")],
+            recorded: Mutex::default(),
+        };
+        let worker = WebhookDeliveryWorker::new(
+            webhooks,
+            MockWebhookSender,
+            WebhookRetryPolicy::default(),
+            Duration::from_secs(10),
+        );
+
+        assert_eq!(worker.run_once().await.unwrap(), 3);
+        assert_eq!(
+            *worker.webhooks.recorded.lock().unwrap(),
+            [
+                WebhookDeliveryId::new("delivery_1"),
+                WebhookDeliveryId::new("delivery_3")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles_per_attempt() {
+        let policy = WebhookRetryPolicy::default();
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(30));
+        assert_eq!(policy.backoff(2), Duration::from_secs(60));
+        assert_eq!(policy.backoff(3), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = WebhookRetryPolicy::default();
+
+        assert_eq!(policy.backoff(20), policy.max_delay);
+        assert_eq!(policy.backoff(u32::MAX), policy.max_delay);
+    }
+}
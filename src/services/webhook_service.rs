@@ -0,0 +1,189 @@
+use chrono::Utc;
+use tracing::{instrument, warn};
+use url::{Host, Url};
+use uuid::Uuid;
+
+use crate::domain::{
+    is_internal_address, Permission, RegisterWebhookError, RegisterWebhookRequest, RequestContext,
+    TenantId, WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryError, WebhookDeliveryId,
+    WebhookDeliveryStatus, WebhookEndpoint, WebhookEndpointId,
+};
+use crate::ports::WebhookRepository;
+
+use super::authorization::authorize;
+
+pub struct WebhookService<W>
+where
+    W: WebhookRepository,
+{
+    webhooks: W,
+}
+
+impl<W> WebhookService<W>
+where
+    W: WebhookRepository,
+{
+    pub fn new(webhooks: W) -> Self {
+        Self { webhooks }
+    }
+
+    #[instrument(
+        name = "register_webhook",
+        skip(self, request, context),
+        fields(tenant_id = %request.tenant_id, actor = %context.actor)
+    )]
+    pub async fn register_webhook(
+        &self,
+        request: &RegisterWebhookRequest,
+        context: &RequestContext,
+    ) -> Result<WebhookEndpoint, RegisterWebhookError> {
+        authorize(
+            &context.principal,
+            Permission::ManageWebhooks,
+            Some(&request.tenant_id),
+        )?;
+
+        let url = Url::parse(&request.url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+
+        let Some(url) = url else {
+            let error = RegisterWebhookError::InvalidUrl(request.url.clone());
+            warn!(error = %error, "webhook registration failed");
+            return Err(error);
+        };
+
+        // Host names are resolved, and checked again, when each delivery is
+        // sent; here only what is internal by itself is caught.
+        let internal = match url.host() {
+            Some(Host::Ipv4(ip)) => is_internal_address(ip.into()),
+            Some(Host::Ipv6(ip)) => is_internal_address(ip.into()),
+            Some(Host::Domain(domain)) => {
+                let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+                domain == "localhost" || domain.ends_with(".localhost")
+            }
+            None => true,
+        };
+
+        if internal {
+            let error = RegisterWebhookError::InternalAddress(request.url.clone());
+            warn!(error = %error, "webhook registration failed");
+            return Err(error);
+        }
+
+        let endpoint = WebhookEndpoint {
+            id: WebhookEndpointId::new(Uuid::new_v4().to_string()),
+            tenant_id: request.tenant_id.clone(),
+            url: request.url.clone(),
+            secret: format!("whsec_{}", Uuid::new_v4().simple()),
+            created_at: Utc::now(),
+        };
+
+        self.webhooks
+            .insert_endpoint(&endpoint)
+            .await
+            .map_err(RegisterWebhookError::Unexpected)?;
+
+        Ok(endpoint)
+    }
+
+    #[instrument(
+        name = "list_webhook_deliveries",
+        skip(self, context),
+        fields(tenant_id = %tenant_id, endpoint_id = %endpoint_id, actor = %context.actor)
+    )]
+    pub async fn list_deliveries(
+        &self,
+        tenant_id: &TenantId,
+        endpoint_id: &WebhookEndpointId,
+        context: &RequestContext,
+    ) -> Result<Vec<(WebhookDelivery, Vec<WebhookDeliveryAttempt>)>, WebhookDeliveryError> {
+        authorize(
+            &context.principal,
+            Permission::ViewWebhookDeliveries,
+            Some(tenant_id),
+        )?;
+
+        let endpoint = self
+            .webhooks
+            .find_endpoint(tenant_id, endpoint_id)
+            .await
+            .map_err(WebhookDeliveryError::Unexpected)?;
+
+        if endpoint.is_none() {
+            return Err(WebhookDeliveryError::EndpointNotFound(endpoint_id.clone()));
+        }
+
+        let deliveries = self
+            .webhooks
+            .list_deliveries(tenant_id, endpoint_id)
+            .await
+            .map_err(WebhookDeliveryError::Unexpected)?;
+
+        let mut log = Vec::with_capacity(deliveries.len());
+        for delivery in deliveries {
+            let attempts = self
+                .webhooks
+                .list_attempts(&delivery.id)
+                .await
+                .map_err(WebhookDeliveryError::Unexpected)?;
+            log.push((delivery, attempts));
+        }
+
+        Ok(log)
+    }
+
+    /// Re-queues a delivery regardless of its current status, unless the
+    /// worker is sending it right now. Attempts keep counting from where they
+    /// left off so the log stays complete.
+    #[instrument(
+        name = "replay_webhook_delivery",
+        skip(self, context),
+        fields(tenant_id = %tenant_id, delivery_id = %delivery_id, actor = %context.actor)
+    )]
+    pub async fn replay_delivery(
+        &self,
+        tenant_id: &TenantId,
+        delivery_id: &WebhookDeliveryId,
+        context: &RequestContext,
+    ) -> Result<WebhookDelivery, WebhookDeliveryError> {
+        authorize(
+            &context.principal,
+            Permission::ManageWebhooks,
+            Some(tenant_id),
+        )?;
+
+        let delivery = self
+            .webhooks
+            .find_delivery(tenant_id, delivery_id)
+            .await
+            .map_err(WebhookDeliveryError::Unexpected)?;
+
+        let mut delivery = match delivery {
+            Some(d) => d,
+            None => {
+                let error = WebhookDeliveryError::DeliveryNotFound(delivery_id.clone());
+                warn!(error = %error, "webhook replay failed");
+                return Err(error);
+            }
+        };
+
+        let now = Utc::now();
+        let scheduled = self
+            .webhooks
+            .schedule_delivery(&delivery.id, now)
+            .await
+            .map_err(WebhookDeliveryError::Unexpected)?;
+
+        if !scheduled {
+            let error = WebhookDeliveryError::DeliveryInFlight(delivery_id.clone());
+            warn!(error = %error, "webhook replay failed");
+            return Err(error);
+        }
+
+        delivery.status = WebhookDeliveryStatus::Pending;
+        delivery.next_attempt_at = Some(now);
+
+        Ok(delivery)
+    }
+}